@@ -0,0 +1,388 @@
+use super::*;
+
+/// A 1-bit image compressed with CCITT Group 4 for embedding as an image
+/// XObject.
+///
+/// Group 4 (ITU-T T.6) codes each row relative to the row above it, which is
+/// much more compact than Deflate for scanned text and line art. The image is
+/// written with [`Filter::CcittFaxDecode`] and the matching decode
+/// parameters.
+///
+/// ```
+/// use pdf_writer::{CcittImage, Chunk, Ref};
+///
+/// // A 16x2 bitmap with a black bar in the middle of each row.
+/// let bitmap = [0xF0, 0x0F, 0xF0, 0x0F];
+/// let image = CcittImage::new(&bitmap, 16, 2, false);
+///
+/// let mut chunk = Chunk::new();
+/// image.write(&mut chunk, Ref::new(1));
+/// ```
+pub struct CcittImage {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    black_is_1: bool,
+}
+
+impl CcittImage {
+    /// Encode a 1-bit image.
+    ///
+    /// The rows of the bitmap are packed most significant bit first and each
+    /// starts at a byte boundary. If `black_is_1` is `false`, zero bits are
+    /// black, as for `DeviceGray` samples. Otherwise, one bits are black.
+    ///
+    /// Panics if the bitmap is too short for the dimensions.
+    pub fn new(bitmap: &[u8], width: u32, height: u32, black_is_1: bool) -> Self {
+        let stride = (width as usize).div_ceil(8);
+        assert!(
+            bitmap.len() >= stride * height as usize,
+            "bitmap is too short for the dimensions"
+        );
+
+        let mut writer = BitWriter::default();
+        let mut reference = vec![width as usize; 2];
+        let mut coding = vec![];
+        for row in bitmap.chunks_exact(stride.max(1)).take(height as usize) {
+            changes(row, width as usize, black_is_1, &mut coding);
+            encode_row(&mut writer, &coding, &reference, width as usize);
+            std::mem::swap(&mut coding, &mut reference);
+        }
+
+        // The end-of-facsimile-block code consists of two EOL codes.
+        writer.push(0b0000_0000_0001, 12);
+        writer.push(0b0000_0000_0001, 12);
+        Self { data: writer.finish(), width, height, black_is_1 }
+    }
+
+    /// The width of the image in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the image in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The encoded data.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Write the image XObject.
+    ///
+    /// The image uses the `DeviceGray` color space. If one bits are black, a
+    /// `/Decode` array maps them to black.
+    pub fn write(&self, chunk: &mut Chunk, id: Ref) {
+        let mut image = chunk.image_xobject(id, &self.data);
+        image.filter(Filter::CcittFaxDecode);
+        image
+            .decode_parms()
+            .k(-1)
+            .columns(self.width as i32)
+            .rows(self.height as i32)
+            .black_is_1(self.black_is_1);
+        image.width(self.width as i32);
+        image.height(self.height as i32);
+        image.color_space().device_gray();
+        image.bits_per_component(1);
+        if self.black_is_1 {
+            image.decode([1.0, 0.0]);
+        }
+    }
+}
+
+/// Find the positions at which the color of a row changes, starting with
+/// white. The positions are followed by two entries with the width.
+fn changes(row: &[u8], width: usize, black_is_1: bool, out: &mut Vec<usize>) {
+    out.clear();
+    let mut black = false;
+    for x in 0..width {
+        let bit = row[x / 8] & (0x80 >> (x % 8)) != 0;
+        if (bit == black_is_1) != black {
+            black = !black;
+            out.push(x);
+        }
+    }
+    out.extend([width, width]);
+}
+
+/// Encode a row given its changes and those of the row above it.
+fn encode_row(
+    writer: &mut BitWriter,
+    coding: &[usize],
+    reference: &[usize],
+    width: usize,
+) {
+    let mut a0 = 0;
+    let mut start = true;
+    let mut black = false;
+    while start || a0 < width {
+        // The changes to the right of `a0`. For the first element, a change
+        // at the very first pixel counts.
+        let after = |changes: &[usize]| {
+            changes.partition_point(|&x| if start { false } else { x <= a0 })
+        };
+
+        let a = after(coding);
+        let a1 = coding[a];
+
+        // The first change on the reference row that has the opposite color
+        // of `a0`. Changes to black are at even indices.
+        let mut b = after(reference);
+        if b % 2 != usize::from(black) {
+            b += 1;
+        }
+        let b1 = reference[b.min(reference.len() - 1)];
+        let b2 = reference[(b + 1).min(reference.len() - 1)];
+
+        if b2 < a1 {
+            writer.push(0b0001, 4);
+            a0 = b2;
+        } else if a1.abs_diff(b1) <= 3 {
+            let (code, len) = VERTICAL[a1 + 3 - b1];
+            writer.push(code, len);
+            a0 = a1;
+            black = !black;
+        } else {
+            let a2 = coding[a + 1];
+            writer.push(0b001, 3);
+            write_run(writer, a1 - a0, black);
+            write_run(writer, a2 - a1, !black);
+            a0 = a2;
+        }
+
+        start = false;
+    }
+}
+
+/// Write the codes for a run of pixels of one color.
+fn write_run(writer: &mut BitWriter, mut run: usize, black: bool) {
+    let (terminating, makeup) = if black {
+        (&BLACK_TERMINATING, &BLACK_MAKEUP)
+    } else {
+        (&WHITE_TERMINATING, &WHITE_MAKEUP)
+    };
+
+    while run >= 64 {
+        let chunk = run.min(2560) / 64;
+        let (code, len) = match chunk {
+            1..=27 => makeup[chunk - 1],
+            _ => EXTENDED_MAKEUP[chunk - 28],
+        };
+        writer.push(code, len);
+        run -= chunk * 64;
+    }
+
+    let (code, len) = terminating[run];
+    writer.push(code, len);
+}
+
+/// Writes codes most significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    data: Vec<u8>,
+    buf: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    /// Append the lowest `len` bits of `code`.
+    fn push(&mut self, code: u16, len: u8) {
+        self.buf = (self.buf << len) | u32::from(code);
+        self.count += u32::from(len);
+        while self.count >= 8 {
+            self.count -= 8;
+            self.data.push((self.buf >> self.count) as u8);
+        }
+    }
+
+    /// Pad the last byte with zeros and return the data.
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.data.push((self.buf << (8 - self.count)) as u8);
+        }
+        self.data
+    }
+}
+
+/// The vertical mode codes for `a1 - b1` from -3 to 3.
+const VERTICAL: [(u16, u8); 7] = [
+    (0b0000010, 7),
+    (0b000010, 6),
+    (0b010, 3),
+    (0b1, 1),
+    (0b011, 3),
+    (0b000011, 6),
+    (0b0000011, 7),
+];
+
+/// The codes for white runs of 0 to 63 pixels.
+#[rustfmt::skip]
+const WHITE_TERMINATING: [(u16, u8); 64] = [
+    (0b00110101, 8), (0b000111, 6), (0b0111, 4), (0b1000, 4),
+    (0b1011, 4), (0b1100, 4), (0b1110, 4), (0b1111, 4),
+    (0b10011, 5), (0b10100, 5), (0b00111, 5), (0b01000, 5),
+    (0b001000, 6), (0b000011, 6), (0b110100, 6), (0b110101, 6),
+    (0b101010, 6), (0b101011, 6), (0b0100111, 7), (0b0001100, 7),
+    (0b0001000, 7), (0b0010111, 7), (0b0000011, 7), (0b0000100, 7),
+    (0b0101000, 7), (0b0101011, 7), (0b0010011, 7), (0b0100100, 7),
+    (0b0011000, 7), (0b00000010, 8), (0b00000011, 8), (0b00011010, 8),
+    (0b00011011, 8), (0b00010010, 8), (0b00010011, 8), (0b00010100, 8),
+    (0b00010101, 8), (0b00010110, 8), (0b00010111, 8), (0b00101000, 8),
+    (0b00101001, 8), (0b00101010, 8), (0b00101011, 8), (0b00101100, 8),
+    (0b00101101, 8), (0b00000100, 8), (0b00000101, 8), (0b00001010, 8),
+    (0b00001011, 8), (0b01010010, 8), (0b01010011, 8), (0b01010100, 8),
+    (0b01010101, 8), (0b00100100, 8), (0b00100101, 8), (0b01011000, 8),
+    (0b01011001, 8), (0b01011010, 8), (0b01011011, 8), (0b01001010, 8),
+    (0b01001011, 8), (0b00110010, 8), (0b00110011, 8), (0b00110100, 8),
+];
+
+/// The codes for white runs of 64 to 1728 pixels in steps of 64.
+#[rustfmt::skip]
+const WHITE_MAKEUP: [(u16, u8); 27] = [
+    (0b11011, 5), (0b10010, 5), (0b010111, 6), (0b0110111, 7),
+    (0b00110110, 8), (0b00110111, 8), (0b01100100, 8), (0b01100101, 8),
+    (0b01101000, 8), (0b01100111, 8), (0b011001100, 9), (0b011001101, 9),
+    (0b011010010, 9), (0b011010011, 9), (0b011010100, 9), (0b011010101, 9),
+    (0b011010110, 9), (0b011010111, 9), (0b011011000, 9), (0b011011001, 9),
+    (0b011011010, 9), (0b011011011, 9), (0b010011000, 9), (0b010011001, 9),
+    (0b010011010, 9), (0b011000, 6), (0b010011011, 9),
+];
+
+/// The codes for black runs of 0 to 63 pixels.
+#[rustfmt::skip]
+const BLACK_TERMINATING: [(u16, u8); 64] = [
+    (0b0000110111, 10), (0b010, 3), (0b11, 2), (0b10, 2),
+    (0b011, 3), (0b0011, 4), (0b0010, 4), (0b00011, 5),
+    (0b000101, 6), (0b000100, 6), (0b0000100, 7), (0b0000101, 7),
+    (0b0000111, 7), (0b00000100, 8), (0b00000111, 8), (0b000011000, 9),
+    (0b0000010111, 10), (0b0000011000, 10), (0b0000001000, 10), (0b00001100111, 11),
+    (0b00001101000, 11), (0b00001101100, 11), (0b00000110111, 11), (0b00000101000, 11),
+    (0b00000010111, 11), (0b00000011000, 11), (0b000011001010, 12), (0b000011001011, 12),
+    (0b000011001100, 12), (0b000011001101, 12), (0b000001101000, 12), (0b000001101001, 12),
+    (0b000001101010, 12), (0b000001101011, 12), (0b000011010010, 12), (0b000011010011, 12),
+    (0b000011010100, 12), (0b000011010101, 12), (0b000011010110, 12), (0b000011010111, 12),
+    (0b000001101100, 12), (0b000001101101, 12), (0b000011011010, 12), (0b000011011011, 12),
+    (0b000001010100, 12), (0b000001010101, 12), (0b000001010110, 12), (0b000001010111, 12),
+    (0b000001100100, 12), (0b000001100101, 12), (0b000001010010, 12), (0b000001010011, 12),
+    (0b000000100100, 12), (0b000000110111, 12), (0b000000111000, 12), (0b000000100111, 12),
+    (0b000000101000, 12), (0b000001011000, 12), (0b000001011001, 12), (0b000000101011, 12),
+    (0b000000101100, 12), (0b000001011010, 12), (0b000001100110, 12), (0b000001100111, 12),
+];
+
+/// The codes for black runs of 64 to 1728 pixels in steps of 64.
+#[rustfmt::skip]
+const BLACK_MAKEUP: [(u16, u8); 27] = [
+    (0b0000001111, 10), (0b000011001000, 12), (0b000011001001, 12), (0b000001011011, 12),
+    (0b000000110011, 12), (0b000000110100, 12), (0b000000110101, 12), (0b0000001101100, 13),
+    (0b0000001101101, 13), (0b0000001001010, 13), (0b0000001001011, 13), (0b0000001001100, 13),
+    (0b0000001001101, 13), (0b0000001110010, 13), (0b0000001110011, 13), (0b0000001110100, 13),
+    (0b0000001110101, 13), (0b0000001110110, 13), (0b0000001110111, 13), (0b0000001010010, 13),
+    (0b0000001010011, 13), (0b0000001010100, 13), (0b0000001010101, 13), (0b0000001011010, 13),
+    (0b0000001011011, 13), (0b0000001100100, 13), (0b0000001100101, 13),
+];
+
+/// The codes for runs of either color of 1792 to 2560 pixels in steps of 64.
+#[rustfmt::skip]
+const EXTENDED_MAKEUP: [(u16, u8); 13] = [
+    (0b00000001000, 11), (0b00000001100, 11), (0b00000001101, 11), (0b000000010010, 12),
+    (0b000000010011, 12), (0b000000010100, 12), (0b000000010101, 12), (0b000000010110, 12),
+    (0b000000010111, 12), (0b000000011100, 12), (0b000000011101, 12), (0b000000011110, 12),
+    (0b000000011111, 12),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ccitt_white_page() {
+        // Every row is coded as vertical mode with no offset.
+        let image = CcittImage::new(&[0xFF, 0xFF], 8, 2, false);
+        assert_eq!(image.as_bytes(), [0b1100_0000, 0b0000_0100, 0, 0b0100_0000]);
+
+        let inverted = CcittImage::new(&[0, 0], 8, 2, true);
+        assert_eq!(inverted.as_bytes(), image.as_bytes());
+
+        let mut chunk = Chunk::new();
+        inverted.write(&mut chunk, Ref::new(1));
+        let pdf = String::from_utf8_lossy(chunk.as_bytes());
+        assert!(pdf.contains(
+            "/DecodeParms <<\n    /K -1\n    /Columns 8\n    /Rows 2\n    /BlackIs1 true\n  >>"
+        ));
+        assert!(pdf.contains("/Decode [1 0]"));
+    }
+
+    #[test]
+    fn test_ccitt_modes() {
+        // The expected data was produced by libtiff. The second row of each
+        // image starts with the mode named in the comment.
+        let encode =
+            |bitmap: &[u8]| CcittImage::new(bitmap, 16, bitmap.len() as u32 / 2, true);
+
+        // Pass mode: The black run above ends before the one in this row.
+        // ..###...........
+        // ........###.....
+        let image = encode(&[0x38, 0x00, 0x00, 0xE0]);
+        assert_eq!(
+            image.as_bytes(),
+            [
+                0b0010_1111,
+                0b0100_0100,
+                0b1100_0101,
+                0b0000_0000,
+                0b0001_0000,
+                0b0000_0001
+            ]
+        );
+
+        // Horizontal mode: The runs are too far from the ones above.
+        // ................
+        // ...######.......
+        // ...........#####
+        let image = encode(&[0x00, 0x00, 0x1F, 0x80, 0x00, 0x1F]);
+        assert_eq!(
+            image.as_bytes(),
+            [
+                0b1001_1000,
+                0b0010_1000,
+                0b1001_0111,
+                0b0011_0000,
+                0b0000_0001,
+                0b0000_0000,
+                0b0001_0000,
+            ]
+        );
+
+        // Vertical modes: The runs are shifted by one or three pixels.
+        // ....####........
+        // .....####.......
+        // ...######.......
+        // ......##........
+        let image = encode(&[0x0F, 0x00, 0x07, 0x80, 0x1F, 0x80, 0x03, 0x00]);
+        assert_eq!(
+            image.as_bytes(),
+            [
+                0b0011_0110,
+                0b1110_1101,
+                0b1100_0010,
+                0b1100_0001,
+                0b1010_1000,
+                0b0000_0000,
+                0b1000_0000,
+                0b0000_1000,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ccitt_runs() {
+        // Coded as the extended makeup code for 2560, the black makeup code for
+        // 1728 and the black terminating code for 5.
+        let mut writer = BitWriter::default();
+        write_run(&mut writer, 2560 + 1728 + 5, true);
+        assert_eq!(writer.finish(), [0b0000_0001, 0b1111_0000, 0b0011_0010, 0b1001_1000]);
+    }
+}
@@ -0,0 +1,1343 @@
+use crate::outline::Segment;
+use crate::reader::Reader;
+use crate::FontError;
+
+/// A parsed Compact Font Format font program.
+///
+/// See Adobe Technical Note #5176.
+#[derive(Debug, Clone)]
+pub(crate) struct Cff<'a> {
+    /// The raw Name INDEX.
+    name_index: &'a [u8],
+    top: Dict<'a>,
+    strings: Vec<&'a [u8]>,
+    global_subrs: Vec<&'a [u8]>,
+    char_strings: Vec<&'a [u8]>,
+    /// One entry for name-keyed fonts and one per Font DICT for CID-keyed
+    /// fonts.
+    fonts: Vec<FontDict<'a>>,
+    /// The index into `fonts` for each glyph. Empty for name-keyed fonts.
+    fd_select: Vec<u8>,
+}
+
+/// A Font DICT with its Private DICT and local subroutines.
+#[derive(Debug, Clone)]
+pub(crate) struct FontDict<'a> {
+    dict: Dict<'a>,
+    private: Dict<'a>,
+    subrs: Vec<&'a [u8]>,
+}
+
+impl<'a> Cff<'a> {
+    pub(crate) fn parse(data: &'a [u8]) -> Result<Self, FontError> {
+        Self::parse_impl(data).ok_or(FontError::MalformedTable("CFF "))?
+    }
+
+    fn parse_impl(data: &'a [u8]) -> Option<Result<Self, FontError>> {
+        if *data.first()? != 1 {
+            return Some(Err(FontError::Unsupported("CFF version")));
+        }
+
+        let header_size = usize::from(*data.get(2)?);
+        let (_, name_end) = parse_index(data, header_size)?;
+        let (top_dicts, top_end) = parse_index(data, name_end)?;
+        let (strings, strings_end) = parse_index(data, top_end)?;
+        let (global_subrs, _) = parse_index(data, strings_end)?;
+        let top = Dict::parse(top_dicts.first()?)?;
+
+        if top.get(op::CHARSTRING_TYPE).is_some_and(|v| v.first() != Some(&2.0)) {
+            return Some(Err(FontError::Unsupported("Type 1 charstrings in CFF")));
+        }
+
+        let (char_strings, _) = parse_index(data, top.offset(op::CHAR_STRINGS)?)?;
+        let mut fonts = vec![];
+        let mut fd_select = vec![];
+        if top.get(op::ROS).is_some() {
+            let (dicts, _) = parse_index(data, top.offset(op::FD_ARRAY)?)?;
+            for dict in dicts {
+                fonts.push(FontDict::parse(data, Dict::parse(dict)?)?);
+            }
+            fd_select =
+                parse_fd_select(data, top.offset(op::FD_SELECT)?, char_strings.len())?;
+            if fd_select.iter().any(|&fd| usize::from(fd) >= fonts.len()) {
+                return None;
+            }
+        } else {
+            fonts.push(FontDict::parse(data, top.clone())?);
+        }
+
+        Some(Ok(Self {
+            name_index: &data[header_size..name_end],
+            top,
+            strings,
+            global_subrs,
+            char_strings,
+            fonts,
+            fd_select,
+        }))
+    }
+
+    /// The font's name from the Name INDEX.
+    pub(crate) fn name(&self) -> Option<&'a str> {
+        let (names, _) = parse_index(self.name_index, 0)?;
+        std::str::from_utf8(names.first()?).ok()
+    }
+
+    /// Whether the font is CID-keyed.
+    pub(crate) fn is_cid(&self) -> bool {
+        self.top.get(op::ROS).is_some()
+    }
+
+    /// Write a CID-keyed copy of the font in which only the glyphs for which
+    /// `keep` returns `true` have outlines.
+    ///
+    /// Glyph IDs stay the same and the charset maps each glyph ID to the
+    /// identical CID. The registry and ordering are set to `Adobe-Identity`.
+    pub(crate) fn subset(&self, keep: impl Fn(u16) -> bool) -> Vec<u8> {
+        let count = self.char_strings.len();
+
+        // The strings for the new registry and ordering.
+        let mut strings = self.strings.clone();
+        let registry = STANDARD_STRINGS + strings.len();
+        strings.extend([b"Adobe".as_slice(), b"Identity"]);
+        let strings_index = write_index(&strings);
+
+        let charset = if count > 1 {
+            let mut charset = vec![2, 0, 1];
+            charset.extend(((count - 2) as u16).to_be_bytes());
+            charset
+        } else {
+            vec![0]
+        };
+
+        let mut fd_select = vec![3];
+        let mut ranges = vec![];
+        for glyph in 0..count {
+            let fd = self.fd_select.get(glyph).copied().unwrap_or(0);
+            if ranges.last().is_none_or(|&(_, last)| last != fd) {
+                ranges.push((glyph as u16, fd));
+            }
+        }
+        fd_select.extend((ranges.len() as u16).to_be_bytes());
+        for (first, fd) in ranges {
+            fd_select.extend(first.to_be_bytes());
+            fd_select.push(fd);
+        }
+        fd_select.extend((count as u16).to_be_bytes());
+
+        let endchar: &[u8] = &[14];
+        let char_strings: Vec<&[u8]> = self
+            .char_strings
+            .iter()
+            .enumerate()
+            .map(|(i, &data)| if i == 0 || keep(i as u16) { data } else { endchar })
+            .collect();
+        let char_strings_index = write_index(&char_strings);
+
+        // Empty the subroutines that the remaining glyphs don't call.
+        let (global_used, local_used) = self.used_subrs(&char_strings);
+        let prune = |subrs: &[&'a [u8]], used: Option<&Vec<bool>>| -> Vec<u8> {
+            let items: Vec<&[u8]> = subrs
+                .iter()
+                .enumerate()
+                .map(|(i, &data)| match used {
+                    Some(used) if !used[i] => &[],
+                    _ => data,
+                })
+                .collect();
+            write_index(&items)
+        };
+        let global_subrs_index = prune(&self.global_subrs, global_used.as_ref());
+
+        // The Private DICTs with their subroutines and the sizes of just the
+        // DICTs.
+        let privates: Vec<(usize, Vec<u8>)> = self
+            .fonts
+            .iter()
+            .enumerate()
+            .map(|(i, font)| {
+                let mut private = vec![];
+                font.private.write_except(&mut private, &[op::SUBRS]);
+                if !font.subrs.is_empty() {
+                    let offset = private.len() + 6;
+                    push_offset(&mut private, offset);
+                    push_op(&mut private, op::SUBRS);
+                }
+                let size = private.len();
+                private
+                    .extend(prune(&font.subrs, local_used.as_ref().map(|used| &used[i])));
+                (size, private)
+            })
+            .collect();
+
+        // The Top DICT has a fixed size because all offsets are written with
+        // five bytes, so we can write it once to learn its size and then
+        // again with the correct offsets.
+        let write_top = |offsets: [usize; 4]| {
+            let mut top = vec![];
+            push_int(&mut top, registry as i32);
+            push_int(&mut top, registry as i32 + 1);
+            push_int(&mut top, 0);
+            push_op(&mut top, op::ROS);
+            self.top.write_except(&mut top, op::TOP_OFFSETS);
+            push_int(&mut top, count as i32);
+            push_op(&mut top, op::CID_COUNT);
+            for (value, operator) in offsets.into_iter().zip([
+                op::CHARSET,
+                op::FD_SELECT,
+                op::CHAR_STRINGS,
+                op::FD_ARRAY,
+            ]) {
+                push_offset(&mut top, value);
+                push_op(&mut top, operator);
+            }
+            top
+        };
+
+        let top_len = write_top([0; 4]).len();
+        let charset_offset = 4
+            + self.name_index.len()
+            + index_len(&[top_len])
+            + strings_index.len()
+            + global_subrs_index.len();
+        let fd_select_offset = charset_offset + charset.len();
+        let char_strings_offset = fd_select_offset + fd_select.len();
+        let fd_array_offset = char_strings_offset + char_strings_index.len();
+
+        // Like the Top DICT, the Font DICTs have a fixed size.
+        let write_font_dicts = |private_offset: usize| {
+            let mut offset = private_offset;
+            let dicts: Vec<Vec<u8>> = self
+                .fonts
+                .iter()
+                .zip(&privates)
+                .map(|(font, (size, private))| {
+                    // Name-keyed fonts keep their Font DICT entries in the
+                    // Top DICT.
+                    let mut dict = vec![];
+                    if self.is_cid() {
+                        font.dict.write_except(&mut dict, &[op::PRIVATE]);
+                    }
+                    push_offset(&mut dict, *size);
+                    push_offset(&mut dict, offset);
+                    push_op(&mut dict, op::PRIVATE);
+                    offset += private.len();
+                    dict
+                })
+                .collect();
+            let refs: Vec<&[u8]> = dicts.iter().map(Vec::as_slice).collect();
+            write_index(&refs)
+        };
+
+        let fd_array_len = write_font_dicts(0).len();
+        let private_offset = fd_array_offset + fd_array_len;
+
+        let mut out = vec![1, 0, 4, 4];
+        out.extend(self.name_index);
+        let top = write_top([
+            charset_offset,
+            fd_select_offset,
+            char_strings_offset,
+            fd_array_offset,
+        ]);
+        out.extend(write_index(&[&top]));
+        out.extend(strings_index);
+        out.extend(global_subrs_index);
+        out.extend(charset);
+        out.extend(fd_select);
+        out.extend(char_strings_index);
+        out.extend(write_font_dicts(private_offset));
+        for (_, private) in privates {
+            out.extend(private);
+        }
+        out
+    }
+
+    /// Which global and local subroutines the charstrings call.
+    ///
+    /// Returns `None` for subroutines that might all be in use because a
+    /// charstring could not be scanned.
+    #[allow(clippy::type_complexity)]
+    fn used_subrs(
+        &self,
+        char_strings: &[&[u8]],
+    ) -> (Option<Vec<bool>>, Option<Vec<Vec<bool>>>) {
+        let mut global = vec![false; self.global_subrs.len()];
+        let mut local: Vec<Vec<bool>> =
+            self.fonts.iter().map(|font| vec![false; font.subrs.len()]).collect();
+        for (glyph, &code) in char_strings.iter().enumerate() {
+            let fd = usize::from(self.fd_select.get(glyph).copied().unwrap_or(0));
+            let mut scanner = SubrScanner {
+                global: &self.global_subrs,
+                local: &self.fonts[fd].subrs,
+                global_used: &mut global,
+                local_used: &mut local[fd],
+                stack: vec![],
+                stems: 0,
+            };
+            if scanner.scan(code, 0).is_none() {
+                return (None, None);
+            }
+        }
+        (Some(global), Some(local))
+    }
+
+    /// Decode the outline of a glyph into segments.
+    pub(crate) fn outline(&self, glyph: u16, segments: &mut Vec<Segment>) -> Option<()> {
+        let code = self.char_strings.get(usize::from(glyph))?;
+        let fd =
+            usize::from(self.fd_select.get(usize::from(glyph)).copied().unwrap_or(0));
+        let mut interpreter = Interpreter {
+            global: &self.global_subrs,
+            local: &self.fonts.get(fd)?.subrs,
+            regions: None,
+            vsindex: 0,
+            segments,
+            stack: vec![],
+            stems: 0,
+            has_width: false,
+            open: false,
+            x: 0.0,
+            y: 0.0,
+        };
+        interpreter.run(code, 0)?;
+        if interpreter.open {
+            interpreter.segments.push(Segment::Close);
+        }
+        Some(())
+    }
+}
+
+/// A parsed CFF2 font program, as used by variable OpenType fonts.
+///
+/// Only the default instance is decoded, i.e. blended values are replaced
+/// by their defaults.
+#[derive(Debug, Clone)]
+pub(crate) struct Cff2<'a> {
+    global_subrs: Vec<&'a [u8]>,
+    char_strings: Vec<&'a [u8]>,
+    /// The local subroutines and the default item variation data per Font
+    /// DICT.
+    fonts: Vec<(Vec<&'a [u8]>, usize)>,
+    /// The index into `fonts` for each glyph.
+    fd_select: Vec<u16>,
+    /// The number of regions of each item variation data in the variation
+    /// store. Needed to know how many operands `blend` consumes.
+    regions: Vec<u16>,
+}
+
+impl<'a> Cff2<'a> {
+    pub(crate) fn parse(data: &'a [u8]) -> Result<Self, FontError> {
+        Self::parse_impl(data).ok_or(FontError::MalformedTable("CFF2"))?
+    }
+
+    fn parse_impl(data: &'a [u8]) -> Option<Result<Self, FontError>> {
+        let mut r = Reader::new(data);
+        if r.u8()? != 2 {
+            return Some(Err(FontError::Unsupported("CFF2 version")));
+        }
+
+        r.skip(1)?;
+        let header_size = usize::from(r.u8()?);
+        let top_end = header_size + usize::from(r.u16()?);
+        let top = Dict::parse(data.get(header_size..top_end)?)?;
+        let (global_subrs, _) = parse_cff2_index(data, top_end)?;
+        let (char_strings, _) = parse_cff2_index(data, top.offset(op::CHAR_STRINGS)?)?;
+
+        let mut fonts = vec![];
+        for dict in parse_cff2_index(data, top.offset(op::FD_ARRAY)?)?.0 {
+            let dict = Dict::parse(dict)?;
+            let mut subrs = vec![];
+            let mut vsindex = 0;
+            if let Some(&[size, offset]) = dict.get(op::PRIVATE).as_deref() {
+                let (size, offset) = (size as usize, offset as usize);
+                let private = Dict::parse(data.get(offset..offset.checked_add(size)?)?)?;
+                if let Some(subrs_offset) = private.offset(op::SUBRS) {
+                    subrs = parse_cff2_index(data, offset + subrs_offset)?.0;
+                }
+                vsindex = private.offset(op::VSINDEX).unwrap_or(0);
+            }
+            fonts.push((subrs, vsindex));
+        }
+
+        let count = char_strings.len();
+        let fd_select = match top.offset(op::FD_SELECT) {
+            Some(offset) => parse_cff2_fd_select(data, offset, count)?,
+            None => vec![0; count],
+        };
+        if fd_select.iter().any(|&fd| usize::from(fd) >= fonts.len()) {
+            return None;
+        }
+
+        let regions = match top.offset(op::VSTORE) {
+            Some(offset) => parse_region_counts(data, offset + 2)?,
+            None => vec![],
+        };
+
+        Some(Ok(Self {
+            global_subrs,
+            char_strings,
+            fonts,
+            fd_select,
+            regions,
+        }))
+    }
+
+    /// Decode the outline of a glyph in the default instance into segments.
+    pub(crate) fn outline(&self, glyph: u16, segments: &mut Vec<Segment>) -> Option<()> {
+        let code = self.char_strings.get(usize::from(glyph))?;
+        let fd = usize::from(*self.fd_select.get(usize::from(glyph))?);
+        let (local, vsindex) = self.fonts.get(fd)?;
+        let mut interpreter = Interpreter {
+            global: &self.global_subrs,
+            local,
+            regions: Some(&self.regions),
+            vsindex: *vsindex,
+            segments,
+            stack: vec![],
+            stems: 0,
+            // CFF2 charstrings have no width.
+            has_width: true,
+            open: false,
+            x: 0.0,
+            y: 0.0,
+        };
+        interpreter.run(code, 0)?;
+        if interpreter.open {
+            interpreter.segments.push(Segment::Close);
+        }
+        Some(())
+    }
+}
+
+/// Interprets Type 2 charstrings to decode glyph outlines.
+///
+/// See Adobe Technical Note #5177.
+struct Interpreter<'a, 'b> {
+    global: &'b [&'a [u8]],
+    local: &'b [&'a [u8]],
+    /// The region counts of the variation store for CFF2 charstrings.
+    regions: Option<&'b [u16]>,
+    /// The item variation data that `blend` refers to.
+    vsindex: usize,
+    segments: &'b mut Vec<Segment>,
+    stack: Vec<f32>,
+    stems: usize,
+    /// Whether the optional width argument was already seen or ruled out.
+    has_width: bool,
+    /// Whether a subpath is in progress.
+    open: bool,
+    x: f32,
+    y: f32,
+}
+
+impl Interpreter<'_, '_> {
+    /// Run a charstring or subroutine. Returns whether `endchar` was reached.
+    fn run(&mut self, code: &[u8], depth: u8) -> Option<bool> {
+        if depth > 10 {
+            return None;
+        }
+
+        let mut r = Reader::new(code);
+        while !r.eof() {
+            let b0 = r.u8()?;
+            match b0 {
+                28 => self.push(r.i16()?.into())?,
+                32..=246 => self.push(f32::from(b0) - 139.0)?,
+                247..=250 => self
+                    .push((f32::from(b0) - 247.0) * 256.0 + f32::from(r.u8()?) + 108.0)?,
+                251..=254 => self.push(
+                    -(f32::from(b0) - 251.0) * 256.0 - f32::from(r.u8()?) - 108.0,
+                )?,
+                255 => self.push(r.i32()? as f32 / 65536.0)?,
+                // hstem, vstem, hstemhm, vstemhm
+                1 | 3 | 18 | 23 => {
+                    self.take_width(self.stack.len() % 2 == 1);
+                    self.stems += self.stack.len() / 2;
+                    self.stack.clear();
+                }
+                // hintmask and cntrmask
+                19 | 20 => {
+                    self.take_width(self.stack.len() % 2 == 1);
+                    self.stems += self.stack.len() / 2;
+                    self.stack.clear();
+                    r.skip(self.stems.div_ceil(8))?;
+                }
+                // rmoveto
+                21 => {
+                    self.take_width(self.stack.len() > 2);
+                    let [dx, dy] = self.args()?;
+                    self.move_by(dx, dy);
+                }
+                // hmoveto
+                22 => {
+                    self.take_width(self.stack.len() > 1);
+                    let [dx] = self.args()?;
+                    self.move_by(dx, 0.0);
+                }
+                // vmoveto
+                4 => {
+                    self.take_width(self.stack.len() > 1);
+                    let [dy] = self.args()?;
+                    self.move_by(0.0, dy);
+                }
+                // rlineto
+                5 => {
+                    for pair in std::mem::take(&mut self.stack).chunks_exact(2) {
+                        self.line_by(pair[0], pair[1]);
+                    }
+                }
+                // hlineto and vlineto
+                6 | 7 => {
+                    let mut horizontal = b0 == 6;
+                    for d in std::mem::take(&mut self.stack) {
+                        if horizontal {
+                            self.line_by(d, 0.0);
+                        } else {
+                            self.line_by(0.0, d);
+                        }
+                        horizontal = !horizontal;
+                    }
+                }
+                // rrcurveto
+                8 => {
+                    for args in std::mem::take(&mut self.stack).chunks_exact(6) {
+                        self.curve_by(
+                            args[0], args[1], args[2], args[3], args[4], args[5],
+                        );
+                    }
+                }
+                // rcurveline
+                24 => {
+                    let args = std::mem::take(&mut self.stack);
+                    let (curves, line) = args.split_at(args.len().checked_sub(2)?);
+                    for c in curves.chunks_exact(6) {
+                        self.curve_by(c[0], c[1], c[2], c[3], c[4], c[5]);
+                    }
+                    self.line_by(line[0], line[1]);
+                }
+                // rlinecurve
+                25 => {
+                    let args = std::mem::take(&mut self.stack);
+                    let (lines, c) = args.split_at(args.len().checked_sub(6)?);
+                    for pair in lines.chunks_exact(2) {
+                        self.line_by(pair[0], pair[1]);
+                    }
+                    self.curve_by(c[0], c[1], c[2], c[3], c[4], c[5]);
+                }
+                // vvcurveto and hhcurveto
+                26 | 27 => {
+                    let args = std::mem::take(&mut self.stack);
+                    let (mut first, rest) = match args.len() % 4 {
+                        1 => (args[0], &args[1..]),
+                        _ => (0.0, &args[..]),
+                    };
+                    for c in rest.chunks_exact(4) {
+                        if b0 == 26 {
+                            self.curve_by(first, c[0], c[1], c[2], 0.0, c[3]);
+                        } else {
+                            self.curve_by(c[0], first, c[1], c[2], c[3], 0.0);
+                        }
+                        first = 0.0;
+                    }
+                }
+                // vhcurveto and hvcurveto
+                30 | 31 => {
+                    let args = std::mem::take(&mut self.stack);
+                    let mut horizontal = b0 == 31;
+                    let mut i = 0;
+                    while i + 4 <= args.len() {
+                        let last = if args.len() - i == 5 { args[i + 4] } else { 0.0 };
+                        let c = &args[i..i + 4];
+                        if horizontal {
+                            self.curve_by(c[0], 0.0, c[1], c[2], last, c[3]);
+                        } else {
+                            self.curve_by(0.0, c[0], c[1], c[2], c[3], last);
+                        }
+                        horizontal = !horizontal;
+                        i += 4;
+                    }
+                }
+                // callsubr and callgsubr
+                10 | 29 => {
+                    let subrs = if b0 == 10 { self.local } else { self.global };
+                    let index = self.stack.pop()? as i32 + subr_bias(subrs.len());
+                    let subr = subrs.get(usize::try_from(index).ok()?)?;
+                    if self.run(subr, depth + 1)? {
+                        return Some(true);
+                    }
+                }
+                // vsindex
+                15 if self.regions.is_some() => {
+                    let [index] = self.args()?;
+                    self.vsindex = index as usize;
+                }
+                // blend
+                16 if self.regions.is_some() => self.blend()?,
+                // return
+                11 => return Some(false),
+                // endchar
+                14 => {
+                    self.take_width(self.stack.len() == 1 || self.stack.len() == 5);
+                    return Some(true);
+                }
+                12 => {
+                    let b1 = r.u8()?;
+                    self.flex(b1)?;
+                    self.stack.clear();
+                }
+                _ => self.stack.clear(),
+            }
+        }
+        Some(false)
+    }
+
+    /// The flex operators. Other escaped operators are ignored.
+    fn flex(&mut self, op: u8) -> Option<()> {
+        let a = &self.stack;
+        let (first, second) = match op {
+            // flex
+            35 if a.len() >= 12 => (
+                [a[0], a[1], a[2], a[3], a[4], a[5]],
+                [a[6], a[7], a[8], a[9], a[10], a[11]],
+            ),
+            // hflex
+            34 if a.len() >= 7 => {
+                ([a[0], 0.0, a[1], a[2], a[3], 0.0], [a[4], 0.0, a[5], -a[2], a[6], 0.0])
+            }
+            // hflex1
+            36 if a.len() >= 9 => {
+                let dy = -(a[1] + a[3] + a[7]);
+                ([a[0], a[1], a[2], a[3], a[4], 0.0], [a[5], 0.0, a[6], a[7], a[8], dy])
+            }
+            // flex1
+            37 if a.len() >= 11 => {
+                let dx: f32 = a[0] + a[2] + a[4] + a[6] + a[8];
+                let dy: f32 = a[1] + a[3] + a[5] + a[7] + a[9];
+                let (dx6, dy6) =
+                    if dx.abs() > dy.abs() { (a[10], -dy) } else { (-dx, a[10]) };
+                ([a[0], a[1], a[2], a[3], a[4], a[5]], [a[6], a[7], a[8], a[9], dx6, dy6])
+            }
+            34..=37 => return None,
+            _ => return Some(()),
+        };
+        let [a, b, c, d, e, f] = first;
+        self.curve_by(a, b, c, d, e, f);
+        let [a, b, c, d, e, f] = second;
+        self.curve_by(a, b, c, d, e, f);
+        Some(())
+    }
+
+    /// Replace blended values by their defaults.
+    fn blend(&mut self) -> Option<()> {
+        let count = self.stack.pop()? as usize;
+        let regions = usize::from(*self.regions?.get(self.vsindex)?);
+        let start = self.stack.len().checked_sub(count * (regions + 1))?;
+        self.stack.truncate(start + count);
+        Some(())
+    }
+
+    fn push(&mut self, value: f32) -> Option<()> {
+        let limit = if self.regions.is_some() { 513 } else { 48 };
+        if self.stack.len() >= limit {
+            return None;
+        }
+        self.stack.push(value);
+        Some(())
+    }
+
+    /// Remove the width from the stack if it is there. It can only occur
+    /// before the first stack-clearing operator.
+    fn take_width(&mut self, present: bool) {
+        if !self.has_width && present {
+            self.stack.remove(0);
+        }
+        self.has_width = true;
+    }
+
+    /// Take exactly `N` arguments from the stack.
+    fn args<const N: usize>(&mut self) -> Option<[f32; N]> {
+        let args = std::mem::take(&mut self.stack);
+        args.try_into().ok()
+    }
+
+    fn move_by(&mut self, dx: f32, dy: f32) {
+        if self.open {
+            self.segments.push(Segment::Close);
+        }
+        self.x += dx;
+        self.y += dy;
+        self.segments.push(Segment::MoveTo(self.x, self.y));
+        self.open = true;
+    }
+
+    fn line_by(&mut self, dx: f32, dy: f32) {
+        self.x += dx;
+        self.y += dy;
+        self.segments.push(Segment::LineTo(self.x, self.y));
+    }
+
+    fn curve_by(&mut self, dx1: f32, dy1: f32, dx2: f32, dy2: f32, dx3: f32, dy3: f32) {
+        let (x1, y1) = (self.x + dx1, self.y + dy1);
+        let (x2, y2) = (x1 + dx2, y1 + dy2);
+        self.x = x2 + dx3;
+        self.y = y2 + dy3;
+        self.segments.push(Segment::CubicTo(x1, y1, x2, y2, self.x, self.y));
+    }
+}
+
+/// Finds the subroutines that a Type 2 charstring calls.
+///
+/// Only keeps track of what is needed to follow subroutine calls: The operand
+/// stack and the number of stem hints, which determines the length of hint
+/// masks.
+struct SubrScanner<'a, 'b> {
+    global: &'b [&'a [u8]],
+    local: &'b [&'a [u8]],
+    global_used: &'b mut Vec<bool>,
+    local_used: &'b mut Vec<bool>,
+    stack: Vec<i32>,
+    stems: usize,
+}
+
+impl SubrScanner<'_, '_> {
+    /// Scan a charstring or subroutine. Returns whether `endchar` was reached.
+    fn scan(&mut self, code: &[u8], depth: u8) -> Option<bool> {
+        if depth > 10 {
+            return None;
+        }
+
+        let mut r = Reader::new(code);
+        while !r.eof() {
+            let b0 = r.u8()?;
+            match b0 {
+                28 => self.stack.push(r.i16()?.into()),
+                32..=246 => self.stack.push(i32::from(b0) - 139),
+                247..=250 => self
+                    .stack
+                    .push((i32::from(b0) - 247) * 256 + i32::from(r.u8()?) + 108),
+                251..=254 => self
+                    .stack
+                    .push(-(i32::from(b0) - 251) * 256 - i32::from(r.u8()?) - 108),
+                // A 16.16 fixed-point number.
+                255 => self.stack.push(r.i32()? >> 16),
+                // hstem, vstem, hstemhm, vstemhm
+                1 | 3 | 18 | 23 => {
+                    self.stems += self.stack.len() / 2;
+                    self.stack.clear();
+                }
+                // hintmask and cntrmask, which may have implicit vstem
+                // operands.
+                19 | 20 => {
+                    self.stems += self.stack.len() / 2;
+                    self.stack.clear();
+                    r.skip(self.stems.div_ceil(8))?;
+                }
+                // callsubr and callgsubr
+                10 | 29 => {
+                    let (subrs, used) = if b0 == 10 {
+                        (self.local, &mut *self.local_used)
+                    } else {
+                        (self.global, &mut *self.global_used)
+                    };
+                    let index = self.stack.pop()? + subr_bias(subrs.len());
+                    let index = usize::try_from(index).ok()?;
+                    *used.get_mut(index)? = true;
+                    if self.scan(subrs[index], depth + 1)? {
+                        return Some(true);
+                    }
+                }
+                // return
+                11 => return Some(false),
+                // endchar
+                14 => return Some(true),
+                12 => {
+                    r.skip(1)?;
+                    self.stack.clear();
+                }
+                _ => self.stack.clear(),
+            }
+        }
+        Some(false)
+    }
+}
+
+/// The number that is added to subroutine indices in charstrings.
+fn subr_bias(count: usize) -> i32 {
+    match count {
+        0..1240 => 107,
+        1240..33900 => 1131,
+        _ => 32768,
+    }
+}
+
+impl<'a> FontDict<'a> {
+    fn parse(data: &'a [u8], dict: Dict<'a>) -> Option<Self> {
+        let mut private = Dict::default();
+        let mut subrs = vec![];
+        if let Some(&[size, offset]) = dict.get(op::PRIVATE).as_deref() {
+            let (size, offset) = (size as usize, offset as usize);
+            private = Dict::parse(data.get(offset..offset.checked_add(size)?)?)?;
+            if let Some(subrs_offset) = private.offset(op::SUBRS) {
+                subrs = parse_index(data, offset + subrs_offset)?.0;
+            }
+        }
+        Some(Self { dict, private, subrs })
+    }
+}
+
+/// The number of predefined strings. User strings have IDs starting at this.
+const STANDARD_STRINGS: usize = 391;
+
+/// DICT operators. Two-byte operators have `12` in the upper byte.
+mod op {
+    pub const CHARSET: u16 = 15;
+    pub const ENCODING: u16 = 16;
+    pub const CHAR_STRINGS: u16 = 17;
+    pub const PRIVATE: u16 = 18;
+    pub const SUBRS: u16 = 19;
+    pub const VSINDEX: u16 = 22;
+    pub const VSTORE: u16 = 24;
+    pub const UNIQUE_ID: u16 = 13;
+    pub const XUID: u16 = 14;
+    pub const CHARSTRING_TYPE: u16 = 0x0C06;
+    pub const ROS: u16 = 0x0C1E;
+    pub const CID_COUNT: u16 = 0x0C22;
+    pub const UID_BASE: u16 = 0x0C23;
+    pub const FD_ARRAY: u16 = 0x0C24;
+    pub const FD_SELECT: u16 = 0x0C25;
+
+    /// The Top DICT operators that are rewritten when subsetting.
+    pub const TOP_OFFSETS: &[u16] = &[
+        CHARSET,
+        ENCODING,
+        CHAR_STRINGS,
+        PRIVATE,
+        UNIQUE_ID,
+        XUID,
+        ROS,
+        CID_COUNT,
+        UID_BASE,
+        FD_ARRAY,
+        FD_SELECT,
+    ];
+}
+
+/// A DICT as a list of operators and their raw operands.
+#[derive(Debug, Clone, Default)]
+struct Dict<'a>(Vec<(u16, &'a [u8])>);
+
+impl<'a> Dict<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut entries = vec![];
+        let mut r = Reader::new(data);
+        let mut start = 0;
+        while !r.eof() {
+            let b0 = r.u8()?;
+            match b0 {
+                // Operators 22 to 24 only exist in CFF2.
+                0..=24 => {
+                    let op =
+                        if b0 == 12 { 0x0C00 | u16::from(r.u8()?) } else { b0.into() };
+                    let end = r.pos() - if b0 == 12 { 2 } else { 1 };
+                    entries.push((op, &data[start..end]));
+                    start = r.pos();
+                }
+                28 => r.skip(2)?,
+                29 => r.skip(4)?,
+                30 => while r.u8()? & 0x0F != 0x0F {},
+                32..=246 => {}
+                247..=254 => r.skip(1)?,
+                _ => return None,
+            }
+        }
+        Some(Self(entries))
+    }
+
+    /// The operands of an operator.
+    fn get(&self, op: u16) -> Option<Vec<f64>> {
+        let &(_, operands) = self.0.iter().find(|&&(other, _)| other == op)?;
+        parse_operands(operands)
+    }
+
+    /// The single operand of an operator that holds an offset.
+    fn offset(&self, op: u16) -> Option<usize> {
+        match self.get(op)?.as_slice() {
+            &[offset] if offset >= 0.0 => Some(offset as usize),
+            _ => None,
+        }
+    }
+
+    /// Write all entries except the ones with the given operators.
+    fn write_except(&self, out: &mut Vec<u8>, ops: &[u16]) {
+        for &(op, operands) in &self.0 {
+            if !ops.contains(&op) {
+                out.extend(operands);
+                push_op(out, op);
+            }
+        }
+    }
+}
+
+/// Parse the operands of a DICT entry.
+fn parse_operands(data: &[u8]) -> Option<Vec<f64>> {
+    let mut values = vec![];
+    let mut r = Reader::new(data);
+    while !r.eof() {
+        let b0 = r.u8()?;
+        let value = match b0 {
+            28 => f64::from(r.i16()?),
+            29 => f64::from(r.i32()?),
+            30 => parse_real(&mut r)?,
+            32..=246 => f64::from(i32::from(b0) - 139),
+            247..=250 => {
+                f64::from((i32::from(b0) - 247) * 256 + i32::from(r.u8()?) + 108)
+            }
+            251..=254 => {
+                f64::from(-(i32::from(b0) - 251) * 256 - i32::from(r.u8()?) - 108)
+            }
+            _ => return None,
+        };
+        values.push(value);
+    }
+    Some(values)
+}
+
+/// Parse a real number operand, whose digits are stored in nibbles.
+fn parse_real(r: &mut Reader) -> Option<f64> {
+    let mut text = String::new();
+    loop {
+        let byte = r.u8()?;
+        for nibble in [byte >> 4, byte & 0x0F] {
+            match nibble {
+                0..=9 => text.push(char::from(b'0' + nibble)),
+                0xA => text.push('.'),
+                0xB => text.push('E'),
+                0xC => text.push_str("E-"),
+                0xE => text.push('-'),
+                0xF => return text.parse().ok(),
+                _ => return None,
+            }
+        }
+    }
+}
+
+/// Parse an INDEX and return its items and the offset at which it ends.
+pub(crate) fn parse_index(data: &[u8], pos: usize) -> Option<(Vec<&[u8]>, usize)> {
+    parse_index_impl(data, pos, 2)
+}
+
+/// Parse a CFF2 INDEX, which has a four-byte count.
+fn parse_cff2_index(data: &[u8], pos: usize) -> Option<(Vec<&[u8]>, usize)> {
+    parse_index_impl(data, pos, 4)
+}
+
+fn parse_index_impl(
+    data: &[u8],
+    pos: usize,
+    count_size: u8,
+) -> Option<(Vec<&[u8]>, usize)> {
+    let mut r = Reader::at(data, pos);
+    let count = r.offset(count_size)?;
+    if count == 0 {
+        return Some((vec![], r.pos()));
+    }
+
+    let size = r.u8()?;
+    let mut offsets = Vec::with_capacity(count.min(data.len()) + 1);
+    for _ in 0..=count {
+        offsets.push(r.offset(size)?);
+    }
+
+    // Offsets are relative to the byte before the object data.
+    let base = r.pos() - 1;
+    let items = offsets
+        .windows(2)
+        .map(|w| data.get(base.checked_add(w[0])?..base.checked_add(w[1])?))
+        .collect::<Option<Vec<_>>>()?;
+    Some((items, base + offsets[count]))
+}
+
+/// Parse an FDSelect structure into a Font DICT index per glyph.
+fn parse_fd_select(data: &[u8], pos: usize, count: usize) -> Option<Vec<u8>> {
+    let mut r = Reader::at(data, pos);
+    match r.u8()? {
+        0 => Some(r.bytes(count)?.to_vec()),
+        3 => {
+            let ranges = r.u16()?;
+            let mut fds = Vec::with_capacity(count);
+            let mut first = r.u16()?;
+            for _ in 0..ranges {
+                let fd = r.u8()?;
+                let next = r.u16()?;
+                if next < first {
+                    return None;
+                }
+                fds.extend(std::iter::repeat_n(fd, usize::from(next - first)));
+                first = next;
+            }
+            fds.resize(count, 0);
+            Some(fds)
+        }
+        _ => None,
+    }
+}
+
+/// Parse a CFF2 FDSelect structure, which may also have format 4 with
+/// two-byte Font DICT indices.
+fn parse_cff2_fd_select(data: &[u8], pos: usize, count: usize) -> Option<Vec<u16>> {
+    let mut r = Reader::at(data, pos);
+    if r.u8()? != 4 {
+        let fds = parse_fd_select(data, pos, count)?;
+        return Some(fds.into_iter().map(u16::from).collect());
+    }
+
+    let ranges = r.u32()?;
+    let mut fds = Vec::with_capacity(count);
+    let mut first = r.u32()? as usize;
+    for _ in 0..ranges {
+        let fd = r.u16()?;
+        let next = r.u32()? as usize;
+        if next < first || next > count {
+            return None;
+        }
+        fds.extend(std::iter::repeat_n(fd, next - first));
+        first = next;
+    }
+    fds.resize(count, 0);
+    Some(fds)
+}
+
+/// Parse the number of regions of each item variation data in an item
+/// variation store.
+fn parse_region_counts(data: &[u8], pos: usize) -> Option<Vec<u16>> {
+    let mut r = Reader::at(data, pos);
+    r.skip(6)?;
+    let count = r.u16()?;
+    (0..count)
+        .map(|_| {
+            let offset = r.u32()? as usize;
+            Reader::at(data, pos.checked_add(offset)? + 4).u16()
+        })
+        .collect()
+}
+
+/// The size of an INDEX with items of the given lengths.
+fn index_len(lengths: &[usize]) -> usize {
+    if lengths.is_empty() {
+        return 2;
+    }
+
+    let total: usize = lengths.iter().sum();
+    3 + offset_size(total + 1) * (lengths.len() + 1) + total
+}
+
+/// Write an INDEX.
+fn write_index(items: &[&[u8]]) -> Vec<u8> {
+    let mut out = vec![];
+    out.extend((items.len() as u16).to_be_bytes());
+    if items.is_empty() {
+        return out;
+    }
+
+    let total: usize = items.iter().map(|item| item.len()).sum();
+    let size = offset_size(total + 1);
+    out.push(size as u8);
+    let mut offset = 1;
+    for len in std::iter::once(0).chain(items.iter().map(|item| item.len())) {
+        offset += len;
+        out.extend(&(offset as u32).to_be_bytes()[4 - size..]);
+    }
+    for item in items {
+        out.extend(*item);
+    }
+    out
+}
+
+/// The smallest offset size that can hold the given value.
+fn offset_size(max: usize) -> usize {
+    match max {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        0x1_0000..=0xFF_FFFF => 3,
+        _ => 4,
+    }
+}
+
+/// Write an integer operand in the most compact form.
+fn push_int(out: &mut Vec<u8>, value: i32) {
+    match value {
+        -107..=107 => out.push((value + 139) as u8),
+        108..=1131 => {
+            let v = value - 108;
+            out.extend([(v / 256 + 247) as u8, (v % 256) as u8]);
+        }
+        -1131..=-108 => {
+            let v = -value - 108;
+            out.extend([(v / 256 + 251) as u8, (v % 256) as u8]);
+        }
+        -32768..=32767 => {
+            out.push(28);
+            out.extend((value as i16).to_be_bytes());
+        }
+        _ => {
+            out.push(29);
+            out.extend(value.to_be_bytes());
+        }
+    }
+}
+
+/// Write an offset operand with a fixed size of five bytes.
+fn push_offset(out: &mut Vec<u8>, value: usize) {
+    out.push(29);
+    out.extend((value as i32).to_be_bytes());
+}
+
+/// Write an operator.
+fn push_op(out: &mut Vec<u8>, op: u16) {
+    if op >> 8 == 12 {
+        out.extend([12, op as u8]);
+    } else {
+        out.push(op as u8);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fixture {
+    use super::*;
+
+    /// A CFF font with the given charstrings and subroutines.
+    ///
+    /// With an empty `fd_select`, the font is name-keyed and uses the first
+    /// list of local subroutines. Otherwise, it is CID-keyed with a Font DICT
+    /// per list of local subroutines and `fd_select` holds the Font DICT of
+    /// each glyph.
+    pub(crate) fn cff(
+        char_strings: &[&[u8]],
+        global_subrs: &[&[u8]],
+        local_subrs: &[&[&[u8]]],
+        fd_select: &[u8],
+    ) -> Vec<u8> {
+        let cid = !fd_select.is_empty();
+        let privates: Vec<Vec<u8>> = local_subrs
+            .iter()
+            .map(|subrs| {
+                let mut private = vec![];
+                if !subrs.is_empty() {
+                    push_offset(&mut private, 6);
+                    push_op(&mut private, op::SUBRS);
+                    private.extend(write_index(subrs));
+                }
+                private
+            })
+            .collect();
+        let private_size = |private: &Vec<u8>| private.len().min(6);
+
+        let write_top = |offsets: [usize; 3]| {
+            let mut top = vec![];
+            if cid {
+                push_int(&mut top, 0);
+                push_int(&mut top, 0);
+                push_int(&mut top, 0);
+                push_op(&mut top, op::ROS);
+            }
+            push_offset(&mut top, offsets[0]);
+            push_op(&mut top, op::CHAR_STRINGS);
+            if cid {
+                push_offset(&mut top, offsets[1]);
+                push_op(&mut top, op::FD_SELECT);
+                push_offset(&mut top, offsets[2]);
+                push_op(&mut top, op::FD_ARRAY);
+            } else {
+                push_offset(&mut top, private_size(&privates[0]));
+                push_offset(&mut top, offsets[2]);
+                push_op(&mut top, op::PRIVATE);
+            }
+            top
+        };
+
+        let name_index = write_index(&[b"Test".as_slice()]);
+        let global_subrs_index = write_index(global_subrs);
+        let char_strings_index = write_index(char_strings);
+        let char_strings_offset = 4
+            + name_index.len()
+            + index_len(&[write_top([0; 3]).len()])
+            + 2
+            + global_subrs_index.len();
+        let fd_select_offset = char_strings_offset + char_strings_index.len();
+
+        let mut tail = vec![];
+        let mut offsets = [char_strings_offset, fd_select_offset, fd_select_offset];
+        if cid {
+            tail.push(0);
+            tail.extend(fd_select);
+            offsets[2] += tail.len();
+
+            // Each Font DICT only has a Private entry with fixed-size offsets.
+            let dict_len = 11;
+            let mut offset = offsets[2] + index_len(&vec![dict_len; privates.len()]);
+            let dicts: Vec<Vec<u8>> = privates
+                .iter()
+                .map(|private| {
+                    let mut dict = vec![];
+                    push_offset(&mut dict, private_size(private));
+                    push_offset(&mut dict, offset);
+                    push_op(&mut dict, op::PRIVATE);
+                    offset += private.len();
+                    dict
+                })
+                .collect();
+            let refs: Vec<&[u8]> = dicts.iter().map(Vec::as_slice).collect();
+            tail.extend(write_index(&refs));
+        }
+        for private in &privates {
+            tail.extend(private);
+        }
+
+        let mut out = vec![1, 0, 4, 4];
+        out.extend(name_index);
+        out.extend(write_index(&[&write_top(offsets)]));
+        out.extend(write_index(&[]));
+        out.extend(global_subrs_index);
+        out.extend(char_strings_index);
+        out.extend(tail);
+        out
+    }
+
+    /// Append charstring operands and an operator to `code`.
+    pub(crate) fn push(code: &mut Vec<u8>, operands: &[i32], op: &[u8]) {
+        for &operand in operands {
+            assert!((-32768..=32767).contains(&operand));
+            push_int(code, operand);
+        }
+        code.extend(op);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixture::{cff, push};
+    use super::*;
+
+    /// The data at the offset that a Top DICT operator points to.
+    fn at<'a>(data: &'a [u8], font: &Cff, op: u16) -> &'a [u8] {
+        &data[font.top.offset(op).unwrap()..]
+    }
+
+    /// The operators of a DICT.
+    fn ops(dict: &Dict) -> Vec<u16> {
+        dict.0.iter().map(|&(op, _)| op).collect()
+    }
+
+    /// Borrow each item as a slice.
+    fn refs(items: &[Vec<u8>]) -> Vec<&[u8]> {
+        items.iter().map(Vec::as_slice).collect()
+    }
+
+    /// The outline of each glyph.
+    fn outlines(font: &Cff) -> Vec<Vec<Segment>> {
+        (0..font.char_strings.len() as u16)
+            .map(|glyph| {
+                let mut segments = vec![];
+                font.outline(glyph, &mut segments).unwrap();
+                segments
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_subset_name_keyed() {
+        let mut locals = [vec![], vec![]];
+        push(&mut locals[0], &[100, 0], &[5, 11]);
+        push(&mut locals[1], &[0, 100], &[5, 11]);
+        let mut globals = [vec![], vec![]];
+        push(&mut globals[0], &[50, 50], &[5, 11]);
+        push(&mut globals[1], &[-50, 50], &[5, 11]);
+
+        // Glyph 1 calls the first local and the second global subroutine and
+        // glyph 2 the others.
+        let mut glyphs = [vec![14], vec![], vec![]];
+        for (glyph, local, global) in [(1, -107, -106), (2, -106, -107)] {
+            push(&mut glyphs[glyph], &[0, 0], &[21]);
+            push(&mut glyphs[glyph], &[local], &[10]);
+            push(&mut glyphs[glyph], &[global], &[29, 14]);
+        }
+
+        let data = cff(&refs(&glyphs), &refs(&globals), &[&refs(&locals)], &[]);
+        let font = Cff::parse(&data).unwrap();
+        assert!(!font.is_cid());
+
+        let subset = font.subset(|glyph| glyph == 1);
+        let new = Cff::parse(&subset).unwrap();
+        assert!(new.is_cid());
+        assert_eq!(new.name(), Some("Test"));
+        assert_eq!(new.top.get(op::ROS), Some(vec![391.0, 392.0, 0.0]));
+        assert_eq!(new.strings, [b"Adobe".as_slice(), b"Identity"]);
+        assert_eq!(new.top.get(op::CID_COUNT), Some(vec![3.0]));
+        assert_eq!(at(&subset, &new, op::CHARSET)[..5], [2, 0, 1, 0, 1]);
+        assert_eq!(at(&subset, &new, op::FD_SELECT)[..8], [3, 0, 1, 0, 0, 0, 0, 3]);
+        assert_eq!(new.fd_select, [0, 0, 0]);
+
+        // The Top DICT entries of the name-keyed font stay in the Top DICT
+        // and the new Font DICT just points to the Private DICT.
+        assert_eq!(new.fonts.len(), 1);
+        assert_eq!(ops(&new.fonts[0].dict), [op::PRIVATE]);
+        assert_eq!(ops(&new.fonts[0].private), [op::SUBRS]);
+        assert_eq!(new.fonts[0].subrs, [locals[0].as_slice(), &[]]);
+        assert_eq!(new.global_subrs, [&[][..], globals[1].as_slice()]);
+        assert_eq!(new.char_strings, [&[14][..], &glyphs[1], &[14]]);
+
+        let [notdef, glyph, dropped] = outlines(&new).try_into().unwrap();
+        assert_eq!(notdef, []);
+        assert_eq!(glyph, outlines(&font)[1]);
+        assert_eq!(dropped, []);
+    }
+
+    #[test]
+    fn test_subset_cid_keyed() {
+        // Glyphs 1 and 2 use the second Font DICT and call its first and
+        // second subroutine. Glyph 3 uses the first Font DICT.
+        let mut subr = vec![];
+        push(&mut subr, &[100, 0], &[5, 11]);
+        let mut glyphs = [vec![14], vec![], vec![], vec![]];
+        for (glyph, index) in [(1, -107), (2, -106), (3, -107)] {
+            push(&mut glyphs[glyph], &[0, 0], &[21]);
+            push(&mut glyphs[glyph], &[index], &[10, 14]);
+        }
+
+        let locals: [&[&[u8]]; 2] = [&[&subr], &[&subr, &subr]];
+        let data = cff(&refs(&glyphs), &[], &locals, &[0, 1, 1, 0]);
+        let font = Cff::parse(&data).unwrap();
+        assert!(font.is_cid());
+
+        let subset = font.subset(|glyph| glyph == 1);
+        let new = Cff::parse(&subset).unwrap();
+        assert_eq!(new.top.get(op::ROS), Some(vec![391.0, 392.0, 0.0]));
+        assert_eq!(new.top.get(op::CID_COUNT), Some(vec![4.0]));
+        assert_eq!(at(&subset, &new, op::CHARSET)[..5], [2, 0, 1, 0, 2]);
+        assert_eq!(
+            at(&subset, &new, op::FD_SELECT)[..14],
+            [3, 0, 3, 0, 0, 0, 0, 1, 1, 0, 3, 0, 0, 4]
+        );
+        assert_eq!(new.fd_select, [0, 1, 1, 0]);
+
+        assert_eq!(new.fonts.len(), 2);
+        for font in &new.fonts {
+            assert_eq!(ops(&font.dict), [op::PRIVATE]);
+            assert_eq!(ops(&font.private), [op::SUBRS]);
+        }
+        assert_eq!(new.fonts[0].subrs, [&[][..]]);
+        assert_eq!(new.fonts[1].subrs, [subr.as_slice(), &[]]);
+        assert!(new.global_subrs.is_empty());
+        assert_eq!(outlines(&new)[1], outlines(&font)[1]);
+        assert_eq!(outlines(&new)[2..], [vec![], vec![]]);
+    }
+
+    #[test]
+    fn test_index_len() {
+        for items in [&[][..], &[b"".as_slice()], &[b"ab", b"c"], &[&[0; 300]]] {
+            let lengths: Vec<usize> = items.iter().map(|item| item.len()).collect();
+            assert_eq!(index_len(&lengths), write_index(items).len());
+        }
+    }
+}
@@ -370,7 +370,9 @@ impl Chunk {
     /// Start writing a character map stream.
     ///
     /// If you want to use this for a `/ToUnicode` CMap, you can create the
-    /// bytes using a [`UnicodeCmap`](types::UnicodeCmap) builder.
+    /// bytes using a [`UnicodeCmap`](types::UnicodeCmap) builder. Encoding
+    /// CMaps and CMaps with multiple codespace ranges can be created with a
+    /// [`CmapBuilder`](types::CmapBuilder).
     pub fn cmap<'a>(&'a mut self, id: Ref, cmap: &'a [u8]) -> Cmap<'a> {
         Cmap::start(self.stream(id, cmap))
     }
@@ -838,8 +838,8 @@ impl<'a> TilingPattern<'a> {
     ///
     /// Maps the pattern coordinate system to the parent content stream
     /// coordinates. The default is the identity matrix.
-    pub fn matrix(&mut self, matrix: [f32; 6]) -> &mut Self {
-        self.stream.insert(Name(b"Matrix")).array().items(matrix);
+    pub fn matrix(&mut self, matrix: impl Into<[f32; 6]>) -> &mut Self {
+        self.stream.insert(Name(b"Matrix")).array().items(matrix.into());
         self
     }
 }
@@ -914,8 +914,8 @@ impl ShadingPattern<'_> {
     /// Write the `/Matrix` attribute.
     ///
     /// Sets the matrix to use for the pattern. Defaults to the identity matrix.
-    pub fn matrix(&mut self, matrix: [f32; 6]) -> &mut Self {
-        self.dict.insert(Name(b"Matrix")).array().items(matrix);
+    pub fn matrix(&mut self, matrix: impl Into<[f32; 6]>) -> &mut Self {
+        self.dict.insert(Name(b"Matrix")).array().items(matrix.into());
         self
     }
 
@@ -996,8 +996,8 @@ impl FunctionShading<'_> {
     /// Maps the shading domain rectangle to the target coordinate system. Can
     /// be used for function shadings. Will otherwise
     /// default to the identity matrix.
-    pub fn matrix(&mut self, matrix: [f32; 6]) -> &mut Self {
-        self.dict.insert(Name(b"Matrix")).array().items(matrix);
+    pub fn matrix(&mut self, matrix: impl Into<[f32; 6]>) -> &mut Self {
+        self.dict.insert(Name(b"Matrix")).array().items(matrix.into());
         self
     }
 
@@ -1118,7 +1118,7 @@ impl<'a> StreamShading<'a> {
     /// Write the `/BitsPerFlag` attribute.
     ///
     /// Sets how many bits are used to represent the vertices' edge flags. Can
-    /// be 0, 1, or 2. Required for type 4, 6, and 7.
+    /// be 2, 4, or 8. Required for type 4, 6, and 7.
     pub fn bits_per_flag(&mut self, bits: i32) -> &mut Self {
         self.stream.pair(Name(b"BitsPerFlag"), bits);
         self
@@ -1300,7 +1300,9 @@ impl OutputIntent<'_> {
     /// identifier for the output condition.
     /// Must reference an [ICC profile](IccProfile) stream.
     ///
-    /// Required for PDF/A. The profile must have the `prtr` or `mntr` tag.
+    /// Required for PDF/A. The profile must have the `prtr` or `mntr` tag,
+    /// which [`IccInfo::validate_output_intent`](crate::IccInfo::validate_output_intent)
+    /// checks.
     pub fn dest_output_profile(&mut self, profile: Ref) -> &mut Self {
         self.dict.pair(Name(b"DestOutputProfile"), profile);
         self
@@ -0,0 +1,1065 @@
+use std::collections::HashMap;
+
+use super::*;
+use crate::outline::{outline_bbox, write_path, Outlines};
+use crate::reader::{read_u16, Reader};
+use crate::sfnt::Face;
+use crate::types::{BlendMode, FunctionShadingType};
+
+/// Converts the color glyphs of an OpenType font into Type 3 glyphs.
+///
+/// Color fonts describe glyphs as layers of outlines that are filled with
+/// colors from the `CPAL` table and, in version 1 of the `COLR` table, with
+/// gradients, transforms and blend modes. Since Type 0 fonts can't carry
+/// color, each such glyph is drawn into the content stream of a Type 3 glyph
+/// in a [`Type3Builder`]. The glyph outlines come from the font's `glyf`,
+/// `CFF ` or `CFF2` table.
+///
+/// Linear and radial gradients become axial and radial shadings with
+/// exponential and stitching functions. Sweep gradients become function-based
+/// shadings with a PostScript calculator function. Gradients whose stops have
+/// different alpha values are drawn opaque, since shadings can't vary the
+/// alpha. The Porter-Duff composite modes, except for those that only show
+/// the source or the backdrop, are drawn like normal source-over compositing.
+/// Variable fonts are drawn in their default instance.
+///
+/// Layers with the foreground color don't set a color, so they are drawn in
+/// the fill color that is current when the text is shown. In gradients, the
+/// foreground color is black.
+///
+/// ```no_run
+/// use pdf_writer::{Chunk, ColorFont, Ref, Type3Builder};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("Emoji.ttf")?;
+/// let mut font = ColorFont::new(&data, 0)?;
+/// let mut builder = Type3Builder::new();
+/// let mut chunk = Chunk::new();
+/// let mut alloc = Ref::new(1);
+///
+/// let glyph = font.glyph_id('🦀').unwrap();
+/// let (font_id, code) = font
+///     .add_glyph(&mut builder, &mut chunk, &mut alloc, glyph, Some("🦀"))
+///     .unwrap();
+///
+/// builder.write(&mut chunk, &mut alloc);
+/// # Ok(())
+/// # }
+/// ```
+pub struct ColorFont<'a> {
+    face: Face<'a>,
+    colr: Colr<'a>,
+    /// The external graphics states written so far by alpha in thousandths
+    /// and blend mode.
+    states: HashMap<(u16, Option<BlendMode>), Ref>,
+}
+
+/// The parsed `COLR` and `CPAL` tables along with the outlines.
+struct Colr<'a> {
+    data: &'a [u8],
+    outlines: Outlines<'a>,
+    /// The version 0 base glyph and layer records.
+    base_records: &'a [u8],
+    layer_records: &'a [u8],
+    /// The absolute offsets of the version 1 base glyph and layer lists.
+    base_list: Option<usize>,
+    layer_list: Option<usize>,
+    cpal: &'a [u8],
+    /// The colors of the selected palette as RGBA.
+    palette: Vec<[f32; 4]>,
+    /// The factor from font units to thousandths of an em.
+    scale: f32,
+}
+
+/// A color that is either from the palette or the foreground color.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Color {
+    rgb: Option<[f32; 3]>,
+    alpha: f32,
+}
+
+/// The color stops of a gradient, sorted by offset.
+#[derive(Debug, Clone)]
+struct ColorLine {
+    /// 0 for pad, 1 for repeat and 2 for reflect.
+    extend: u8,
+    stops: Vec<(f32, Color)>,
+}
+
+impl<'a> ColorFont<'a> {
+    /// Parse a font with `COLR` and `CPAL` tables.
+    ///
+    /// For font collections, `index` selects the face. For all other fonts, it
+    /// must be zero.
+    pub fn new(data: &'a [u8], index: u32) -> Result<Self, FontError> {
+        let face = Face::parse(data, index)?;
+        let colr = Colr::parse(&face)?;
+        Ok(Self { face, colr, states: HashMap::new() })
+    }
+
+    /// The number of palettes in the `CPAL` table.
+    pub fn num_palettes(&self) -> u16 {
+        read_u16(self.colr.cpal, 4).unwrap_or(0)
+    }
+
+    /// Select the palette that glyphs are drawn with. By default, the first
+    /// palette is used.
+    ///
+    /// Panics if the font has no palette with this index.
+    pub fn palette(&mut self, index: u16) -> &mut Self {
+        assert!(index < self.num_palettes(), "palette {index} is out of bounds");
+        self.colr.palette = read_palette(self.colr.cpal, index).unwrap_or_default();
+        self
+    }
+
+    /// The glyph for a character according to the font's `cmap` table.
+    pub fn glyph_id(&self, c: char) -> Option<u16> {
+        self.face.glyph_id(c)
+    }
+
+    /// Whether the font has a color description for a glyph.
+    pub fn has_color_glyph(&self, glyph: u16) -> bool {
+        self.colr.base_paint(glyph).is_some() || self.colr.base_layers(glyph).is_some()
+    }
+
+    /// Add a color glyph to a Type 3 font builder and return the font and
+    /// character code to show it with.
+    ///
+    /// Shadings, functions and graphics states that the glyph needs are
+    /// written to `chunk` with IDs from `alloc` and registered in the
+    /// builder's [resources](Type3Builder::resources). The `text` is what the
+    /// glyph stands for.
+    ///
+    /// Returns `None` if the font has no color description for the glyph or
+    /// if it is malformed.
+    pub fn add_glyph(
+        &mut self,
+        builder: &mut Type3Builder,
+        chunk: &mut Chunk,
+        alloc: &mut Ref,
+        glyph: u16,
+        text: Option<&str>,
+    ) -> Option<(Ref, u8)> {
+        let scale = self.colr.scale;
+        let root = Transform::scale(scale, scale);
+        let mut painter = Painter {
+            colr: &self.colr,
+            states: &mut self.states,
+            chunk,
+            alloc,
+            resources: builder.resources(),
+            content: Content::new(),
+            ctm: root,
+            clip: None,
+            bbox: None,
+            visiting: vec![],
+        };
+
+        if scale != 1.0 {
+            painter.content.transform(root);
+        }
+        if let Some(offset) = self.colr.base_paint(glyph) {
+            painter.paint(offset)?;
+        } else {
+            for (layer, palette_index) in self.colr.base_layers(glyph)? {
+                let color = self.colr.color(palette_index, 1.0);
+                painter.paint_glyph(layer, Fill::Solid(color))?;
+            }
+        }
+
+        let [x1, y1, x2, y2] = painter.bbox.unwrap_or_default();
+        let content = painter.content;
+        let advance = self.face.advance(glyph).map_or(0.0, |a| f32::from(a) * scale);
+        let bbox = Rect::new(x1, y1, x2, y2);
+        Some(builder.add_color_glyph(alloc, content, advance, bbox, text))
+    }
+}
+
+impl<'a> Colr<'a> {
+    fn parse(face: &Face<'a>) -> Result<Self, FontError> {
+        let data = face.require(b"COLR")?;
+        let cpal = face.require(b"CPAL")?;
+        let malformed = FontError::MalformedTable("COLR");
+        let mut r = Reader::new(data);
+        let version = r.u16().ok_or(malformed)?;
+        if version > 1 {
+            return Err(FontError::Unsupported("COLR version"));
+        }
+
+        let num_base = usize::from(r.u16().ok_or(malformed)?);
+        let base_offset = r.u32().ok_or(malformed)? as usize;
+        let layer_offset = r.u32().ok_or(malformed)? as usize;
+        let num_layers = usize::from(r.u16().ok_or(malformed)?);
+        let records = |offset: usize, len: usize| {
+            data.get(offset..offset + len)
+                .ok_or(FontError::MalformedTable("COLR"))
+        };
+        let base_records = records(base_offset, 6 * num_base)?;
+        let layer_records = records(layer_offset, 4 * num_layers)?;
+
+        let (mut base_list, mut layer_list) = (None, None);
+        if version == 1 {
+            let non_zero = |offset: u32| (offset > 0).then_some(offset as usize);
+            base_list = non_zero(r.u32().ok_or(malformed)?);
+            layer_list = non_zero(r.u32().ok_or(malformed)?);
+        }
+
+        Ok(Self {
+            data,
+            outlines: Outlines::parse(face)?,
+            base_records,
+            layer_records,
+            base_list,
+            layer_list,
+            cpal,
+            palette: read_palette(cpal, 0).ok_or(FontError::MalformedTable("CPAL"))?,
+            scale: face.to_em(1.0_f32),
+        })
+    }
+
+    /// The absolute offset of the root paint of a version 1 glyph.
+    fn base_paint(&self, glyph: u16) -> Option<usize> {
+        let list = self.base_list?;
+        let count = Reader::at(self.data, list).u32()? as usize;
+        let records = self.data.get(list + 4..list + 4 + 6 * count)?;
+        let i = binary_search(records, 6, glyph)?;
+        let offset = Reader::at(records, 6 * i + 2).u32()? as usize;
+        Some(list + offset)
+    }
+
+    /// The layers of a version 0 glyph as glyph IDs and palette indices.
+    fn base_layers(&self, glyph: u16) -> Option<impl Iterator<Item = (u16, u16)> + '_> {
+        let i = binary_search(self.base_records, 6, glyph)?;
+        let mut r = Reader::at(self.base_records, 6 * i + 2);
+        let first = usize::from(r.u16()?);
+        let count = usize::from(r.u16()?);
+        let layers = self.layer_records.get(4 * first..4 * (first + count))?;
+        Some(layers.chunks_exact(4).map(|layer| {
+            let mut r = Reader::new(layer);
+            (r.u16().unwrap(), r.u16().unwrap())
+        }))
+    }
+
+    /// The absolute offset of a paint in the layer list.
+    fn layer_paint(&self, index: usize) -> Option<usize> {
+        let list = self.layer_list?;
+        let count = Reader::at(self.data, list).u32()? as usize;
+        if index >= count {
+            return None;
+        }
+        let offset = Reader::at(self.data, list + 4 + 4 * index).u32()? as usize;
+        Some(list + offset)
+    }
+
+    /// A color from the palette, with the alpha multiplied by `alpha`.
+    fn color(&self, index: u16, alpha: f32) -> Color {
+        match self.palette.get(usize::from(index)) {
+            Some(&[r, g, b, a]) => Color { rgb: Some([r, g, b]), alpha: a * alpha },
+            None => Color { rgb: None, alpha },
+        }
+    }
+
+    /// Read a color line at an absolute offset.
+    fn color_line(&self, offset: usize, var: bool) -> Option<ColorLine> {
+        let mut r = Reader::at(self.data, offset);
+        let extend = r.u8()?;
+        let count = r.u16()?;
+        let mut stops = Vec::with_capacity(count.into());
+        for _ in 0..count {
+            let stop = f2dot14(&mut r)?;
+            let index = r.u16()?;
+            let alpha = f2dot14(&mut r)?;
+            if var {
+                r.skip(4)?;
+            }
+            stops.push((stop, self.color(index, alpha)));
+        }
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Some(ColorLine { extend, stops })
+    }
+}
+
+/// Read the colors of a palette from the `CPAL` table.
+fn read_palette(cpal: &[u8], index: u16) -> Option<Vec<[f32; 4]>> {
+    let mut r = Reader::new(cpal);
+    r.skip(2)?;
+    let entries = usize::from(r.u16()?);
+    let palettes = r.u16()?;
+    r.skip(2)?;
+    let records = r.u32()? as usize;
+    if index >= palettes {
+        return None;
+    }
+    let first = usize::from(read_u16(cpal, 12 + 2 * usize::from(index))?);
+    let start = records + 4 * first;
+    let colors = cpal.get(start..start + 4 * entries)?;
+    Some(
+        colors
+            .chunks_exact(4)
+            .map(|c| [c[2], c[1], c[0], c[3]].map(|v| f32::from(v) / 255.0))
+            .collect(),
+    )
+}
+
+/// Find the record for a glyph in records that start with a sorted glyph ID.
+fn binary_search(records: &[u8], size: usize, glyph: u16) -> Option<usize> {
+    let count = records.len() / size;
+    let (mut lo, mut hi) = (0, count);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        let other = read_u16(records, size * mid)?;
+        match other.cmp(&glyph) {
+            std::cmp::Ordering::Less => lo = mid + 1,
+            std::cmp::Ordering::Greater => hi = mid,
+            std::cmp::Ordering::Equal => return Some(mid),
+        }
+    }
+    None
+}
+
+fn f2dot14(r: &mut Reader) -> Option<f32> {
+    r.i16().map(|v| f32::from(v) / 16384.0)
+}
+
+/// What to fill a glyph outline with.
+enum Fill {
+    Solid(Color),
+    /// The paint at an absolute offset.
+    Paint(usize),
+}
+
+/// Draws the paint graph of a glyph into a content stream.
+struct Painter<'a, 'b> {
+    colr: &'b Colr<'a>,
+    states: &'b mut HashMap<(u16, Option<BlendMode>), Ref>,
+    chunk: &'b mut Chunk,
+    alloc: &'b mut Ref,
+    resources: &'b mut ResourceCollector,
+    content: Content,
+    /// The transform from the current space to glyph space.
+    ctm: Transform,
+    /// The bounding box of the current clip region in glyph space.
+    clip: Option<[f32; 4]>,
+    /// The bounding box of everything drawn so far in glyph space.
+    bbox: Option<[f32; 4]>,
+    /// The paints that are currently being drawn, to detect cycles.
+    visiting: Vec<usize>,
+}
+
+impl Painter<'_, '_> {
+    /// Draw the paint at an absolute offset.
+    fn paint(&mut self, offset: usize) -> Option<()> {
+        // Cycles are malformed, but simply not drawing them is friendlier.
+        if self.visiting.contains(&offset) || self.visiting.len() >= 64 {
+            return Some(());
+        }
+
+        self.visiting.push(offset);
+        let result = self.paint_impl(offset);
+        self.visiting.pop();
+        result
+    }
+
+    fn paint_impl(&mut self, offset: usize) -> Option<()> {
+        let colr = self.colr;
+        let mut r = Reader::at(colr.data, offset);
+        let format = r.u8()?;
+        let child = |r: &mut Reader| r.u24().map(|o| offset + o as usize);
+        match format {
+            // PaintColrLayers
+            1 => {
+                let count = usize::from(r.u8()?);
+                let first = r.u32()? as usize;
+                for i in first..first + count {
+                    self.paint(colr.layer_paint(i)?)?;
+                }
+            }
+            // PaintSolid
+            2 | 3 => {
+                let index = r.u16()?;
+                let color = colr.color(index, f2dot14(&mut r)?);
+                self.fill_clip(color);
+            }
+            // PaintLinearGradient
+            4 | 5 => {
+                let line = colr.color_line(child(&mut r)?, format == 5)?;
+                let mut p = [0.0; 6];
+                for v in &mut p {
+                    *v = f32::from(r.i16()?);
+                }
+                self.linear_gradient(&line, p);
+            }
+            // PaintRadialGradient
+            6 | 7 => {
+                let line = colr.color_line(child(&mut r)?, format == 7)?;
+                let (x0, y0, r0) = (r.i16()?, r.i16()?, r.u16()?);
+                let (x1, y1, r1) = (r.i16()?, r.i16()?, r.u16()?);
+                let c0 = [f32::from(x0), f32::from(y0), f32::from(r0)];
+                let c1 = [f32::from(x1), f32::from(y1), f32::from(r1)];
+                self.radial_gradient(&line, c0, c1);
+            }
+            // PaintSweepGradient
+            8 | 9 => {
+                let line = colr.color_line(child(&mut r)?, format == 9)?;
+                let center = (f32::from(r.i16()?), f32::from(r.i16()?));
+                let angles = (f2dot14(&mut r)? * 180.0, f2dot14(&mut r)? * 180.0);
+                self.sweep_gradient(&line, center, angles);
+            }
+            // PaintGlyph
+            10 => {
+                let paint = child(&mut r)?;
+                let glyph = r.u16()?;
+                self.paint_glyph(glyph, Fill::Paint(paint))?;
+            }
+            // PaintColrGlyph
+            11 => {
+                let glyph = r.u16()?;
+                self.paint(colr.base_paint(glyph)?)?;
+            }
+            // PaintTransform
+            12 | 13 => {
+                let paint = child(&mut r)?;
+                let mut t = Reader::at(colr.data, child(&mut r)?);
+                let mut m = [0.0; 6];
+                for v in &mut m {
+                    *v = t.i32()? as f32 / 65536.0;
+                }
+                self.transform(m.into(), paint)?;
+            }
+            // PaintTranslate
+            14 | 15 => {
+                let paint = child(&mut r)?;
+                let (dx, dy) = (f32::from(r.i16()?), f32::from(r.i16()?));
+                self.transform(Transform::translate(dx, dy), paint)?;
+            }
+            // PaintScale and its variants
+            16..=23 => {
+                let paint = child(&mut r)?;
+                let sx = f2dot14(&mut r)?;
+                let sy = if format < 20 { f2dot14(&mut r)? } else { sx };
+                let m = Transform::scale(sx, sy);
+                self.transform(
+                    around_center(m, &mut r, matches!(format, 18 | 19 | 22 | 23))?,
+                    paint,
+                )?;
+            }
+            // PaintRotate and PaintRotateAroundCenter
+            24..=27 => {
+                let paint = child(&mut r)?;
+                let angle = f2dot14(&mut r)? * std::f32::consts::PI;
+                let m = Transform::rotate_radians(angle);
+                self.transform(around_center(m, &mut r, format >= 26)?, paint)?;
+            }
+            // PaintSkew and PaintSkewAroundCenter
+            28..=31 => {
+                let paint = child(&mut r)?;
+                let x = (f2dot14(&mut r)? * std::f32::consts::PI).tan();
+                let y = (f2dot14(&mut r)? * std::f32::consts::PI).tan();
+                let m = Transform::new(1.0, y, -x, 1.0, 0.0, 0.0);
+                self.transform(around_center(m, &mut r, format >= 30)?, paint)?;
+            }
+            // PaintComposite
+            32 => {
+                let source = child(&mut r)?;
+                let mode = r.u8()?;
+                let backdrop = child(&mut r)?;
+                self.composite(source, mode, backdrop)?;
+            }
+            _ => {}
+        }
+        Some(())
+    }
+
+    /// Draw a paint with an additional transform.
+    fn transform(&mut self, m: Transform, paint: usize) -> Option<()> {
+        let ctm = self.ctm;
+        self.content.save_state();
+        self.content.transform(m);
+        self.ctm = m.then(ctm);
+        let result = self.paint(paint);
+        self.ctm = ctm;
+        self.content.restore_state();
+        result
+    }
+
+    /// Fill the outline of a glyph.
+    fn paint_glyph(&mut self, glyph: u16, fill: Fill) -> Option<()> {
+        let segments = self.colr.outlines.outline(glyph)?;
+        let Some(bbox) = outline_bbox(&segments, self.ctm) else {
+            return Some(());
+        };
+        // A glyph outside of the clip region isn't visible.
+        let clip = match self.clip {
+            Some(clip) => match intersect(clip, bbox) {
+                Some(clip) => clip,
+                None => return Some(()),
+            },
+            None => bbox,
+        };
+        self.bbox = Some(self.bbox.map_or(clip, |b| union(b, clip)));
+
+        // Solid fills don't need a clip path.
+        let fill = match fill {
+            Fill::Paint(offset) if matches!(self.colr.data.get(offset), Some(2 | 3)) => {
+                let mut r = Reader::at(self.colr.data, offset + 1);
+                let index = r.u16()?;
+                Fill::Solid(self.colr.color(index, f2dot14(&mut r)?))
+            }
+            fill => fill,
+        };
+
+        self.content.save_state();
+        match fill {
+            Fill::Solid(color) => {
+                self.set_color(color);
+                write_path(&mut self.content, &segments);
+                self.content.fill_nonzero();
+            }
+            Fill::Paint(offset) => {
+                write_path(&mut self.content, &segments);
+                self.content.clip_nonzero().end_path();
+                let prev = self.clip.replace(clip);
+                self.paint(offset)?;
+                self.clip = prev;
+            }
+        }
+        self.content.restore_state();
+        Some(())
+    }
+
+    /// Fill the whole clip region with a color.
+    fn fill_clip(&mut self, color: Color) {
+        let Some([x1, y1, x2, y2]) = self.local_clip() else { return };
+        self.content.save_state();
+        self.set_color(color);
+        self.content.rect(x1, y1, x2 - x1, y2 - y1).fill_nonzero();
+        self.content.restore_state();
+    }
+
+    /// Draw a linear gradient from three points.
+    fn linear_gradient(&mut self, line: &ColorLine, p: [f32; 6]) {
+        let [x0, y0, x1, y1, x2, y2] = p;
+
+        // The color lines are parallel to the line from p0 to p2, so the
+        // gradient goes along its perpendicular.
+        let (nx, ny) = (y2 - y0, x0 - x2);
+        let (dx, dy) = if nx == 0.0 && ny == 0.0 {
+            (x1 - x0, y1 - y0)
+        } else {
+            let k = ((x1 - x0) * nx + (y1 - y0) * ny) / (nx * nx + ny * ny);
+            (k * nx, k * ny)
+        };
+
+        let Some(([s0, s1], alpha)) = self.check_line(line) else { return };
+        let start = (x0 + s0 * dx, y0 + s0 * dy);
+        let (vx, vy) = ((s1 - s0) * dx, (s1 - s0) * dy);
+        let len = vx * vx + vy * vy;
+        if len < 1e-6 {
+            return self.fill_clip(line.stops[line.stops.len() - 1].1);
+        }
+
+        // The range of the gradient parameter within the clip region.
+        let range = self.local_clip().map(|[cx1, cy1, cx2, cy2]| {
+            [(cx1, cy1), (cx2, cy1), (cx1, cy2), (cx2, cy2)]
+                .map(|(x, y)| ((x - start.0) * vx + (y - start.1) * vy) / len)
+        });
+        let (lo, hi, function) = self.gradient_function(line, range);
+        let coords =
+            [start.0 + lo * vx, start.1 + lo * vy, start.0 + hi * vx, start.1 + hi * vy];
+        self.shade(FunctionShadingType::Axial, &coords, [lo, hi], function, alpha);
+    }
+
+    /// Draw a two-point conical gradient.
+    fn radial_gradient(&mut self, line: &ColorLine, c0: [f32; 3], c1: [f32; 3]) {
+        let Some(([s0, s1], alpha)) = self.check_line(line) else { return };
+        let circle = |t: f32| {
+            let s = s0 + t * (s1 - s0);
+            [0, 1, 2].map(|i| c0[i] + s * (c1[i] - c0[i]))
+        };
+
+        // How many repetitions are needed to cover the clip region. Circles
+        // can't have a negative radius.
+        let range = self.local_clip().map(|[cx1, cy1, cx2, cy2]| {
+            let covers = |[x, y, r]: [f32; 3]| {
+                [(cx1, cy1), (cx2, cy1), (cx1, cy2), (cx2, cy2)]
+                    .iter()
+                    .all(|&(px, py)| (px - x).hypot(py - y) <= r)
+            };
+            let hi = (1..64).find(|&t| covers(circle(t as f32))).unwrap_or(64);
+            let lo = (0..64).find(|&t| circle(-(t as f32) - 1.0)[2] < 0.0).unwrap_or(64);
+            [-(lo as f32), 0.0, 1.0, hi as f32]
+        });
+
+        let (lo, hi, function) = self.gradient_function(line, range);
+        let [x0, y0, r0] = circle(lo);
+        let [x1, y1, r1] = circle(hi);
+        let coords = [x0, y0, r0.max(0.0), x1, y1, r1.max(0.0)];
+        self.shade(FunctionShadingType::Radial, &coords, [lo, hi], function, alpha);
+    }
+
+    /// Draw a sweep gradient around a center between two angles in degrees.
+    fn sweep_gradient(
+        &mut self,
+        line: &ColorLine,
+        center: (f32, f32),
+        angles: (f32, f32),
+    ) {
+        let Some(([s0, s1], alpha)) = self.check_line(line) else { return };
+        let Some([x1, y1, x2, y2]) = self.local_clip() else { return };
+        let start = angles.0 + s0 * (angles.1 - angles.0);
+        let span = (s1 - s0) * (angles.1 - angles.0);
+        if span.abs() < 1e-6 {
+            return self.fill_clip(line.stops[line.stops.len() - 1].1);
+        }
+
+        // The function maps a point to an angle, then to the position on the
+        // color line and finally to a color.
+        let mut code = Buf::new();
+        code.extend(b"{ ");
+        push_ops(&mut code, &[center.1], "sub exch");
+        push_ops(&mut code, &[center.0], "sub atan");
+        if start < 0.0 {
+            code.extend(b"dup ");
+            push_ops(&mut code, &[start + 360.0], "ge { 360 sub } if");
+        }
+        push_ops(&mut code, &[start], "sub");
+        push_ops(&mut code, &[span], "div");
+        push_extend(&mut code, line.extend);
+        push_color_ramp(&mut code, &stops_in(line, s0, s1));
+        code.push(b'}');
+
+        let function = self.alloc.bump();
+        self.chunk
+            .post_script_function(function, code.as_slice())
+            .domain([x1, x2, y1, y2])
+            .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+
+        let id = self.alloc.bump();
+        let mut shading = self.chunk.function_shading(id);
+        shading.shading_type(FunctionShadingType::Function);
+        shading.color_space().device_rgb();
+        shading.domain([x1, x2, y1, y2]).function(function);
+        shading.finish();
+        self.draw_shading(id, alpha);
+    }
+
+    /// Check that a color line has stops and return the range of their
+    /// offsets and the common alpha of all stops.
+    fn check_line(&mut self, line: &ColorLine) -> Option<([f32; 2], f32)> {
+        let first = line.stops.first()?;
+        let last = line.stops.last()?;
+        if last.0 - first.0 < 1e-6 {
+            self.fill_clip(last.1);
+            return None;
+        }
+
+        let alpha = first.1.alpha;
+        let uniform = line.stops.iter().all(|(_, color)| color.alpha == alpha);
+        Some(([first.0, last.0], if uniform { alpha } else { 1.0 }))
+    }
+
+    /// Write the function for the colors of a gradient.
+    ///
+    /// For repeating and reflecting gradients, the color line is repeated to
+    /// cover the parameter range of the clip region. Returns the parameter
+    /// range covered by the function.
+    fn gradient_function(
+        &mut self,
+        line: &ColorLine,
+        range: Option<[f32; 4]>,
+    ) -> (f32, f32, Ref) {
+        let [s0, s1] = [line.stops[0].0, line.stops[line.stops.len() - 1].0];
+        let stops = stops_in(line, s0, s1);
+
+        // One exponential function per pair of neighbouring stops with
+        // different offsets, stitched together.
+        let mut functions = vec![];
+        let mut bounds = vec![];
+        for pair in stops.windows(2) {
+            let ((t0, c0), (t1, c1)) = (pair[0], pair[1]);
+            if t1 - t0 < 1e-6 {
+                continue;
+            }
+            if !functions.is_empty() {
+                bounds.push(t0);
+            }
+            let id = self.alloc.bump();
+            self.chunk
+                .exponential_function(id)
+                .domain([0.0, 1.0])
+                .c0(c0)
+                .c1(c1)
+                .n(1.0);
+            functions.push(id);
+        }
+
+        let base = if functions.len() == 1 {
+            functions[0]
+        } else {
+            let id = self.alloc.bump();
+            let encode: Vec<f32> = functions.iter().flat_map(|_| [0.0, 1.0]).collect();
+            self.chunk
+                .stitching_function(id)
+                .domain([0.0, 1.0])
+                .functions(functions)
+                .bounds(bounds)
+                .encode(encode);
+            id
+        };
+
+        let Some(range) = range.filter(|_| line.extend != 0) else {
+            return (0.0, 1.0, base);
+        };
+
+        let lo = range.iter().fold(0.0_f32, |a, &b| a.min(b)).floor().max(-64.0);
+        let hi = range.iter().fold(1.0_f32, |a, &b| a.max(b)).ceil().min(64.0);
+        let periods = (lo as i32..hi as i32).collect::<Vec<_>>();
+        let encode: Vec<f32> = periods
+            .iter()
+            .flat_map(|&p| {
+                if line.extend == 2 && p.rem_euclid(2) == 1 {
+                    [1.0, 0.0]
+                } else {
+                    [0.0, 1.0]
+                }
+            })
+            .collect();
+
+        let id = self.alloc.bump();
+        self.chunk
+            .stitching_function(id)
+            .domain([lo, hi])
+            .functions(periods.iter().map(|_| base))
+            .bounds(periods.iter().skip(1).map(|&p| p as f32))
+            .encode(encode);
+        (lo, hi, id)
+    }
+
+    /// Write an axial or radial shading and draw it.
+    fn shade(
+        &mut self,
+        kind: FunctionShadingType,
+        coords: &[f32],
+        domain: [f32; 2],
+        function: Ref,
+        alpha: f32,
+    ) {
+        let id = self.alloc.bump();
+        let mut shading = self.chunk.function_shading(id);
+        shading.shading_type(kind);
+        shading.color_space().device_rgb();
+        shading
+            .coords(coords.iter().copied())
+            .function(function)
+            .extend([true, true]);
+        shading.insert(Name(b"Domain")).array().items(domain);
+        shading.finish();
+        self.draw_shading(id, alpha);
+    }
+
+    /// Paint a shading over the clip region.
+    fn draw_shading(&mut self, id: Ref, alpha: f32) {
+        if self.clip.is_none() {
+            return;
+        }
+
+        self.content.save_state();
+        if alpha < 1.0 {
+            self.set_state(alpha, None);
+        }
+        let name = self.resources.shading(id);
+        self.content.shading(name);
+        self.content.restore_state();
+    }
+
+    /// Compose a source paint with a backdrop paint.
+    fn composite(&mut self, source: usize, mode: u8, backdrop: usize) -> Option<()> {
+        let blend = match mode {
+            // Clear
+            0 => return Some(()),
+            // Source
+            1 => return self.paint(source),
+            // Destination
+            2 => return self.paint(backdrop),
+            // Destination over
+            4 => {
+                self.paint(source)?;
+                return self.paint(backdrop);
+            }
+            13 => BlendMode::Screen,
+            14 => BlendMode::Overlay,
+            15 => BlendMode::Darken,
+            16 => BlendMode::Lighten,
+            17 => BlendMode::ColorDodge,
+            18 => BlendMode::ColorBurn,
+            19 => BlendMode::HardLight,
+            20 => BlendMode::SoftLight,
+            21 => BlendMode::Difference,
+            22 => BlendMode::Exclusion,
+            23 => BlendMode::Multiply,
+            24 => BlendMode::Hue,
+            25 => BlendMode::Saturation,
+            26 => BlendMode::Color,
+            27 => BlendMode::Luminosity,
+            _ => BlendMode::Normal,
+        };
+
+        self.paint(backdrop)?;
+        self.content.save_state();
+        if blend != BlendMode::Normal {
+            self.set_state(1.0, Some(blend));
+        }
+        let result = self.paint(source);
+        self.content.restore_state();
+        result
+    }
+
+    /// Set the fill color and alpha.
+    fn set_color(&mut self, color: Color) {
+        if color.alpha < 1.0 {
+            self.set_state(color.alpha, None);
+        }
+        if let Some([r, g, b]) = color.rgb {
+            self.content.set_fill_rgb(r, g, b);
+        }
+    }
+
+    /// Set an external graphics state with a fill alpha and blend mode.
+    fn set_state(&mut self, alpha: f32, blend: Option<BlendMode>) {
+        let key = ((alpha.clamp(0.0, 1.0) * 1000.0).round() as u16, blend);
+        let id = *self.states.entry(key).or_insert_with(|| {
+            let id = self.alloc.bump();
+            let mut state = self.chunk.ext_graphics(id);
+            if key.0 < 1000 {
+                state.non_stroking_alpha(f32::from(key.0) / 1000.0);
+            }
+            if let Some(blend) = blend {
+                state.blend_mode(blend);
+            }
+            id
+        });
+        let name = self.resources.ext_g_state(id);
+        self.content.set_parameters(name);
+    }
+
+    /// The bounding box of the clip region in the current space.
+    fn local_clip(&self) -> Option<[f32; 4]> {
+        let [x1, y1, x2, y2] = self.clip?;
+        let bbox = self.ctm.invert()?.map_bbox(Rect::new(x1, y1, x2, y2));
+        Some([bbox.x1, bbox.y1, bbox.x2, bbox.y2])
+    }
+}
+
+/// Apply a transform around a center point if the paint has one.
+fn around_center(m: Transform, r: &mut Reader, centered: bool) -> Option<Transform> {
+    if !centered {
+        return Some(m);
+    }
+    let (cx, cy) = (f32::from(r.i16()?), f32::from(r.i16()?));
+    let m = Transform::translate(-cx, -cy).then(m);
+    Some(m.then(Transform::translate(cx, cy)))
+}
+
+/// The stops of a color line with offsets mapped from `s0..s1` to `0..1` and
+/// colors as RGB.
+fn stops_in(line: &ColorLine, s0: f32, s1: f32) -> Vec<(f32, [f32; 3])> {
+    line.stops
+        .iter()
+        .map(|&(t, color)| ((t - s0) / (s1 - s0), color.rgb.unwrap_or_default()))
+        .collect()
+}
+
+/// Write numbers followed by PostScript operators.
+fn push_ops(code: &mut Buf, numbers: &[f32], ops: &str) {
+    for &number in numbers {
+        code.push_float(number);
+        code.push(b' ');
+    }
+    if !ops.is_empty() {
+        code.extend(ops.as_bytes());
+        code.push(b' ');
+    }
+}
+
+/// Write PostScript code that maps the value on the stack into `0..1`
+/// according to a color line's extend mode.
+fn push_extend(code: &mut Buf, extend: u8) {
+    code.extend(match extend {
+        1 => b"dup floor sub ".as_slice(),
+        2 => b"dup 2 div floor 2 mul sub dup 1 gt { 2 exch sub } if ".as_slice(),
+        _ => b"dup 0 lt { pop 0 } if dup 1 gt { pop 1 } if ".as_slice(),
+    });
+}
+
+/// Write PostScript code that maps the value on the stack to the color of a
+/// piecewise linear ramp through the stops.
+fn push_color_ramp(code: &mut Buf, stops: &[(f32, [f32; 3])]) {
+    let segments: Vec<_> =
+        stops.windows(2).filter(|pair| pair[1].0 > pair[0].0).collect();
+    let last = stops[stops.len() - 1].1;
+    for pair in &segments {
+        let ((t0, c0), (t1, c1)) = (pair[0], pair[1]);
+        code.extend(b"dup ");
+        push_ops(code, &[t1], "le {");
+        push_ops(code, &[t0], "sub");
+        push_ops(code, &[t1 - t0], "div");
+        for i in 0..3 {
+            if i < 2 {
+                code.extend(b"dup ");
+            }
+            push_ops(code, &[c1[i] - c0[i]], "mul");
+            push_ops(code, &[c0[i]], "add");
+            if i < 2 {
+                code.extend(b"exch ");
+            }
+        }
+        code.extend(b"} { ");
+    }
+    code.extend(b"pop ");
+    push_ops(code, &last, "");
+    for _ in &segments {
+        code.extend(b"} ifelse ");
+    }
+}
+
+fn intersect(a: [f32; 4], b: [f32; 4]) -> Option<[f32; 4]> {
+    let r = [a[0].max(b[0]), a[1].max(b[1]), a[2].min(b[2]), a[3].min(b[3])];
+    (r[0] <= r[2] && r[1] <= r[3]).then_some(r)
+}
+
+fn union(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    [a[0].min(b[0]), a[1].min(b[1]), a[2].max(b[2]), a[3].max(b[3])]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sfnt::fixture::{be, truetype_font};
+
+    /// A font whose glyph 1 is a rectangle, glyph 2 is a COLR v0 glyph with two
+    /// rectangle layers and glyph 3 is a rectangle with a linear gradient.
+    fn test_font() -> Vec<u8> {
+        let mut colr = be(&[1, 1], 2);
+        colr.extend(be(&[34, 40], 4));
+        colr.extend(be(&[2], 2));
+        colr.extend(be(&[48, 0, 0, 0, 0], 4));
+        colr.extend(be(&[2, 0, 2, 1, 0, 1, 0xFFFF], 2));
+        colr.extend(be(&[1], 4));
+        colr.extend(be(&[3], 2));
+        colr.extend(be(&[10], 4));
+        colr.extend([10, 0, 0, 6, 0, 1]);
+        colr.extend([4, 0, 0, 16]);
+        colr.extend(be(&[0, 0, 600, 0, 0, 700], 2));
+        colr.push(0);
+        colr.extend(be(&[2, 0, 0, 0x4000, 0x4000, 1, 0x4000], 2));
+        font_with_colr(&colr)
+    }
+
+    /// A font with the given `COLR` table, a rectangle as glyph 1 and a
+    /// palette with red and blue.
+    fn font_with_colr(colr: &[u8]) -> Vec<u8> {
+        let mut glyf = be(&[1, 0, 0, 600, 700, 3, 0], 2);
+        glyf.extend([1, 1, 1, 1]);
+        glyf.extend(be(&[0, 600, 0, -600, 0, 0, 700, 0], 2));
+        glyf.extend([0, 0]);
+        let loca = be(&[0, 0, 18, 18, 18], 2);
+
+        let mut cpal = be(&[0, 2, 1, 2], 2);
+        cpal.extend(be(&[14], 4));
+        cpal.extend(be(&[0], 2));
+        cpal.extend([0, 0, 255, 255, 255, 0, 0, 128]);
+
+        truetype_font(
+            [500, 600, 700],
+            &glyf,
+            &loca,
+            &[(*b"COLR", colr), (*b"CPAL", &cpal)],
+        )
+    }
+
+    #[test]
+    fn test_color_font_layers() {
+        let data = test_font();
+        let mut font = ColorFont::new(&data, 0).unwrap();
+        assert_eq!(font.num_palettes(), 1);
+        assert!(font.has_color_glyph(2));
+        assert!(font.has_color_glyph(3));
+        assert!(!font.has_color_glyph(1));
+
+        let mut builder = Type3Builder::new();
+        let mut chunk = Chunk::new();
+        let mut alloc = Ref::new(1);
+        assert_eq!(font.add_glyph(&mut builder, &mut chunk, &mut alloc, 1, None), None);
+        let glyph = font.add_glyph(&mut builder, &mut chunk, &mut alloc, 2, Some("A"));
+        assert_eq!(glyph, Some((Ref::new(1), 0)));
+        builder.write(&mut chunk, &mut alloc);
+
+        let output = String::from_utf8_lossy(chunk.as_bytes());
+        let rect = "0 0 m\n600 0 l\n600 700 l\n0 700 l\nh\nf\n";
+        assert!(output.contains("/Widths [700]"));
+        assert!(output.contains(&format!("700 0 d0\nq\n1 0 0 rg\n{rect}Q\nq\n{rect}Q\n")));
+    }
+
+    #[test]
+    fn test_color_font_gradient() {
+        let data = test_font();
+        let mut font = ColorFont::new(&data, 0).unwrap();
+        let mut builder = Type3Builder::new();
+        let mut chunk = Chunk::new();
+        let mut alloc = Ref::new(1);
+        let glyph = font.add_glyph(&mut builder, &mut chunk, &mut alloc, 3, None);
+        assert_eq!(glyph, Some((Ref::new(3), 0)));
+        builder.write(&mut chunk, &mut alloc);
+
+        let output = String::from_utf8_lossy(chunk.as_bytes());
+        assert!(output.contains("/C0 [1 0 0]\n  /C1 [0 0 1]"));
+        assert!(output.contains("/ShadingType 2"));
+        assert!(output.contains("/Coords [0 0 600 0]"));
+        assert!(output.contains("/Shading <<\n      /Sh1 2 0 R"));
+        assert!(output.contains("0 700 l\nh\nW\nn\nq\n/Sh1 sh\nQ\nQ\n"));
+    }
+
+    #[test]
+    fn test_color_font_glyph_outside_clip() {
+        // Glyph 2 clips to the rectangle and paints it again shifted by 1000
+        // units, which is entirely outside of the clip.
+        let mut colr = be(&[1, 0], 2);
+        colr.extend(be(&[0, 0], 4));
+        colr.extend(be(&[0], 2));
+        colr.extend(be(&[34, 0, 0, 0, 0], 4));
+        colr.extend(be(&[1], 4));
+        colr.extend(be(&[2], 2));
+        colr.extend(be(&[10], 4));
+        colr.extend([10, 0, 0, 6, 0, 1]);
+        colr.extend([14, 0, 0, 8]);
+        colr.extend(be(&[1000, 0], 2));
+        colr.extend([10, 0, 0, 6, 0, 1]);
+        colr.extend([2, 0, 0, 0x40, 0]);
+
+        let data = font_with_colr(&colr);
+        let mut font = ColorFont::new(&data, 0).unwrap();
+        let mut builder = Type3Builder::new();
+        let mut chunk = Chunk::new();
+        let mut alloc = Ref::new(1);
+        let glyph = font.add_glyph(&mut builder, &mut chunk, &mut alloc, 2, None);
+        assert_eq!(glyph, Some((Ref::new(1), 0)));
+        builder.write(&mut chunk, &mut alloc);
+
+        let output = String::from_utf8_lossy(chunk.as_bytes());
+        let rect = "0 0 m\n600 0 l\n600 700 l\n0 700 l\nh\n";
+        assert!(output.contains("/FontBBox [0 0 600 700]"));
+        assert!(output.contains(&format!("{rect}W\nn\nq\n1 0 0 1 1000 0 cm\nQ\nQ\n")));
+    }
+
+    #[test]
+    fn test_sweep_function() {
+        let mut code = Buf::new();
+        push_extend(&mut code, 1);
+        push_color_ramp(&mut code, &[(0.0, [1.0, 0.0, 0.0]), (1.0, [0.0, 0.0, 1.0])]);
+        assert_eq!(
+            std::str::from_utf8(code.as_slice()).unwrap(),
+            "dup floor sub dup 1 le { 0 sub 1 div dup -1 mul 1 add exch \
+             dup 0 mul 0 add exch 1 mul 0 add } { pop 0 0 1 } ifelse ",
+        );
+    }
+}
@@ -1,12 +1,16 @@
 use super::*;
 use crate::chunk::Settings;
 use crate::object::{is_delimiter_character, TextStrLike};
+use crate::validate::{Validator, Violation};
 
 /// A builder for a content stream.
 pub struct Content {
     buf: Buf,
     settings: Settings,
     q_depth: usize,
+    current_point: (f32, f32),
+    subpath_start: (f32, f32),
+    validator: Option<Box<Validator>>,
 }
 
 /// Core methods.
@@ -37,6 +41,9 @@ impl Content {
             buf: Buf::with_capacity(capacity),
             settings,
             q_depth: 0,
+            current_point: (0.0, 0.0),
+            subpath_start: (0.0, 0.0),
+            validator: None,
         }
     }
 
@@ -60,6 +67,9 @@ impl Content {
     /// Start writing an arbitrary operation.
     #[inline]
     pub fn op<'a>(&'a mut self, operator: &'a str) -> Operation<'a> {
+        if let Some(validator) = &mut self.validator {
+            validator.check(operator, self.buf.len());
+        }
         Operation::start(&mut self.buf, operator, self.settings)
     }
 
@@ -72,12 +82,75 @@ impl Content {
     ///   modes.
     /// - The actual underlying data of the content stream, which can be written
     ///   to a chunk (and optionally apply a filter before doing so).
+    ///
+    /// Panics if [validation](Self::enable_validation) is enabled and the
+    /// content stream is malformed. Use [`finish_checked`](Self::finish_checked)
+    /// to handle violations gracefully.
     pub fn finish(mut self) -> Buf {
+        if let Err(violations) = self.validate() {
+            let list: Vec<_> = violations.iter().map(ToString::to_string).collect();
+            panic!("malformed content stream: {}", list.join(", "));
+        }
+
         if self.buf.last() == Some(&b'\n') {
             self.buf.inner.pop();
         }
         self.buf
     }
+
+    /// Return the buffer of the content stream if it has no violations.
+    ///
+    /// Like [`finish`](Self::finish), but fails with all
+    /// [violations](Self::validate) if validation is enabled and the content
+    /// stream is malformed.
+    pub fn finish_checked(mut self) -> Result<Buf, Vec<Violation>> {
+        self.validate()?;
+        self.validator = None;
+        Ok(self.finish())
+    }
+}
+
+/// Validation.
+impl Content {
+    /// Start checking that the operations written from now on follow the
+    /// structure rules for content streams.
+    ///
+    /// This tracks the graphics object state machine from section 8.2 of the
+    /// PDF 1.7 specification and the nesting of `q`/`Q`, `BT`/`ET`,
+    /// `BMC`/`BDC`/`EMC` and `BX`/`EX`. Among other things, it detects path
+    /// operators inside of text objects, text operators outside of them and
+    /// restoring the graphics state at nesting depth zero. Operators written
+    /// through [`op`](Self::op) are checked as well, unknown operators are
+    /// ignored.
+    ///
+    /// Validation is off by default because it adds a bit of overhead to
+    /// every operation. Violations are reported by [`validate`](Self::validate),
+    /// [`finish`](Self::finish) and [`finish_checked`](Self::finish_checked).
+    pub fn enable_validation(&mut self) -> &mut Self {
+        if self.validator.is_none() {
+            self.validator = Some(Box::default());
+        }
+        self
+    }
+
+    /// Check the operations written so far, as if the content stream ended
+    /// here.
+    ///
+    /// Returns all violations in the order of their position in the stream.
+    /// Always succeeds if [validation](Self::enable_validation) is disabled.
+    pub fn validate(&self) -> Result<(), Vec<Violation>> {
+        match &self.validator {
+            Some(validator) => {
+                let violations = validator.violations();
+                if violations.is_empty() {
+                    Ok(())
+                } else {
+                    Err(violations)
+                }
+            }
+            None => Ok(()),
+        }
+    }
 }
 
 /// Writer for an _operation_ in a content stream.
@@ -334,18 +407,24 @@ impl Content {
     /// `cm`: Pre-concatenate the `matrix` with the current transformation
     /// matrix.
     #[inline]
-    pub fn transform(&mut self, matrix: [f32; 6]) -> &mut Self {
-        self.op("cm").operands(matrix);
+    pub fn transform(&mut self, matrix: impl Into<[f32; 6]>) -> &mut Self {
+        self.op("cm").operands(matrix.into());
         self
     }
 }
 
 /// Path construction.
+///
+/// The content stream keeps track of the current point for the path
+/// construction helpers below. Path operations written through
+/// [`op`](Self::op) are not taken into account.
 impl Content {
     /// `m`: Begin a new subpath at (x, y).
     #[inline]
     pub fn move_to(&mut self, x: f32, y: f32) -> &mut Self {
         self.op("m").operands([x, y]);
+        self.current_point = (x, y);
+        self.subpath_start = (x, y);
         self
     }
 
@@ -353,6 +432,7 @@ impl Content {
     #[inline]
     pub fn line_to(&mut self, x: f32, y: f32) -> &mut Self {
         self.op("l").operands([x, y]);
+        self.current_point = (x, y);
         self
     }
 
@@ -369,6 +449,7 @@ impl Content {
         y3: f32,
     ) -> &mut Self {
         self.op("c").operands([x1, y1, x2, y2, x3, y3]);
+        self.current_point = (x3, y3);
         self
     }
 
@@ -377,6 +458,7 @@ impl Content {
     #[inline]
     pub fn cubic_to_initial(&mut self, x2: f32, y2: f32, x3: f32, y3: f32) -> &mut Self {
         self.op("v").operands([x2, y2, x3, y3]);
+        self.current_point = (x3, y3);
         self
     }
 
@@ -385,6 +467,7 @@ impl Content {
     #[inline]
     pub fn cubic_to_final(&mut self, x1: f32, y1: f32, x3: f32, y3: f32) -> &mut Self {
         self.op("y").operands([x1, y1, x3, y3]);
+        self.current_point = (x3, y3);
         self
     }
 
@@ -392,6 +475,7 @@ impl Content {
     #[inline]
     pub fn close_path(&mut self) -> &mut Self {
         self.op("h");
+        self.current_point = self.subpath_start;
         self
     }
 
@@ -399,10 +483,246 @@ impl Content {
     #[inline]
     pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32) -> &mut Self {
         self.op("re").operands([x, y, width, height]);
+        self.current_point = (x, y);
+        self.subpath_start = (x, y);
         self
     }
 }
 
+/// Path construction helpers.
+///
+/// These methods write curved shapes in terms of the primitive path
+/// construction operators. Circular and elliptical arcs are approximated with
+/// one cubic Bézier segment per quarter turn (or less). The maximum radial
+/// error of this approximation is about 0.027% of the radius, i.e. less than
+/// 0.03pt for a radius of 100pt.
+impl Content {
+    /// Append a quadratic Bézier segment from the current point to (x2, y2)
+    /// with (x1, y1) as control point.
+    ///
+    /// Since PDF only supports cubic Bézier curves, this writes a `c`
+    /// operation with the degree-elevated control points. The result is
+    /// exact.
+    pub fn quad_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32) -> &mut Self {
+        let (x0, y0) = self.current_point;
+        self.cubic_to(
+            (x0 + 2.0 * x1) / 3.0,
+            (y0 + 2.0 * y1) / 3.0,
+            (x2 + 2.0 * x1) / 3.0,
+            (y2 + 2.0 * y1) / 3.0,
+            x2,
+            y2,
+        )
+    }
+
+    /// Append an elliptical arc from the current point to (x, y).
+    ///
+    /// This uses the endpoint parameterization from SVG's `A` path command:
+    /// - `rx` and `ry` are the radii of the ellipse, whose x-axis is rotated by
+    ///   `rotation` degrees.
+    /// - Of the four candidate arcs, `large_arc` selects one that spans more
+    ///   than 180 degrees and `sweep` selects one that is drawn in the
+    ///   direction of increasing angles (counter-clockwise in PDF's default
+    ///   coordinate system).
+    ///
+    /// Like in SVG, radii that are too small to connect the two points are
+    /// scaled up, zero radii result in a straight line and an arc to the
+    /// current point is omitted.
+    #[allow(clippy::too_many_arguments)]
+    pub fn arc_to(
+        &mut self,
+        rx: f32,
+        ry: f32,
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        x: f32,
+        y: f32,
+    ) -> &mut Self {
+        let (x0, y0) = self.current_point;
+        if (x0, y0) == (x, y) {
+            return self;
+        }
+
+        if rx == 0.0 || ry == 0.0 {
+            return self.line_to(x, y);
+        }
+
+        arc_to_cubics((x0, y0), (rx, ry), rotation, large_arc, sweep, (x, y), |c| {
+            self.cubic_to(c[0], c[1], c[2], c[3], c[4], c[5]);
+        });
+        self
+    }
+
+    /// Append a closed circle with center (cx, cy) and radius `r` as a new
+    /// subpath.
+    pub fn circle(&mut self, cx: f32, cy: f32, r: f32) -> &mut Self {
+        self.ellipse(cx, cy, r, r)
+    }
+
+    /// Append a closed, axis-aligned ellipse with center (cx, cy) and radii
+    /// `rx` and `ry` as a new subpath.
+    ///
+    /// The subpath starts at the rightmost point and runs counter-clockwise.
+    pub fn ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32) -> &mut Self {
+        let kx = rx * KAPPA;
+        let ky = ry * KAPPA;
+        self.move_to(cx + rx, cy)
+            .cubic_to(cx + rx, cy + ky, cx + kx, cy + ry, cx, cy + ry)
+            .cubic_to(cx - kx, cy + ry, cx - rx, cy + ky, cx - rx, cy)
+            .cubic_to(cx - rx, cy - ky, cx - kx, cy - ry, cx, cy - ry)
+            .cubic_to(cx + kx, cy - ry, cx + rx, cy - ky, cx + rx, cy)
+            .close_path()
+    }
+
+    /// Append a closed rectangle with rounded corners as a new subpath.
+    ///
+    /// The `radii` are given for the lower-left, lower-right, upper-right and
+    /// upper-left corner (in this order, assuming PDF's default coordinate
+    /// system). Like in CSS, if the radii of two adjacent corners add up to
+    /// more than the length of the side between them, all radii are scaled
+    /// down proportionally. A radius of zero results in a sharp corner.
+    ///
+    /// The subpath runs counter-clockwise.
+    pub fn rounded_rect(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        radii: [f32; 4],
+    ) -> &mut Self {
+        let (x0, x1) = (x.min(x + width), x.max(x + width));
+        let (y0, y1) = (y.min(y + height), y.max(y + height));
+        let (w, h) = (x1 - x0, y1 - y0);
+
+        let [mut ll, mut lr, mut ur, mut ul] = radii.map(|r| r.max(0.0));
+        let scale = [(w, ll + lr), (h, lr + ur), (w, ur + ul), (h, ul + ll)]
+            .into_iter()
+            .filter(|&(_, sum)| sum > 0.0)
+            .map(|(side, sum)| side / sum)
+            .fold(1.0, f32::min);
+        if scale < 1.0 {
+            for r in [&mut ll, &mut lr, &mut ur, &mut ul] {
+                *r *= scale;
+            }
+        }
+
+        // Edges whose straight part is fully taken up by the corners are
+        // skipped.
+        self.move_to(x0 + ll, y0);
+        if x1 - lr > x0 + ll {
+            self.line_to(x1 - lr, y0);
+        }
+        if lr > 0.0 {
+            let k = lr * KAPPA;
+            self.cubic_to(x1 - lr + k, y0, x1, y0 + lr - k, x1, y0 + lr);
+        }
+        if y1 - ur > y0 + lr {
+            self.line_to(x1, y1 - ur);
+        }
+        if ur > 0.0 {
+            let k = ur * KAPPA;
+            self.cubic_to(x1, y1 - ur + k, x1 - ur + k, y1, x1 - ur, y1);
+        }
+        if x0 + ul < x1 - ur {
+            self.line_to(x0 + ul, y1);
+        }
+        if ul > 0.0 {
+            let k = ul * KAPPA;
+            self.cubic_to(x0 + ul - k, y1, x0, y1 - ul + k, x0, y1 - ul);
+        }
+        if y0 + ll < y1 - ul {
+            self.line_to(x0, y0 + ll);
+        }
+        if ll > 0.0 {
+            let k = ll * KAPPA;
+            self.cubic_to(x0, y0 + ll - k, x0 + ll - k, y0, x0 + ll, y0);
+        }
+        self.close_path()
+    }
+}
+
+/// Approximate an elliptical arc in SVG's endpoint parameterization with cubic
+/// Bézier segments, calling `emit` with the control and end points of each.
+///
+/// The points `from` and `to` must differ and both radii must be nonzero. The
+/// last segment ends exactly at `to` to avoid accumulating rounding errors.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn arc_to_cubics(
+    from: (f32, f32),
+    radii: (f32, f32),
+    rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: (f32, f32),
+    mut emit: impl FnMut([f32; 6]),
+) {
+    // See appendix B.2.4 of the SVG 2 specification for the conversion
+    // from endpoint to center parameterization.
+    let (x0, y0) = (from.0 as f64, from.1 as f64);
+    let (x1, y1) = (to.0 as f64, to.1 as f64);
+    let (mut rx, mut ry) = ((radii.0 as f64).abs(), (radii.1 as f64).abs());
+    let (sin, cos) = (rotation as f64).to_radians().sin_cos();
+
+    let dx = (x0 - x1) / 2.0;
+    let dy = (y0 - y1) / 2.0;
+    let xp = cos * dx + sin * dy;
+    let yp = -sin * dx + cos * dy;
+
+    let lambda = (xp / rx).powi(2) + (yp / ry).powi(2);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let num = (rx * ry).powi(2) - (rx * yp).powi(2) - (ry * xp).powi(2);
+    let den = (rx * yp).powi(2) + (ry * xp).powi(2);
+    let mut coef = (num / den).max(0.0).sqrt();
+    if large_arc == sweep {
+        coef = -coef;
+    }
+
+    let cxp = coef * rx * yp / ry;
+    let cyp = -coef * ry * xp / rx;
+    let cx = cos * cxp - sin * cyp + (x0 + x1) / 2.0;
+    let cy = sin * cxp + cos * cyp + (y0 + y1) / 2.0;
+
+    let start = ((yp - cyp) / ry).atan2((xp - cxp) / rx);
+    let end = ((-yp - cyp) / ry).atan2((-xp - cxp) / rx);
+    let mut sweep_angle = end - start;
+    if sweep && sweep_angle < 0.0 {
+        sweep_angle += std::f64::consts::TAU;
+    } else if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= std::f64::consts::TAU;
+    }
+
+    // Map from the unit circle to the ellipse.
+    let [a, b, c, d] = [rx * cos, rx * sin, -ry * sin, ry * cos];
+    let map = |u: f64, v: f64| [(a * u + c * v + cx) as f32, (b * u + d * v + cy) as f32];
+
+    let n = (sweep_angle.abs() / std::f64::consts::FRAC_PI_2 - 1e-9)
+        .ceil()
+        .max(1.0);
+    let step = sweep_angle / n;
+    let alpha = 4.0 / 3.0 * (step / 4.0).tan();
+
+    let n = n as usize;
+    for i in 0..n {
+        let angle = start + step * i as f64;
+        let (sin0, cos0) = angle.sin_cos();
+        let (sin1, cos1) = (angle + step).sin_cos();
+        let [x1, y1] = map(cos0 - alpha * sin0, sin0 + alpha * cos0);
+        let [x2, y2] = map(cos1 + alpha * sin1, sin1 - alpha * cos1);
+        let [x3, y3] = if i + 1 == n { [to.0, to.1] } else { map(cos1, sin1) };
+        emit([x1, y1, x2, y2, x3, y3]);
+    }
+}
+
+/// The distance of the control points from the end points for a cubic Bézier
+/// approximation of a quarter circle with radius one.
+const KAPPA: f32 = 0.552_284_8;
+
 /// Path painting.
 impl Content {
     /// `S`: Stroke the current path.
@@ -622,8 +942,8 @@ impl Content {
 
     /// `Tm`: Set the text matrix.
     #[inline]
-    pub fn set_text_matrix(&mut self, matrix: [f32; 6]) -> &mut Self {
-        self.op("Tm").operands(matrix);
+    pub fn set_text_matrix(&mut self, matrix: impl Into<[f32; 6]>) -> &mut Self {
+        self.op("Tm").operands(matrix.into());
         self
     }
 
@@ -1807,4 +2127,81 @@ mod tests {
             b"/Test<</ActualText(Actual)/MCID 1/Type/Background>>BDC"
         );
     }
+
+    #[test]
+    fn test_content_path_helpers() {
+        let mut content = Content::new();
+        content
+            .move_to(0.0, 0.0)
+            .quad_to(3.0, 3.0, 6.0, 0.0)
+            .arc_to(0.0, 2.0, 0.0, false, true, 6.0, 3.0)
+            .arc_to(1.0, 1.0, 0.0, false, true, 6.0, 3.0)
+            .rounded_rect(0.0, 0.0, 10.0, 4.0, [4.0, 4.0, 0.0, 4.0]);
+
+        assert_eq!(
+            content.finish().into_vec(),
+            b"0 0 m\n2 2 4 2 6 0 c\n6 3 l\n2 0 m\n8 0 l\n\
+              9.104569 0 10 0.89543045 10 2 c\n10 4 l\n2 4 l\n\
+              0.89543045 4 0 3.1045694 0 2 c\n\
+              0 0.89543045 0.89543045 0 2 0 c\nh"
+        );
+    }
+
+    #[test]
+    fn test_content_arcs() {
+        fn endpoints(content: Content) -> Vec<(f32, f32)> {
+            crate::ContentParser::new(&content.finish())
+                .filter_map(|op| match op.unwrap() {
+                    crate::types::Op::CubicTo(_, _, _, _, x, y) => Some((x, y)),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        let close = |(x1, y1): (f32, f32), (x2, y2): (f32, f32)| {
+            (x1 - x2).abs() < 1e-5 && (y1 - y2).abs() < 1e-5
+        };
+
+        // A semicircle in positive direction is split into two quarters.
+        let mut content = Content::new();
+        content
+            .move_to(1.0, 0.0)
+            .arc_to(1.0, 1.0, 0.0, false, true, -1.0, 0.0);
+        let points = endpoints(content);
+        assert_eq!(points.len(), 2);
+        assert!(close(points[0], (0.0, 1.0)));
+        assert_eq!(points[1], (-1.0, 0.0));
+
+        // The same in negative direction, with radii that are too small.
+        let mut content = Content::new();
+        content
+            .move_to(1.0, 0.0)
+            .arc_to(0.5, 0.5, 0.0, false, false, -1.0, 0.0);
+        let points = endpoints(content);
+        assert_eq!(points.len(), 2);
+        assert!(close(points[0], (0.0, -1.0)));
+
+        // A rotated elliptical arc.
+        let mut content = Content::new();
+        content
+            .move_to(0.0, 0.0)
+            .arc_to(2.0, 1.0, 90.0, false, true, 0.0, 4.0);
+        let points = endpoints(content);
+        assert_eq!(points.len(), 2);
+        assert!(close(points[0], (1.0, 2.0)));
+        assert_eq!(points[1], (0.0, 4.0));
+
+        // A large arc spanning three quarters of a circle.
+        let mut content = Content::new();
+        content.move_to(1.0, 0.0).arc_to(1.0, 1.0, 0.0, true, true, 0.0, -1.0);
+        let points = endpoints(content);
+        assert_eq!(points.len(), 3);
+        assert!(close(points[1], (-1.0, 0.0)));
+
+        // A circle consists of four quarters.
+        let mut content = Content::new();
+        content.circle(5.0, 5.0, 2.0);
+        let points = endpoints(content);
+        assert_eq!(points, [(5.0, 7.0), (3.0, 5.0), (5.0, 3.0), (7.0, 5.0)]);
+    }
 }
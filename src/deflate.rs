@@ -0,0 +1,21 @@
+use std::borrow::Cow;
+
+use crate::{Filter, Stream};
+
+/// A function that compresses data with zlib-wrapped Deflate.
+pub(crate) type Deflate = fn(&[u8]) -> Vec<u8>;
+
+/// Compress data if a compression function is set.
+pub(crate) fn compress(deflate: Option<Deflate>, data: &[u8]) -> Cow<'_, [u8]> {
+    match deflate {
+        Some(deflate) => Cow::Owned(deflate(data)),
+        None => Cow::Borrowed(data),
+    }
+}
+
+/// Mark a stream as compressed if a compression function is set.
+pub(crate) fn filter(deflate: Option<Deflate>, stream: &mut Stream) {
+    if deflate.is_some() {
+        stream.filter(Filter::FlateDecode);
+    }
+}
@@ -0,0 +1,512 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use super::*;
+use crate::cff::Cff;
+use crate::deflate::{self, Deflate};
+use crate::encoding::EncodeError;
+use crate::reader::read_u16;
+use crate::sfnt::{postscript_name, Face, FaceMetrics, Glyf};
+use crate::subset::{glyph_closure, subset_truetype};
+use crate::types::{CidFontType, CmapBuilder, FontFlags, SystemInfo};
+
+/// A TrueType or OpenType font that is embedded as a subset.
+///
+/// Text is encoded glyph by glyph with two-byte codes that are equal to the
+/// glyph IDs (`Identity-H`). The font keeps track of the used glyphs and the
+/// text they stand for. Once all text is encoded, [`write`](Self::write)
+/// produces the font program with just these glyphs and all objects needed
+/// to use it: A _Type 0_ font with a CID font, a font descriptor, a CID set
+/// and a `/ToUnicode` character map.
+///
+/// Fonts with TrueType outlines are embedded as `/FontFile2` and fonts with
+/// CFF outlines as `/FontFile3` with subtype `/CIDFontType0C`. Fonts with
+/// `CFF2` outlines are not supported.
+///
+/// ```no_run
+/// use pdf_writer::{Content, EmbeddedFont, Name, Pdf, Ref, Str};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("NotoSans-Regular.ttf")?;
+/// let mut font = EmbeddedFont::new(&data, 0)?;
+///
+/// let mut content = Content::new();
+/// content.begin_text();
+/// content.set_font(Name(b"F1"), 12.0);
+/// content.show(Str(&font.encode("Hello, world!")?));
+/// content.end_text();
+///
+/// let mut pdf = Pdf::new();
+/// let mut alloc = Ref::new(10);
+/// font.write(&mut pdf, Ref::new(1), &mut alloc);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Characters are mapped to glyphs one by one through the font's `cmap`
+/// table. For ligatures and complex scripts, shape the text yourself and
+/// encode the resulting glyphs with [`encode_glyph`](Self::encode_glyph).
+pub struct EmbeddedFont<'a> {
+    face: Face<'a>,
+    cff: Option<Cff<'a>>,
+    metrics: FaceMetrics,
+    /// The used glyphs and the text they stand for.
+    glyphs: BTreeMap<u16, String>,
+    deflate: Option<Deflate>,
+}
+
+impl<'a> EmbeddedFont<'a> {
+    /// Parse a font from TrueType or OpenType data.
+    ///
+    /// For font collections, `index` selects the face. For all other fonts, it
+    /// must be zero.
+    pub fn new(data: &'a [u8], index: u32) -> Result<Self, FontError> {
+        let face = Face::parse(data, index)?;
+        let cff = match face.table(b"CFF ") {
+            Some(table) => Some(Cff::parse(table)?),
+            None if face.table(b"CFF2").is_some() => {
+                return Err(FontError::Unsupported("CFF2 outlines"));
+            }
+            None => {
+                Glyf::parse(&face)?;
+                None
+            }
+        };
+
+        // Subsetting copies the full horizontal metrics, so check them here
+        // for `write` not to fail later.
+        let long_metrics = read_u16(face.require(b"hhea")?, 34)
+            .ok_or(FontError::MalformedTable("hhea"))?;
+        let needed = 4 * usize::from(long_metrics.clamp(1, face.num_glyphs()));
+        if face.require(b"hmtx")?.len() < needed {
+            return Err(FontError::MalformedTable("hmtx"));
+        }
+
+        let metrics = FaceMetrics::parse(&face)?;
+        Ok(Self {
+            face,
+            cff,
+            metrics,
+            glyphs: BTreeMap::new(),
+            deflate: None,
+        })
+    }
+
+    /// Compress the font program, CID set and character map streams with a
+    /// [Deflate function](crate#compression).
+    pub fn deflate(&mut self, deflate: fn(&[u8]) -> Vec<u8>) -> &mut Self {
+        self.deflate = Some(deflate);
+        self
+    }
+
+    /// The number of glyphs in the font.
+    pub fn num_glyphs(&self) -> u16 {
+        self.face.num_glyphs()
+    }
+
+    /// The glyph for a character according to the font's `cmap` table.
+    pub fn glyph_id(&self, c: char) -> Option<u16> {
+        self.face.glyph_id(c)
+    }
+
+    /// The advance width of a glyph in thousandths of an em, that is, in text
+    /// space units for a font size of one.
+    pub fn glyph_width(&self, glyph: u16) -> Option<f32> {
+        self.face.advance(glyph).map(|advance| self.face.to_em(advance))
+    }
+
+    /// The width of a string in points when set at the given font size.
+    ///
+    /// Characters without a glyph count as zero width. Kerning and
+    /// character and word spacing are not considered.
+    pub fn measure(&self, text: &str, size: f32) -> f32 {
+        let total: f32 =
+            text.chars().filter_map(|c| self.glyph_width(self.glyph_id(c)?)).sum();
+        total * size / 1000.0
+    }
+
+    /// Whether the font's license allows embedding it in a document.
+    ///
+    /// This is based on the `fsType` field of the `OS/2` table: Fonts with
+    /// _restricted license embedding_ and fonts that only allow embedding
+    /// bitmaps must not be embedded.
+    pub fn is_embeddable(&self) -> bool {
+        let permissions = self.metrics.permissions;
+        permissions & 0x000F != 0x0002 && permissions & 0x0200 == 0
+    }
+
+    /// Encode a string for use with [`Content::show`] and mark its glyphs as
+    /// used.
+    ///
+    /// Fails with all characters for which the font has no glyph. In that
+    /// case, no glyphs are marked.
+    pub fn encode(&mut self, text: &str) -> Result<Vec<u8>, EncodeError> {
+        let mut glyphs = Vec::with_capacity(text.len());
+        let mut unencodable = vec![];
+        for (offset, c) in text.char_indices() {
+            match self.glyph_id(c) {
+                Some(glyph) => glyphs.push((glyph, c)),
+                None => unencodable.push((offset, c)),
+            }
+        }
+
+        if !unencodable.is_empty() {
+            return Err(EncodeError::new(unencodable));
+        }
+
+        let mut bytes = Vec::with_capacity(2 * glyphs.len());
+        for (glyph, c) in glyphs {
+            self.glyphs.entry(glyph).or_insert_with(|| c.into());
+            bytes.extend(glyph.to_be_bytes());
+        }
+        Ok(bytes)
+    }
+
+    /// Encode a single glyph and mark it as used.
+    ///
+    /// The `text` is what the glyph stands for, for example `"ffi"` for a
+    /// ligature. It is used for text extraction and may be empty. Only the
+    /// text given the first time a glyph is used is kept.
+    ///
+    /// Panics if the font has no glyph with this ID.
+    pub fn encode_glyph(&mut self, glyph: u16, text: &str) -> [u8; 2] {
+        assert!(glyph < self.num_glyphs(), "glyph ID {glyph} is out of bounds");
+        self.glyphs.entry(glyph).or_insert_with(|| text.into());
+        glyph.to_be_bytes()
+    }
+
+    /// The glyphs that were used so far.
+    pub fn used_glyphs(&self) -> impl Iterator<Item = u16> + '_ {
+        self.glyphs.keys().copied()
+    }
+
+    /// Write the subsetted font and all objects belonging to it.
+    ///
+    /// The _Type 0_ font that must be referenced from resource dictionaries is
+    /// written with `id`. The other objects use IDs from `alloc`, which is
+    /// advanced accordingly.
+    pub fn write(&self, chunk: &mut Chunk, id: Ref, alloc: &mut Ref) {
+        let cid_font_id = alloc.bump();
+        let descriptor_id = alloc.bump();
+        let file_id = alloc.bump();
+        let cid_set_id = alloc.bump();
+        let cmap_id = alloc.bump();
+
+        let used: BTreeSet<u16> = self.glyphs.keys().copied().collect();
+        let (program, present) = match &self.cff {
+            Some(cff) => {
+                let mut present = used.clone();
+                present.insert(0);
+                (cff.subset(|glyph| used.contains(&glyph)), present)
+            }
+            None => {
+                let glyf = Glyf::parse(&self.face).expect("font was checked on creation");
+                let present = glyph_closure(&glyf, &used);
+                let program = subset_truetype(&self.face, &present)
+                    .expect("font was checked on creation");
+                (program, present)
+            }
+        };
+
+        let base_font = format!("{}+{}", subset_tag(&present), self.postscript_name());
+        let base_font = Name(base_font.as_bytes());
+        let system_info = SystemInfo {
+            registry: Str(b"Adobe"),
+            ordering: Str(b"Identity"),
+            supplement: 0,
+        };
+
+        chunk
+            .type0_font(id)
+            .base_font(base_font)
+            .encoding_predefined(Name(b"Identity-H"))
+            .descendant_font(cid_font_id)
+            .to_unicode(cmap_id);
+
+        let mut cid_font = chunk.cid_font(cid_font_id);
+        cid_font
+            .subtype(if self.cff.is_some() {
+                CidFontType::Type0
+            } else {
+                CidFontType::Type2
+            })
+            .base_font(base_font)
+            .system_info(system_info)
+            .font_descriptor(descriptor_id);
+        if self.cff.is_none() {
+            cid_font.cid_to_gid_map_predefined(Name(b"Identity"));
+        }
+        self.write_widths(&mut cid_font, &present);
+        cid_font.finish();
+
+        let metrics = &self.metrics;
+        let to_em = |units: i16| self.face.to_em(units);
+        let [x_min, y_min, x_max, y_max] = metrics.bbox.map(to_em);
+        let mut flags = FontFlags::SYMBOLIC;
+        flags.set(FontFlags::FIXED_PITCH, metrics.fixed_pitch);
+        flags.set(FontFlags::SERIF, metrics.serif);
+        flags.set(FontFlags::SCRIPT, metrics.script);
+        flags.set(FontFlags::ITALIC, metrics.italic || metrics.italic_angle != 0.0);
+
+        let mut descriptor = chunk.font_descriptor(descriptor_id);
+        descriptor
+            .name(base_font)
+            .flags(flags)
+            .bbox(Rect::new(x_min, y_min, x_max, y_max))
+            .italic_angle(metrics.italic_angle)
+            .ascent(to_em(metrics.ascender))
+            .descent(to_em(metrics.descender))
+            .cap_height(to_em(metrics.cap_height.unwrap_or(metrics.ascender)));
+        if let Some(x_height) = metrics.x_height {
+            descriptor.x_height(to_em(x_height));
+        }
+        descriptor.stem_v(10.0 + 0.244 * (f32::from(metrics.weight) - 50.0));
+        if self.cff.is_some() {
+            descriptor.font_file3(file_id);
+        } else {
+            descriptor.font_file2(file_id);
+        }
+        descriptor.cid_set(cid_set_id);
+        descriptor.finish();
+
+        let data = deflate::compress(self.deflate, &program);
+        let mut stream = chunk.stream(file_id, &data);
+        if self.cff.is_some() {
+            stream.pair(Name(b"Subtype"), Name(b"CIDFontType0C"));
+        } else {
+            stream.pair(Name(b"Length1"), program.len() as i32);
+        }
+        deflate::filter(self.deflate, &mut stream);
+        stream.finish();
+
+        // One bit per CID, starting with the high-order bit of the first byte.
+        let mut cid_set = vec![0; present.last().map_or(0, |&last| last / 8 + 1).into()];
+        for &glyph in &present {
+            cid_set[usize::from(glyph / 8)] |= 0x80 >> (glyph % 8);
+        }
+        let data = deflate::compress(self.deflate, &cid_set);
+        deflate::filter(self.deflate, &mut chunk.stream(cid_set_id, &data));
+
+        let mut cmap = CmapBuilder::new(Name(b"Custom"), system_info);
+        cmap.codespace_range(&[0x00, 0x00], &[0xFF, 0xFF]);
+        for (&glyph, text) in &self.glyphs {
+            if !text.is_empty() {
+                cmap.bf_char(&glyph.to_be_bytes(), text);
+            }
+        }
+        let cmap = cmap.finish();
+        let data = deflate::compress(self.deflate, &cmap);
+        let mut cmap = chunk.cmap(cmap_id, &data);
+        cmap.name(Name(b"Custom")).system_info(system_info);
+        deflate::filter(self.deflate, &mut cmap);
+    }
+
+    /// Write the `/DW` and `/W` attributes for the present glyphs.
+    fn write_widths(&self, cid_font: &mut CidFont, glyphs: &BTreeSet<u16>) {
+        let widths: Vec<(u16, f32)> = glyphs
+            .iter()
+            .map(|&glyph| (glyph, self.glyph_width(glyph).unwrap_or(0.0)))
+            .collect();
+
+        // The most common width becomes the default.
+        let mut counts = HashMap::new();
+        for &(_, width) in &widths {
+            *counts.entry(width.to_bits()).or_insert(0) += 1;
+        }
+        let default = counts
+            .into_iter()
+            .max_by_key(|&(bits, count)| (count, std::cmp::Reverse(bits)))
+            .map_or(0.0, |(bits, _)| f32::from_bits(bits));
+        cid_font.default_width(default);
+        cid_font.widths().compact(widths, default);
+    }
+
+    /// The PostScript name of the font with characters that need escaping in
+    /// PDF names removed.
+    fn postscript_name(&self) -> String {
+        let name = postscript_name(&self.face)
+            .or_else(|| self.cff.as_ref()?.name().map(Into::into))
+            .unwrap_or_default();
+        let name: String = name
+            .chars()
+            .filter(|&c| c.is_ascii_graphic() && !"()<>[]{}/%#".contains(c))
+            .take(63)
+            .collect();
+        if name.is_empty() {
+            "Font".into()
+        } else {
+            name
+        }
+    }
+}
+
+/// A tag of six uppercase letters that identifies a subset.
+///
+/// Derived from the glyphs in the subset, such that different subsets of the
+/// same font in one document get different names.
+fn subset_tag(glyphs: &BTreeSet<u16>) -> String {
+    // FNV-1a, which is stable across platforms and releases.
+    let mut hash: u32 = 0x811C9DC5;
+    for byte in glyphs.iter().flat_map(|glyph| glyph.to_be_bytes()) {
+        hash = (hash ^ u32::from(byte)).wrapping_mul(0x01000193);
+    }
+
+    (0..6)
+        .map(|_| {
+            let letter = char::from(b'A' + (hash % 26) as u8);
+            hash /= 26;
+            letter
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cff::fixture::{cff, push};
+    use crate::sfnt::fixture::{be, truetype_font};
+    use crate::sfnt::{glyph_components, Tag};
+    use crate::subset::write_sfnt;
+
+    /// A TrueType font with an empty `.notdef` glyph and glyphs for "A", "B"
+    /// and "C". "B" is a composite glyph that refers to "A".
+    fn test_font() -> Vec<u8> {
+        let mut cmap = be(&[0, 1, 3, 1], 2);
+        cmap.extend(be(&[12], 4));
+        cmap.extend(be(&[4, 32, 0, 4, 4, 1, 0], 2));
+        cmap.extend(be(&[0x43, 0xFFFF, 0, 0x41, 0xFFFF, -0x40, 1, 0, 0], 2));
+
+        let simple = be(&[1, 0, 0, 600, 700, 0, 0], 2).into_iter().chain([1, 0, 0, 0, 0]);
+        let mut glyf: Vec<u8> = simple.clone().chain([0]).collect();
+        glyf.extend(simple.clone().chain([0]));
+        glyf.extend(be(&[-1, 0, 0, 600, 700, 0x0002, 1], 2));
+        glyf.extend([0, 0]);
+        glyf.extend(simple.chain([0]));
+        let loca = be(&[0, 10, 20, 28, 38], 2);
+
+        truetype_font([500, 600, 600], &glyf, &loca, &[(*b"cmap", &cmap)])
+    }
+
+    #[test]
+    fn test_font_metrics_and_encoding() {
+        let data = test_font();
+        let mut font = EmbeddedFont::new(&data, 0).unwrap();
+        assert_eq!(font.num_glyphs(), 4);
+        assert_eq!(font.glyph_id('B'), Some(2));
+        assert_eq!(font.glyph_id('D'), None);
+        assert_eq!(font.glyph_width(0), Some(500.0));
+        assert_eq!(font.glyph_width(3), Some(600.0));
+        assert_eq!(font.measure("ABD", 10.0), 12.0);
+        assert!(font.is_embeddable());
+        assert_eq!(font.encode("BA").unwrap(), [0, 2, 0, 1]);
+        assert_eq!(font.encode("AD").unwrap_err().unencodable(), [(1, 'D')]);
+        assert_eq!(font.used_glyphs().collect::<Vec<_>>(), [1, 2]);
+        assert!(matches!(
+            EmbeddedFont::new(&data, 1),
+            Err(FontError::FaceIndexOutOfBounds)
+        ));
+        assert!(matches!(EmbeddedFont::new(b"abcd", 0), Err(FontError::UnknownFormat)));
+    }
+
+    #[test]
+    fn test_truncated_metrics() {
+        let data = test_font();
+        let face = Face::parse(&data, 0).unwrap();
+        let tables: Vec<(Tag, &[u8])> =
+            [b"cmap", b"glyf", b"head", b"hhea", b"hmtx", b"loca", b"maxp"]
+                .into_iter()
+                .map(|tag| {
+                    let table = face.table(tag).unwrap();
+                    (*tag, if tag == b"hmtx" { &table[..8] } else { table })
+                })
+                .collect();
+
+        let data = write_sfnt(&tables);
+        assert!(matches!(
+            EmbeddedFont::new(&data, 0),
+            Err(FontError::MalformedTable("hmtx"))
+        ));
+    }
+
+    #[test]
+    fn test_subset_truetype() {
+        let data = test_font();
+        let face = Face::parse(&data, 0).unwrap();
+        let glyf = Glyf::parse(&face).unwrap();
+        let glyphs = glyph_closure(&glyf, &BTreeSet::from([2]));
+        assert_eq!(glyphs, BTreeSet::from([0, 1, 2]));
+
+        let subset = subset_truetype(&face, &glyphs).unwrap();
+        let face = Face::parse(&subset, 0).unwrap();
+        let glyf = Glyf::parse(&face).unwrap();
+        assert_eq!(face.num_glyphs(), 3);
+        assert_eq!(face.advance(2), Some(600));
+        assert_eq!(glyf.glyph(1).unwrap().len(), 20);
+        assert_eq!(glyph_components(glyf.glyph(2).unwrap()).collect::<Vec<_>>(), [1]);
+        assert_eq!(face.table(b"cmap"), None);
+    }
+
+    #[test]
+    fn test_write_embedded_font() {
+        let data = test_font();
+        let mut font = EmbeddedFont::new(&data, 0).unwrap();
+        font.encode("B").unwrap();
+
+        let mut chunk = Chunk::new();
+        let mut alloc = Ref::new(2);
+        font.write(&mut chunk, Ref::new(1), &mut alloc);
+        assert_eq!(alloc, Ref::new(7));
+
+        let cid_set = b"stream\n\xE0\nendstream";
+        assert!(chunk.as_bytes().windows(cid_set.len()).any(|w| w == cid_set));
+
+        let output = String::from_utf8_lossy(chunk.as_bytes());
+        let tag = subset_tag(&BTreeSet::from([0, 1, 2]));
+        assert!(output.contains(&format!("/BaseFont /{tag}+Font")));
+        assert!(output.contains("/Subtype /CIDFontType2"));
+        assert!(output.contains("/CIDToGIDMap /Identity"));
+        assert!(output.contains("/DW 600\n  /W [0 [500]]"));
+        assert!(output.contains("/FontFile2 4 0 R"));
+        assert!(output.contains("<0002> <0042>"));
+    }
+
+    #[test]
+    fn test_write_cff_font() {
+        let mut glyph = vec![];
+        push(&mut glyph, &[0, 0], &[21]);
+        push(&mut glyph, &[100, 0, 0, 100], &[5, 14]);
+        let glyphs: [&[u8]; 4] = [&[14], &glyph, &glyph, &[14]];
+        let program = cff(&glyphs, &[], &[&[]], &[]);
+        let data = truetype_font([500, 600, 700], &[], &[], &[(*b"CFF ", &program)]);
+
+        let mut font = EmbeddedFont::new(&data, 0).unwrap();
+        font.encode_glyph(2, "A");
+        let mut chunk = Chunk::new();
+        let mut alloc = Ref::new(2);
+        font.write(&mut chunk, Ref::new(1), &mut alloc);
+
+        let output = String::from_utf8_lossy(chunk.as_bytes());
+        let tag = subset_tag(&BTreeSet::from([0, 2]));
+        assert!(output.contains(&format!("/BaseFont /{tag}+Test")));
+        assert!(output.contains("/Subtype /CIDFontType0\n"));
+        assert!(!output.contains("/CIDToGIDMap"));
+        assert!(output.contains("/FontFile3 4 0 R"));
+        assert!(output.contains("/Subtype /CIDFontType0C"));
+
+        let bytes = chunk.as_bytes();
+        let find = |needle: &[u8], from: usize| {
+            from + bytes[from..].windows(needle.len()).position(|w| w == needle).unwrap()
+        };
+        let start = find(b"stream\n", find(b"/CIDFontType0C", 0)) + 7;
+        let subset = &bytes[start..find(b"\nendstream", start)];
+        let subset = Cff::parse(subset).unwrap();
+        assert!(subset.is_cid());
+        assert_eq!(subset.name(), Some("Test"));
+
+        // Only the used glyph and `.notdef` keep their outlines.
+        let mut segments = vec![];
+        subset.outline(1, &mut segments).unwrap();
+        assert!(segments.is_empty());
+        subset.outline(2, &mut segments).unwrap();
+        assert_eq!(segments.len(), 4);
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::types::{SimpleEncoding, StandardFont};
 use std::marker::PhantomData;
 
 /// Writer for a _Type-1 font dictionary_.
@@ -57,6 +58,24 @@ impl Type1Font<'_> {
         self
     }
 
+    /// Write the `/FirstChar`, `/LastChar`, and `/Widths` attributes with the
+    /// metrics of a standard 14 font in the given encoding.
+    ///
+    /// The range spans all codes that the encoding maps to a glyph of the
+    /// font. Codes in between without a glyph get a width of zero.
+    pub fn standard_widths(
+        &mut self,
+        font: StandardFont,
+        encoding: SimpleEncoding,
+    ) -> &mut Self {
+        let width = |code| font.char_width(code, encoding);
+        let first = (0..=255).find(|&c| width(c).is_some()).unwrap_or(0);
+        let last = (0..=255).rev().find(|&c| width(c).is_some()).unwrap_or(0);
+        self.first_char(first);
+        self.last_char(last);
+        self.widths((first..=last).map(|c| width(c).unwrap_or(0.0)))
+    }
+
     /// Write the `/FontDescriptor` attribute. Required (except for standard 14
     /// fonts before PDF 1.5).
     pub fn font_descriptor(&mut self, id: Ref) -> &mut Self {
@@ -119,8 +138,8 @@ impl Type3Font<'_> {
 
     /// Write the `/FontMatrix` attribute, which defines the mapping from glyph
     /// space to text space. Required.
-    pub fn matrix(&mut self, matrix: [f32; 6]) -> &mut Self {
-        self.insert(Name(b"FontMatrix")).array().items(matrix);
+    pub fn matrix(&mut self, matrix: impl Into<[f32; 6]>) -> &mut Self {
+        self.insert(Name(b"FontMatrix")).array().items(matrix.into());
         self
     }
 
@@ -355,6 +374,28 @@ impl CidFont<'_> {
         self.insert(Name(b"W")).start()
     }
 
+    /// Write the `/DW2` attribute, specifying the default vertical metrics for
+    /// vertical writing.
+    ///
+    /// The first number is the vertical component of the position vector,
+    /// which goes from the horizontal to the vertical origin of a glyph. The
+    /// horizontal component is always half the glyph's width. The second
+    /// number is the vertical displacement, which is typically negative.
+    /// Defaults to `[880 -1000]`.
+    pub fn default_vertical_metrics(
+        &mut self,
+        position_y: f32,
+        displacement_y: f32,
+    ) -> &mut Self {
+        self.insert(Name(b"DW2")).array().items([position_y, displacement_y]);
+        self
+    }
+
+    /// Start writing the `/W2` (vertical metrics) array for vertical writing.
+    pub fn vertical_metrics(&mut self) -> VerticalMetrics<'_> {
+        self.insert(Name(b"W2")).start()
+    }
+
     /// Write the `/CIDToGIDMap` attribute as a predefined name.
     ///
     /// The attribute must be present for PDF/A. The only permissible predefined
@@ -424,10 +465,165 @@ impl Widths<'_> {
         self.item(width);
         self
     }
+
+    /// Specifies the widths of the given CIDs in the most compact form.
+    ///
+    /// CIDs whose width is `default` are left out, so it should match the
+    /// [`/DW` attribute](CidFont::default_width). Runs of CIDs with the same
+    /// width are written as ranges and other neighbouring CIDs as lists. The
+    /// CIDs may come in any order. If a CID occurs more than once, its first
+    /// width is used.
+    pub fn compact(
+        &mut self,
+        widths: impl IntoIterator<Item = (u16, f32)>,
+        default: f32,
+    ) -> &mut Self {
+        for entry in compact_widths(widths, default) {
+            match entry {
+                WidthsEntry::Same(first, last, width) => self.same(first, last, width),
+                WidthsEntry::Consecutive(start, widths) => {
+                    self.consecutive(start, widths)
+                }
+            };
+        }
+        self
+    }
 }
 
 deref!('a, Widths<'a> => Array<'a>, array);
 
+/// Writer for a _CID font vertical metrics array_.
+///
+/// Each metric consists of the vertical displacement of a glyph followed by
+/// the horizontal and vertical components of its position vector, see
+/// [`CidFont::default_vertical_metrics`].
+///
+/// This struct is created by [`CidFont::vertical_metrics`].
+pub struct VerticalMetrics<'a> {
+    array: Array<'a>,
+}
+
+writer!(VerticalMetrics: |obj| Self { array: obj.array() });
+
+impl VerticalMetrics<'_> {
+    /// Specifies individual metrics for a range of consecutive CIDs starting
+    /// at `start`.
+    pub fn consecutive(
+        &mut self,
+        start: u16,
+        metrics: impl IntoIterator<Item = [f32; 3]>,
+    ) -> &mut Self {
+        self.item(i32::from(start));
+        self.push().array().items(metrics.into_iter().flatten());
+        self
+    }
+
+    /// Specifies the same metrics for all CIDs between `first` and `last`.
+    pub fn same(&mut self, first: u16, last: u16, metrics: [f32; 3]) -> &mut Self {
+        self.item(i32::from(first));
+        self.item(i32::from(last));
+        self.items(metrics);
+        self
+    }
+}
+
+deref!('a, VerticalMetrics<'a> => Array<'a>, array);
+
+/// An entry in a `/W` array.
+#[derive(Debug, Clone, PartialEq)]
+enum WidthsEntry {
+    Same(u16, u16, f32),
+    Consecutive(u16, Vec<f32>),
+}
+
+/// Split widths into the entries that need the fewest array items.
+fn compact_widths(
+    widths: impl IntoIterator<Item = (u16, f32)>,
+    default: f32,
+) -> Vec<WidthsEntry> {
+    let mut widths: Vec<(u16, f32)> = widths.into_iter().collect();
+    widths.sort_by_key(|&(cid, _)| cid);
+    widths.dedup_by_key(|&mut (cid, _)| cid);
+
+    // Runs of consecutive CIDs with the same width.
+    let mut runs: Vec<(u16, u16, f32)> = vec![];
+    for (cid, width) in widths.into_iter().filter(|&(_, width)| width != default) {
+        match runs.last_mut() {
+            Some((_, last, w)) if *last + 1 == cid && *w == width => *last = cid,
+            _ => runs.push((cid, cid, width)),
+        }
+    }
+
+    // Each run is either written as a range, which takes three items, or as
+    // part of a list. A new list takes two items in addition to the widths,
+    // while continuing the previous one means filling the gap with default
+    // widths. Find the cheapest choice for each run, tracking the cost up to
+    // the run for both ways of writing it.
+    #[derive(Copy, Clone)]
+    enum Choice {
+        Range,
+        Start,
+        Continue,
+    }
+
+    let mut range_cost = 0;
+    let mut list_cost = usize::MAX;
+    let mut steps: Vec<[(Choice, bool); 2]> = Vec::with_capacity(runs.len());
+    for (i, &(first, last, _)) in runs.iter().enumerate() {
+        let len = usize::from(last - first) + 1;
+        let prev_list = list_cost <= range_cost;
+        let prev = range_cost.min(list_cost);
+        let range = (prev + 3, (Choice::Range, prev_list));
+        let start = (prev + 2 + len, (Choice::Start, prev_list));
+        let list = match i.checked_sub(1) {
+            Some(j) if list_cost != usize::MAX => {
+                let gap = usize::from(first - runs[j].1) - 1;
+                let cont = (list_cost + gap + len, (Choice::Continue, true));
+                if cont.0 <= start.0 {
+                    cont
+                } else {
+                    start
+                }
+            }
+            _ => start,
+        };
+        range_cost = range.0;
+        list_cost = list.0;
+        steps.push([range.1, list.1]);
+    }
+
+    // Walk back through the cheapest choices.
+    let mut choices = vec![Choice::Range; runs.len()];
+    let mut in_list = list_cost <= range_cost;
+    for (i, step) in steps.iter().enumerate().rev() {
+        let (choice, prev_list) = step[usize::from(in_list)];
+        choices[i] = choice;
+        in_list = prev_list;
+    }
+
+    let mut entries = vec![];
+    let mut prev_last = 0;
+    for ((first, last, width), choice) in runs.into_iter().zip(choices) {
+        let len = usize::from(last - first) + 1;
+        match choice {
+            Choice::Range => entries.push(WidthsEntry::Same(first, last, width)),
+            Choice::Start => entries.push(WidthsEntry::Consecutive(
+                first,
+                std::iter::repeat_n(width, len).collect(),
+            )),
+            Choice::Continue => {
+                if let Some(WidthsEntry::Consecutive(_, widths)) = entries.last_mut() {
+                    let gap = usize::from(first - prev_last) - 1;
+                    widths.extend(std::iter::repeat_n(default, gap));
+                    widths.extend(std::iter::repeat_n(width, len));
+                }
+            }
+        }
+        prev_last = last;
+    }
+    entries
+}
+
 /// Writer for a _font descriptor dictionary_.
 ///
 /// This struct is created by [`Chunk::font_descriptor`].
@@ -550,6 +746,9 @@ impl FontDescriptor<'_> {
     }
 
     /// Write the `/FontFile` attribute, referecing Type 1 font data.
+    ///
+    /// A [`Type1Program`](crate::Type1Program) can write this stream from
+    /// PFB or PFA data.
     pub fn font_file(&mut self, id: Ref) -> &mut Self {
         self.pair(Name(b"FontFile"), id);
         self
@@ -569,6 +768,29 @@ impl FontDescriptor<'_> {
         self
     }
 
+    /// Write the `/FontName`, `/Flags`, `/FontBBox`, `/ItalicAngle`,
+    /// `/Ascent`, `/Descent`, `/CapHeight`, `/XHeight`, `/StemV`, and `/StemH`
+    /// attributes with the metrics of a standard 14 font.
+    ///
+    /// `/CapHeight` and `/XHeight` are omitted for _Symbol_ and
+    /// _ZapfDingbats_.
+    pub fn standard_metrics(&mut self, font: StandardFont) -> &mut Self {
+        self.name(font.to_name());
+        self.flags(font.flags());
+        self.bbox(font.bbox());
+        self.italic_angle(font.italic_angle());
+        self.ascent(font.ascent());
+        self.descent(font.descent());
+        if let Some(cap_height) = font.cap_height() {
+            self.cap_height(cap_height);
+        }
+        if let Some(x_height) = font.x_height() {
+            self.x_height(x_height);
+        }
+        self.stem_v(font.stem_v());
+        self.stem_h(font.stem_h())
+    }
+
     /// Write the `/CharSet` attribute, encoding the character names of a font
     /// subset as a string. This is only relevant for Type 1 fonts. PDF 1.1+.
     ///
@@ -868,54 +1090,8 @@ where
     /// Create a new, empty unicode character map while specifying the writing
     /// mode.
     pub fn with_writing_mode(name: Name, info: SystemInfo, mode: WMode) -> Self {
-        // https://www.adobe.com/content/dam/acom/en/devnet/font/pdfs/5014.CIDFont_Spec.pdf
-
         let mut buf = Buf::new();
-
-        // Static header.
-        buf.extend(b"%!PS-Adobe-3.0 Resource-CMap\n");
-        buf.extend(b"%%DocumentNeededResources: procset CIDInit\n");
-        buf.extend(b"%%IncludeResource: procset CIDInit\n");
-
-        // Dynamic header.
-        buf.extend(b"%%BeginResource: CMap ");
-        buf.extend(name.0);
-        buf.push(b'\n');
-        buf.extend(b"%%Title: (");
-        buf.extend(name.0);
-        buf.push(b' ');
-        buf.extend(info.registry.0);
-        buf.push(b' ');
-        buf.extend(info.ordering.0);
-        buf.push(b' ');
-        buf.push_int(info.supplement);
-        buf.extend(b")\n");
-        buf.extend(b"%%Version: 1\n");
-        buf.extend(b"%%EndComments\n");
-
-        // General body.
-        buf.extend(b"/CIDInit /ProcSet findresource begin\n");
-        buf.extend(b"12 dict begin\n");
-        buf.extend(b"begincmap\n");
-        buf.extend(b"/CIDSystemInfo 3 dict dup begin\n");
-        buf.extend(b"    /Registry ");
-        buf.push_val(info.registry);
-        buf.extend(b" def\n");
-        buf.extend(b"    /Ordering ");
-        buf.push_val(info.ordering);
-        buf.extend(b" def\n");
-        buf.extend(b"    /Supplement ");
-        buf.push_val(info.supplement);
-        buf.extend(b" def\n");
-        buf.extend(b"end def\n");
-        buf.extend(b"/CMapName ");
-        buf.push_val(name);
-        buf.extend(b" def\n");
-        buf.extend(b"/CMapVersion 1 def\n");
-        buf.extend(b"/CMapType 0 def\n");
-        buf.extend(b"/WMode ");
-        buf.push_int(mode.to_int());
-        buf.extend(b" def\n");
+        write_cmap_header(&mut buf, name, info, mode, 0, None);
 
         // We just cover the whole unicode codespace.
         buf.extend(b"1 begincodespacerange\n");
@@ -969,14 +1145,7 @@ where
         // Flush the in-progress range.
         self.flush_range();
 
-        // End of body.
-        self.buf.extend(b"endcmap\n");
-        self.buf.extend(b"CMapName currentdict /CMap defineresource pop\n");
-        self.buf.extend(b"end\n");
-        self.buf.extend(b"end\n");
-        self.buf.extend(b"%%EndResource\n");
-        self.buf.extend(b"%%EOF");
-
+        write_cmap_footer(&mut self.buf);
         self.buf
     }
 
@@ -993,6 +1162,364 @@ where
     }
 }
 
+/// Write a section of a CMap program with at most 100 entries per block.
+fn write_cmap_section<T>(
+    buf: &mut Buf,
+    kind: &str,
+    entries: &[T],
+    mut push: impl FnMut(&mut Buf, &T),
+) {
+    for block in entries.chunks(100) {
+        buf.push_int(block.len() as i32);
+        buf.extend(b" begin");
+        buf.extend(kind.as_bytes());
+        buf.push(b'\n');
+        for entry in block {
+            push(buf, entry);
+            buf.push(b'\n');
+        }
+        buf.extend(b"end");
+        buf.extend(kind.as_bytes());
+        buf.push(b'\n');
+    }
+}
+
+/// Write a code as a hexadecimal string.
+fn push_code(buf: &mut Buf, code: &[u8]) {
+    buf.push(b'<');
+    for &byte in code {
+        buf.push_hex(byte);
+    }
+    buf.push(b'>');
+}
+
+/// Write the start of a CMap program up to the codespace ranges.
+fn write_cmap_header(
+    buf: &mut Buf,
+    name: Name,
+    info: SystemInfo,
+    mode: WMode,
+    cmap_type: i32,
+    use_cmap: Option<Name>,
+) {
+    // https://www.adobe.com/content/dam/acom/en/devnet/font/pdfs/5014.CIDFont_Spec.pdf
+
+    // Static header.
+    buf.extend(b"%!PS-Adobe-3.0 Resource-CMap\n");
+    buf.extend(b"%%DocumentNeededResources: procset CIDInit\n");
+    buf.extend(b"%%IncludeResource: procset CIDInit\n");
+
+    // Dynamic header.
+    buf.extend(b"%%BeginResource: CMap ");
+    buf.extend(name.0);
+    buf.push(b'\n');
+    buf.extend(b"%%Title: (");
+    buf.extend(name.0);
+    buf.push(b' ');
+    buf.extend(info.registry.0);
+    buf.push(b' ');
+    buf.extend(info.ordering.0);
+    buf.push(b' ');
+    buf.push_int(info.supplement);
+    buf.extend(b")\n");
+    buf.extend(b"%%Version: 1\n");
+    buf.extend(b"%%EndComments\n");
+
+    // General body.
+    buf.extend(b"/CIDInit /ProcSet findresource begin\n");
+    buf.extend(b"12 dict begin\n");
+    buf.extend(b"begincmap\n");
+    if let Some(base) = use_cmap {
+        buf.push_val(base);
+        buf.extend(b" usecmap\n");
+    }
+    buf.extend(b"/CIDSystemInfo 3 dict dup begin\n");
+    buf.extend(b"    /Registry ");
+    buf.push_val(info.registry);
+    buf.extend(b" def\n");
+    buf.extend(b"    /Ordering ");
+    buf.push_val(info.ordering);
+    buf.extend(b" def\n");
+    buf.extend(b"    /Supplement ");
+    buf.push_val(info.supplement);
+    buf.extend(b" def\n");
+    buf.extend(b"end def\n");
+    buf.extend(b"/CMapName ");
+    buf.push_val(name);
+    buf.extend(b" def\n");
+    buf.extend(b"/CMapVersion 1 def\n");
+    buf.extend(b"/CMapType ");
+    buf.push_int(cmap_type);
+    buf.extend(b" def\n");
+    buf.extend(b"/WMode ");
+    buf.push_int(mode.to_int());
+    buf.extend(b" def\n");
+}
+
+/// Write the end of a CMap program.
+fn write_cmap_footer(buf: &mut Buf) {
+    buf.extend(b"endcmap\n");
+    buf.extend(b"CMapName currentdict /CMap defineresource pop\n");
+    buf.extend(b"end\n");
+    buf.extend(b"end\n");
+    buf.extend(b"%%EndResource\n");
+    buf.extend(b"%%EOF");
+}
+
+/// A builder for general character map streams.
+///
+/// Unlike [`UnicodeCmap`], this supports multiple codespace ranges with codes
+/// of different lengths. It can build both _encoding CMaps_, which map codes
+/// to CIDs and are used with [`Type0Font::encoding_cmap`], and `/ToUnicode`
+/// CMaps, which map codes to text. Mappings for consecutive codes are
+/// automatically combined into ranges.
+///
+/// Codes are between one and four bytes long. All methods that take codes
+/// panic if a code has another length or if the two codes of a range differ
+/// in length.
+///
+/// ```
+/// use pdf_writer::types::{CmapBuilder, SystemInfo};
+/// use pdf_writer::{Name, Str};
+///
+/// let info = SystemInfo {
+///     registry: Str(b"Adobe"),
+///     ordering: Str(b"Identity"),
+///     supplement: 0,
+/// };
+///
+/// // Single-byte codes for ASCII and two-byte codes for everything else.
+/// let mut cmap = CmapBuilder::new(Name(b"Custom-H"), info);
+/// cmap.codespace_range(&[0x00], &[0x7F])
+///     .codespace_range(&[0x80, 0x00], &[0xFF, 0xFF])
+///     .cid_range(&[0x20], &[0x7E], 1)
+///     .cid_char(&[0x80, 0x00], 96);
+/// let data = cmap.finish();
+/// ```
+pub struct CmapBuilder<'a> {
+    name: Name<'a>,
+    info: SystemInfo<'a>,
+    mode: WMode,
+    use_cmap: Option<Name<'a>>,
+    codespace: Vec<(Vec<u8>, Vec<u8>)>,
+    cid_ranges: Vec<(Vec<u8>, Vec<u8>, u32)>,
+    cid_chars: Vec<(Vec<u8>, u32)>,
+    notdef_ranges: Vec<(Vec<u8>, Vec<u8>, u32)>,
+    bf_chars: Vec<(Vec<u8>, Vec<u16>)>,
+}
+
+impl<'a> CmapBuilder<'a> {
+    /// Create a new, empty character map for a horizontal writing mode font.
+    pub fn new(name: Name<'a>, info: SystemInfo<'a>) -> Self {
+        Self {
+            name,
+            info,
+            mode: WMode::Horizontal,
+            use_cmap: None,
+            codespace: vec![],
+            cid_ranges: vec![],
+            cid_chars: vec![],
+            notdef_ranges: vec![],
+            bf_chars: vec![],
+        }
+    }
+
+    /// Set the writing mode.
+    pub fn writing_mode(&mut self, mode: WMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Extend another CMap, for example a predefined one like `/Identity-H`.
+    ///
+    /// The mappings of this CMap take precedence over those of the base CMap.
+    /// When the CMap is embedded, the base must also be given with
+    /// [`Cmap::use_cmap_predefined`] or [`Cmap::use_cmap_stream`].
+    pub fn use_cmap(&mut self, base: Name<'a>) -> &mut Self {
+        self.use_cmap = Some(base);
+        self
+    }
+
+    /// Add a codespace range, which determines how many bytes of a string
+    /// form a code.
+    ///
+    /// A code belongs to the range if each of its bytes is between the
+    /// corresponding bytes of `low` and `high`.
+    pub fn codespace_range(&mut self, low: &[u8], high: &[u8]) -> &mut Self {
+        check_code_range(low, high);
+        self.codespace.push((low.to_vec(), high.to_vec()));
+        self
+    }
+
+    /// Map a range of codes to consecutive CIDs starting at `cid`.
+    pub fn cid_range(&mut self, low: &[u8], high: &[u8], cid: u32) -> &mut Self {
+        check_code_range(low, high);
+        self.cid_ranges.push((low.to_vec(), high.to_vec(), cid));
+        self
+    }
+
+    /// Map a single code to a CID.
+    ///
+    /// If a code is mapped more than once, the first mapping is used.
+    pub fn cid_char(&mut self, code: &[u8], cid: u32) -> &mut Self {
+        check_code_range(code, code);
+        self.cid_chars.push((code.to_vec(), cid));
+        self
+    }
+
+    /// Map a range of codes to the CID that is used when a code has no glyph
+    /// in the font.
+    pub fn notdef_range(&mut self, low: &[u8], high: &[u8], cid: u32) -> &mut Self {
+        check_code_range(low, high);
+        self.notdef_ranges.push((low.to_vec(), high.to_vec(), cid));
+        self
+    }
+
+    /// Map a code to the text it represents for a `/ToUnicode` CMap.
+    ///
+    /// If a code is mapped more than once, the first mapping is used.
+    pub fn bf_char(&mut self, code: &[u8], text: &str) -> &mut Self {
+        check_code_range(code, code);
+        self.bf_chars.push((code.to_vec(), text.encode_utf16().collect()));
+        self
+    }
+
+    /// Finish building the character map.
+    pub fn finish(self) -> Buf {
+        let mut buf = Buf::new();
+        let cmap_type = if self.bf_chars.is_empty() { 1 } else { 2 };
+        write_cmap_header(
+            &mut buf,
+            self.name,
+            self.info,
+            self.mode,
+            cmap_type,
+            self.use_cmap,
+        );
+
+        write_cmap_section(
+            &mut buf,
+            "codespacerange",
+            &self.codespace,
+            |buf, (low, high)| {
+                push_code(buf, low);
+                buf.push(b' ');
+                push_code(buf, high);
+            },
+        );
+
+        // Combine runs of single CIDs into ranges.
+        let mut cid_ranges = self.cid_ranges;
+        let mut cid_chars = vec![];
+        for (code, cid, len) in compact_mappings(self.cid_chars, |&cid, &next| {
+            cid.checked_add(1) == Some(next)
+        }) {
+            if len == 1 {
+                cid_chars.push((code, cid));
+            } else {
+                let mut high = code.clone();
+                *high.last_mut().unwrap() += (len - 1) as u8;
+                cid_ranges.push((code, high, cid));
+            }
+        }
+
+        let push_range = |buf: &mut Buf, (low, high, cid): &(Vec<u8>, Vec<u8>, u32)| {
+            push_code(buf, low);
+            buf.push(b' ');
+            push_code(buf, high);
+            buf.push(b' ');
+            buf.push_val(*cid as i32);
+        };
+        write_cmap_section(&mut buf, "cidrange", &cid_ranges, push_range);
+        write_cmap_section(&mut buf, "cidchar", &cid_chars, |buf, (code, cid)| {
+            push_code(buf, code);
+            buf.push(b' ');
+            buf.push_val(*cid as i32);
+        });
+        write_cmap_section(&mut buf, "notdefrange", &self.notdef_ranges, push_range);
+
+        // Combine runs of single characters into ranges. Only the last byte of
+        // the text may change within a range.
+        let mut bf_ranges = vec![];
+        let mut bf_chars = vec![];
+        for (code, text, len) in compact_mappings(self.bf_chars, |text, next| {
+            match (text.split_last(), next.split_last()) {
+                (Some((&last, rest)), Some((&next_last, next_rest))) => {
+                    rest == next_rest && last & 0xFF != 0xFF && last + 1 == next_last
+                }
+                _ => false,
+            }
+        }) {
+            if len == 1 {
+                bf_chars.push((code, text));
+            } else {
+                let mut high = code.clone();
+                *high.last_mut().unwrap() += (len - 1) as u8;
+                bf_ranges.push((code, high, text));
+            }
+        }
+
+        let push_text = |buf: &mut Buf, text: &[u16]| {
+            buf.push(b'<');
+            for &unit in text {
+                buf.push_hex_u16(unit);
+            }
+            buf.push(b'>');
+        };
+        write_cmap_section(&mut buf, "bfchar", &bf_chars, |buf, (code, text)| {
+            push_code(buf, code);
+            buf.push(b' ');
+            push_text(buf, text);
+        });
+        write_cmap_section(&mut buf, "bfrange", &bf_ranges, |buf, (low, high, text)| {
+            push_code(buf, low);
+            buf.push(b' ');
+            push_code(buf, high);
+            buf.push(b' ');
+            push_text(buf, text);
+        });
+
+        write_cmap_footer(&mut buf);
+        buf
+    }
+}
+
+/// Panics if the two codes don't have the same, valid length.
+fn check_code_range(low: &[u8], high: &[u8]) {
+    assert!((1..=4).contains(&low.len()), "codes must be one to four bytes long");
+    assert_eq!(low.len(), high.len(), "codes of a range must have the same length");
+}
+
+/// Sort single mappings by code and group runs of codes that only differ in
+/// their last byte and whose values are consecutive.
+///
+/// Yields the first code and value of each run together with its length.
+fn compact_mappings<T: Clone>(
+    mut mappings: Vec<(Vec<u8>, T)>,
+    consecutive: impl Fn(&T, &T) -> bool,
+) -> Vec<(Vec<u8>, T, usize)> {
+    mappings.sort_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    mappings.dedup_by(|(a, _), (b, _)| a == b);
+
+    let mut runs: Vec<(Vec<u8>, T, usize)> = vec![];
+    let mut prev: Option<(Vec<u8>, T)> = None;
+    for (code, value) in mappings {
+        let continues = prev.as_ref().is_some_and(|(prev_code, prev_value)| {
+            let (&last, rest) = code.split_last().unwrap();
+            let (&prev_last, prev_rest) = prev_code.split_last().unwrap();
+            rest == prev_rest
+                && prev_last.checked_add(1) == Some(last)
+                && consecutive(prev_value, &value)
+        });
+        match runs.last_mut() {
+            Some((_, _, len)) if continues => *len += 1,
+            _ => runs.push((code.clone(), value.clone(), 1)),
+        }
+        prev = Some((code, value));
+    }
+    runs
+}
+
 /// Type3 fonts require (in Acrobat at least) IDs in CMaps to be encoded with
 /// one byte only, whereas other font types use two bytes.
 ///
@@ -1051,3 +1578,204 @@ impl SystemInfo<'_> {
             .pair(Name(b"Supplement"), self.supplement);
     }
 }
+
+/// An error that occurs when a font cannot be read.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum FontError {
+    /// The data is not a TrueType or OpenType font or font collection, or, for
+    /// [`Type1Program`](crate::Type1Program), not a PFB or PFA font.
+    UnknownFormat,
+    /// The font collection has no face with the requested index.
+    FaceIndexOutOfBounds,
+    /// A required table is missing.
+    MissingTable(&'static str),
+    /// A table is malformed.
+    MalformedTable(&'static str),
+    /// The font uses a feature that is not supported, like `CFF2` outlines.
+    Unsupported(&'static str),
+    /// A part of a Type 1 font program is malformed.
+    MalformedProgram(&'static str),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownFormat => f.write_str("unknown font format"),
+            Self::FaceIndexOutOfBounds => f.write_str("face index is out of bounds"),
+            Self::MissingTable(table) => write!(f, "missing `{table}` table"),
+            Self::MalformedTable(table) => write!(f, "malformed `{table}` table"),
+            Self::Unsupported(feature) => write!(f, "unsupported {feature}"),
+            Self::MalformedProgram(part) => write!(f, "malformed {part} of font program"),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_widths() {
+        use WidthsEntry::*;
+        assert_eq!(compact_widths([], 1000.0), []);
+        assert_eq!(compact_widths([(1, 1000.0), (2, 1000.0)], 1000.0), []);
+        assert_eq!(
+            compact_widths([(5, 500.0), (3, 500.0), (4, 500.0), (4, 600.0)], 1000.0),
+            [Same(3, 5, 500.0)],
+        );
+        assert_eq!(compact_widths([(7, 250.0)], 1000.0), [Consecutive(7, vec![250.0])]);
+        assert_eq!(
+            compact_widths([(1, 250.0), (2, 300.0), (4, 350.0), (10, 400.0)], 1000.0),
+            [
+                Consecutive(1, vec![250.0, 300.0, 1000.0, 350.0]),
+                Consecutive(10, vec![400.0])
+            ],
+        );
+        assert_eq!(
+            compact_widths(
+                [(1, 250.0), (2, 300.0)]
+                    .into_iter()
+                    .chain((3..10).map(|cid| (cid, 500.0)))
+                    .chain([(10, 300.0)]),
+                1000.0,
+            ),
+            [
+                Consecutive(1, vec![250.0, 300.0]),
+                Same(3, 9, 500.0),
+                Consecutive(10, vec![300.0]),
+            ],
+        );
+        assert_eq!(
+            compact_widths([(1, 250.0), (2, 500.0), (3, 500.0), (4, 300.0)], 1000.0),
+            [Consecutive(1, vec![250.0, 500.0, 500.0, 300.0])],
+        );
+        assert_eq!(
+            compact_widths([(65534, 500.0), (65535, 500.0)], 0.0),
+            [Same(65534, 65535, 500.0)],
+        );
+    }
+
+    #[test]
+    fn test_vertical_metrics() {
+        test_obj!(
+            |obj| {
+                let mut font = obj.start::<CidFont>();
+                font.default_vertical_metrics(880.0, -1000.0);
+                font.vertical_metrics()
+                    .consecutive(1, [[-1000.0, 500.0, 880.0], [-500.0, 250.0, 880.0]])
+                    .same(10, 20, [-1000.0, 500.0, 900.0]);
+            },
+            b"<<",
+            b"  /Type /Font",
+            b"  /DW2 [880 -1000]",
+            b"  /W2 [1 [-1000 500 880 -500 250 880] 10 20 -1000 500 900]",
+            b">>",
+        );
+    }
+
+    #[test]
+    fn test_cmap_builder() {
+        let info = SystemInfo {
+            registry: Str(b"Adobe"),
+            ordering: Str(b"Japan1"),
+            supplement: 6,
+        };
+
+        let mut cmap = CmapBuilder::new(Name(b"Custom-V"), info);
+        cmap.writing_mode(WMode::Vertical)
+            .use_cmap(Name(b"Custom-H"))
+            .codespace_range(&[0x00], &[0x80])
+            .codespace_range(&[0x81, 0x40], &[0x9F, 0xFC])
+            .cid_range(&[0x20], &[0x7E], 1)
+            .cid_char(&[0x81, 0x42], 634)
+            .cid_char(&[0x81, 0x41], 633)
+            .cid_char(&[0x81, 0x43], 700)
+            .cid_char(&[0x81, 0x41], 1)
+            .cid_char(&[0x81, 0xFF], 701)
+            .cid_char(&[0x82, 0x00], 702)
+            .notdef_range(&[0x00], &[0x1F], 1);
+        let buf = cmap.finish();
+        let body = buf.as_slice().split(|&b| b == b'\n').skip(9);
+        assert_eq!(
+            body.take(29).collect::<Vec<_>>(),
+            [
+                &b"begincmap"[..],
+                b"/Custom-H usecmap",
+                b"/CIDSystemInfo 3 dict dup begin",
+                b"    /Registry (Adobe) def",
+                b"    /Ordering (Japan1) def",
+                b"    /Supplement 6 def",
+                b"end def",
+                b"/CMapName /Custom-V def",
+                b"/CMapVersion 1 def",
+                b"/CMapType 1 def",
+                b"/WMode 1 def",
+                b"2 begincodespacerange",
+                b"<00> <80>",
+                b"<8140> <9FFC>",
+                b"endcodespacerange",
+                b"2 begincidrange",
+                b"<20> <7E> 1",
+                b"<8141> <8142> 633",
+                b"endcidrange",
+                b"3 begincidchar",
+                b"<8143> 700",
+                b"<81FF> 701",
+                b"<8200> 702",
+                b"endcidchar",
+                b"1 beginnotdefrange",
+                b"<00> <1F> 1",
+                b"endnotdefrange",
+                b"endcmap",
+                b"CMapName currentdict /CMap defineresource pop",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cmap_builder_to_unicode() {
+        let info = SystemInfo {
+            registry: Str(b"Adobe"),
+            ordering: Str(b"Identity"),
+            supplement: 0,
+        };
+
+        let mut cmap = CmapBuilder::new(Name(b"Custom"), info);
+        cmap.codespace_range(&[0x00, 0x00], &[0xFF, 0xFF])
+            .bf_char(&[0x00, 0x03], "c")
+            .bf_char(&[0x00, 0x01], "a")
+            .bf_char(&[0x00, 0x02], "b")
+            .bf_char(&[0x00, 0x04], "ffi")
+            .bf_char(&[0x00, 0x05], "\u{FF}")
+            .bf_char(&[0x00, 0x06], "\u{100}")
+            .bf_char(&[0x00, 0x07], "😀")
+            .bf_char(&[0x00, 0x08], "😁");
+        let buf = cmap.finish();
+        let text = std::str::from_utf8(buf.as_slice()).unwrap();
+        assert!(text.contains("/CMapType 2 def\n"));
+        assert!(text.contains(concat!(
+            "3 beginbfchar\n",
+            "<0004> <006600660069>\n",
+            "<0005> <00FF>\n",
+            "<0006> <0100>\n",
+            "endbfchar\n",
+            "2 beginbfrange\n",
+            "<0001> <0003> <0061>\n",
+            "<0007> <0008> <D83DDE00>\n",
+            "endbfrange\n",
+        )));
+    }
+
+    #[test]
+    fn test_widths_compact() {
+        test_obj!(
+            |obj| obj
+                .start::<Widths>()
+                .compact([(0, 500.0), (9, 600.0), (8, 600.0), (7, 600.0)], 1000.0),
+            b"[0 [500] 7 9 600]",
+        );
+    }
+}
@@ -122,6 +122,156 @@ impl InterpolationOrder {
     }
 }
 
+/// Samples of a function for a [sampled function](SampledFunction).
+///
+/// The function is evaluated on an evenly spaced grid that spans the domain.
+/// Its outputs are clamped to the range and quantized to the given number of
+/// bits. Typical uses are tint transforms of `Separation` and `DeviceN` color
+/// spaces and gradients that no exponential function can express.
+///
+/// ```
+/// use pdf_writer::{Chunk, FunctionSamples, Ref};
+///
+/// // A tint transform from a spot color to CMYK.
+/// let domain = [0.0, 1.0];
+/// let range = [0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0];
+/// let samples = FunctionSamples::new(&domain, &range, &[2], 8, |x| {
+///     vec![0.0, 0.6 * x[0], 0.9 * x[0], 0.0]
+/// });
+///
+/// let mut chunk = Chunk::new();
+/// samples.write(&mut chunk, Ref::new(1));
+/// ```
+pub struct FunctionSamples {
+    domain: Vec<f32>,
+    range: Vec<f32>,
+    size: Vec<usize>,
+    bits: u8,
+    data: Vec<u8>,
+}
+
+impl FunctionSamples {
+    /// Sample a function.
+    ///
+    /// The domain and range contain a minimum and a maximum for each input
+    /// and output, respectively. The size contains the number of samples for
+    /// each input. The number of bits per sample must be 1, 2, 4, 8, 12, 16,
+    /// 24 or 32. Panics if these don't fit together or the function returns
+    /// the wrong number of outputs.
+    pub fn new(
+        domain: &[f32],
+        range: &[f32],
+        size: &[usize],
+        bits_per_sample: u8,
+        func: impl Fn(&[f32]) -> Vec<f32>,
+    ) -> Self {
+        assert!(
+            matches!(bits_per_sample, 1 | 2 | 4 | 8 | 12 | 16 | 24 | 32),
+            "bits per sample must be 1, 2, 4, 8, 12, 16, 24 or 32",
+        );
+        assert!(!size.is_empty(), "function must have at least one input");
+        assert_eq!(domain.len(), 2 * size.len(), "domain must have two values per input");
+        assert!(size.iter().all(|&n| n >= 1), "sizes must be at least one");
+        let outputs = range.len() / 2;
+        assert!(
+            outputs > 0 && 2 * outputs == range.len(),
+            "range must have two values per output",
+        );
+
+        let scale = ((1u64 << bits_per_sample) - 1) as f64;
+        let mut writer = SampleWriter::new(bits_per_sample);
+        let mut index = vec![0; size.len()];
+        let mut input = vec![0.0; size.len()];
+
+        // The first input varies fastest.
+        let count: usize = size.iter().product();
+        for _ in 0..count {
+            for (i, x) in input.iter_mut().enumerate() {
+                let (min, max) = (domain[2 * i], domain[2 * i + 1]);
+                *x = match size[i] {
+                    1 => min,
+                    n => min + (max - min) * index[i] as f32 / (n - 1) as f32,
+                };
+            }
+
+            let output = func(&input);
+            assert_eq!(output.len(), outputs, "function must have {outputs} outputs");
+            for (j, y) in output.into_iter().enumerate() {
+                let (min, max) = (f64::from(range[2 * j]), f64::from(range[2 * j + 1]));
+                let t = if max > min { (f64::from(y) - min) / (max - min) } else { 0.0 };
+                writer.push((t.clamp(0.0, 1.0) * scale).round() as u32);
+            }
+
+            for (i, n) in index.iter_mut().zip(size) {
+                *i += 1;
+                if *i < *n {
+                    break;
+                }
+                *i = 0;
+            }
+        }
+
+        Self {
+            domain: domain.to_vec(),
+            range: range.to_vec(),
+            size: size.to_vec(),
+            bits: bits_per_sample,
+            data: writer.finish(),
+        }
+    }
+
+    /// The packed samples.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Write the sampled function stream.
+    ///
+    /// Writes all attributes except for `/Order`, which can be added through
+    /// the returned writer. The `/Encode` and `/Decode` attributes map the
+    /// domain onto the whole sample grid and the samples onto the range.
+    pub fn write<'a>(&'a self, chunk: &'a mut Chunk, id: Ref) -> SampledFunction<'a> {
+        let mut func = chunk.sampled_function(id, &self.data);
+        func.domain(self.domain.iter().copied());
+        func.range(self.range.iter().copied());
+        func.size(self.size.iter().map(|&n| n as i32));
+        func.bits_per_sample(self.bits.into());
+        func.encode(self.size.iter().flat_map(|&n| [0.0, (n - 1) as f32]));
+        func.decode(self.range.iter().copied());
+        func
+    }
+}
+
+/// Packs samples of any width into a continuous big-endian bit stream.
+struct SampleWriter {
+    data: Vec<u8>,
+    bits: u8,
+    buffer: u64,
+    filled: u8,
+}
+
+impl SampleWriter {
+    fn new(bits: u8) -> Self {
+        Self { data: vec![], bits, buffer: 0, filled: 0 }
+    }
+
+    fn push(&mut self, sample: u32) {
+        self.buffer = (self.buffer << self.bits) | u64::from(sample);
+        self.filled += self.bits;
+        while self.filled >= 8 {
+            self.filled -= 8;
+            self.data.push((self.buffer >> self.filled) as u8);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.filled > 0 {
+            self.data.push((self.buffer << (8 - self.filled)) as u8);
+        }
+        self.data
+    }
+}
+
 /// Writer for an _exponential function dictionary_. PDF 1.3+.
 ///
 /// The function result is `y_i = C0_i + x^N * (C1_i - C0_i)` where `i` is the
@@ -430,6 +580,46 @@ impl PostScriptOp<'_> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_function_samples() {
+        // Two inputs with 3 and 2 samples, and 12-bit outputs.
+        let samples = FunctionSamples::new(
+            &[0.0, 1.0, -1.0, 1.0],
+            &[0.0, 2.0, 0.0, 1.0],
+            &[3, 2],
+            12,
+            |x| vec![x[0] + x[1], 0.5],
+        );
+
+        // The first input varies fastest and sums below zero are clamped.
+        let sums = [-1.0, -0.5, 0.0, 1.0, 1.5, 2.0];
+        let mut packed = vec![];
+        for sum in sums {
+            let first = (f64::max(sum, 0.0) / 2.0 * 4095.0).round() as u32;
+            let bits = (first << 12) | 2048;
+            packed.extend(&bits.to_be_bytes()[1..]);
+        }
+        assert_eq!(samples.as_bytes(), packed);
+
+        let mut chunk = Chunk::new();
+        samples
+            .write(&mut chunk, Ref::new(1))
+            .order(InterpolationOrder::Cubic);
+        let pdf = String::from_utf8_lossy(chunk.as_bytes());
+        assert!(pdf.contains("/Size [3 2]"));
+        assert!(pdf.contains("/BitsPerSample 12"));
+        assert!(pdf.contains("/Encode [0 2 0 1]"));
+        assert!(pdf.contains("/Decode [0 2 0 1]"));
+    }
+
+    #[test]
+    fn test_function_samples_padding() {
+        let samples = FunctionSamples::new(&[0.0, 1.0], &[0.0, 1.0], &[5], 1, |x| {
+            vec![if x[0] >= 0.5 { 1.0 } else { 0.0 }]
+        });
+        assert_eq!(samples.as_bytes(), [0b0011_1000]);
+    }
+
     #[test]
     fn test_post_script_encoding() {
         use PostScriptOp::*;
@@ -0,0 +1,375 @@
+use super::*;
+use crate::image::ImageError;
+use crate::reader::read_u32;
+use crate::types::RenderingIntent;
+
+/// Information from the header and tag table of an ICC profile.
+///
+/// Determines what has to be written into an [ICC profile
+/// stream](crate::writers::IccProfile) alongside the profile bytes: The number
+/// of components, a matching alternate color space and, for Lab profiles, the
+/// component ranges. Readers and PDF/A validators expect these entries to agree
+/// with the profile.
+///
+/// ```no_run
+/// use pdf_writer::{IccInfo, Pdf, Ref};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("FOGRA39.icc")?;
+/// let profile = IccInfo::new(&data)?;
+/// profile.validate_output_intent(2)?;
+///
+/// let mut pdf = Pdf::new();
+/// profile.write(&mut pdf, Ref::new(1));
+/// # Ok(())
+/// # }
+/// ```
+pub struct IccInfo<'a> {
+    data: &'a [u8],
+    color_space: IccColorSpace,
+    class: IccClass,
+    version: (u8, u8, u8),
+    intent: RenderingIntent,
+    /// The white point of the profile connection space.
+    illuminant: [f32; 3],
+    /// The signatures of the tags.
+    tags: Vec<[u8; 4]>,
+}
+
+/// The color space of the data that an ICC profile describes.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IccColorSpace {
+    /// `GRAY`, with one component.
+    Gray,
+    /// `RGB`, with three components.
+    Rgb,
+    /// `CMYK`, with four components.
+    Cmyk,
+    /// `Lab`, with three components.
+    Lab,
+}
+
+impl IccColorSpace {
+    /// The number of components.
+    pub fn n(self) -> i32 {
+        match self {
+            Self::Gray => 1,
+            Self::Rgb | Self::Lab => 3,
+            Self::Cmyk => 4,
+        }
+    }
+}
+
+/// The device class of an ICC profile.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IccClass {
+    /// `scnr`, for input devices like scanners and cameras.
+    Input,
+    /// `mntr`, for displays.
+    Display,
+    /// `prtr`, for output devices like printers.
+    Output,
+    /// `link`, converting directly between two device color spaces.
+    DeviceLink,
+    /// `spac`, converting between a color space and the connection space.
+    ColorSpace,
+    /// `abst`, applying an effect in the connection space.
+    Abstract,
+    /// `nmcl`, for named colors.
+    NamedColor,
+}
+
+impl<'a> IccInfo<'a> {
+    /// Parse the header and the tag table of an ICC profile.
+    ///
+    /// Fails for profiles whose data color space can't be used in PDF, that
+    /// is, anything but gray, RGB, CMYK and Lab.
+    pub fn new(data: &'a [u8]) -> Result<Self, ImageError> {
+        if data.get(36..40) != Some(b"acsp") {
+            return Err(ImageError::UnknownFormat);
+        }
+
+        let u32_at = |pos| read_u32(data, pos);
+        let size = u32_at(0).unwrap_or(0) as usize;
+        if size < 132 || size > data.len() {
+            return Err(ImageError::Malformed("profile header"));
+        }
+
+        let class = match &data[12..16] {
+            b"scnr" => IccClass::Input,
+            b"mntr" => IccClass::Display,
+            b"prtr" => IccClass::Output,
+            b"link" => IccClass::DeviceLink,
+            b"spac" => IccClass::ColorSpace,
+            b"abst" => IccClass::Abstract,
+            b"nmcl" => IccClass::NamedColor,
+            _ => return Err(ImageError::Malformed("profile class")),
+        };
+
+        let color_space = match &data[16..20] {
+            b"GRAY" => IccColorSpace::Gray,
+            b"RGB " => IccColorSpace::Rgb,
+            b"CMYK" => IccColorSpace::Cmyk,
+            b"Lab " => IccColorSpace::Lab,
+            _ => return Err(ImageError::Unsupported("profile color space")),
+        };
+
+        let intent = match u32_at(64) {
+            Some(0) => RenderingIntent::Perceptual,
+            Some(1) => RenderingIntent::RelativeColorimetric,
+            Some(2) => RenderingIntent::Saturation,
+            Some(3) => RenderingIntent::AbsoluteColorimetric,
+            _ => return Err(ImageError::Malformed("rendering intent")),
+        };
+
+        let s15_fixed16 = |pos| u32_at(pos).unwrap() as i32 as f32 / 65536.0;
+        let illuminant = [s15_fixed16(68), s15_fixed16(72), s15_fixed16(76)];
+
+        let count = u32_at(128).unwrap() as usize;
+        if count > (size - 132) / 12 {
+            return Err(ImageError::Malformed("tag table"));
+        }
+
+        let mut tags = Vec::with_capacity(count);
+        for entry in data[132..132 + 12 * count].chunks_exact(12) {
+            let offset = read_u32(entry, 4).unwrap() as usize;
+            let len = read_u32(entry, 8).unwrap() as usize;
+            if offset.checked_add(len).is_none_or(|end| end > size) {
+                return Err(ImageError::Malformed("tag table"));
+            }
+
+            tags.push(entry[..4].try_into().unwrap());
+        }
+
+        Ok(Self {
+            data: &data[..size],
+            color_space,
+            class,
+            version: (data[8], data[9] >> 4, data[9] & 0xF),
+            intent,
+            illuminant,
+            tags,
+        })
+    }
+
+    /// The color space of the data that the profile describes.
+    pub fn color_space(&self) -> IccColorSpace {
+        self.color_space
+    }
+
+    /// The number of components, as written into the `/N` attribute.
+    pub fn n(&self) -> i32 {
+        self.color_space.n()
+    }
+
+    /// The device class of the profile.
+    pub fn class(&self) -> IccClass {
+        self.class
+    }
+
+    /// The major, minor and bug fix version of the profile.
+    pub fn version(&self) -> (u8, u8, u8) {
+        self.version
+    }
+
+    /// The rendering intent that the profile was created for.
+    pub fn rendering_intent(&self) -> RenderingIntent {
+        self.intent
+    }
+
+    /// Whether the profile has a transform for the given rendering intent.
+    ///
+    /// Matrix/TRC profiles, which have no lookup tables, use the same
+    /// transform for all intents.
+    pub fn has_intent(&self, intent: RenderingIntent) -> bool {
+        let index = match intent {
+            RenderingIntent::Perceptual => b'0',
+            RenderingIntent::RelativeColorimetric
+            | RenderingIntent::AbsoluteColorimetric => b'1',
+            RenderingIntent::Saturation => b'2',
+        };
+
+        let has_tag = |tag: &[u8; 4]| self.tags.contains(tag);
+        let lut =
+            |prefix: &[u8; 3], index| has_tag(&[prefix[0], prefix[1], prefix[2], index]);
+
+        let has_luts = (b'0'..=b'2').any(|i| lut(b"A2B", i) || lut(b"B2A", i));
+        lut(b"A2B", index)
+            || lut(b"B2A", index)
+            || (!has_luts && (has_tag(b"kTRC") || has_tag(b"rTRC")))
+    }
+
+    /// Check the restrictions that the given part of PDF/A imposes on profiles
+    /// of `ICCBased` color spaces, e.g. `2` for PDF/A-2b.
+    ///
+    /// The profile must be an input, display, output or color space profile.
+    /// PDF/A-1 only permits version 2 profiles, later parts also permit
+    /// versions 3 and 4.
+    pub fn validate_pdfa(&self, part: u8) -> Result<(), ImageError> {
+        let max_version = if part == 1 { 2 } else { 4 };
+        if self.version.0 > max_version {
+            return Err(ImageError::Forbidden("ICC profile version"));
+        }
+
+        if !matches!(
+            self.class,
+            IccClass::Input | IccClass::Display | IccClass::Output | IccClass::ColorSpace
+        ) {
+            return Err(ImageError::Forbidden("ICC profile class"));
+        }
+
+        Ok(())
+    }
+
+    /// Check the restrictions that PDF/A imposes on the
+    /// [destination profile](crate::writers::OutputIntent::dest_output_profile)
+    /// of an output intent.
+    ///
+    /// In addition to the [general restrictions](Self::validate_pdfa), the
+    /// profile must be an output or display profile for gray, RGB or CMYK
+    /// data.
+    pub fn validate_output_intent(&self, part: u8) -> Result<(), ImageError> {
+        self.validate_pdfa(part)?;
+
+        if !matches!(self.class, IccClass::Output | IccClass::Display) {
+            return Err(ImageError::Forbidden("output intent profile class"));
+        }
+
+        if self.color_space == IccColorSpace::Lab {
+            return Err(ImageError::Forbidden("Lab output intent profile"));
+        }
+
+        Ok(())
+    }
+
+    /// Write the ICC profile stream with its `/N`, `/Alternate` and, for Lab
+    /// profiles, `/Range` attributes.
+    pub fn write(&self, chunk: &mut Chunk, id: Ref) {
+        let mut profile = chunk.icc_profile(id, self.data);
+        profile.n(self.n());
+
+        let alternate = profile.alternate();
+        match self.color_space {
+            IccColorSpace::Gray => alternate.device_gray(),
+            IccColorSpace::Rgb => alternate.device_rgb(),
+            IccColorSpace::Cmyk => alternate.device_cmyk(),
+            IccColorSpace::Lab => {
+                alternate.lab(self.illuminant, None, Some([-128.0, 127.0, -128.0, 127.0]))
+            }
+        }
+
+        if self.color_space == IccColorSpace::Lab {
+            profile.range([0.0, 100.0, -128.0, 127.0, -128.0, 127.0]);
+        }
+    }
+}
+
+/// Builds small ICC profiles for tests.
+#[cfg(test)]
+pub(crate) mod fixture {
+    /// Build an ICC profile with empty tags.
+    pub(crate) fn profile(
+        class: &[u8; 4],
+        space: &[u8; 4],
+        version: u8,
+        tags: &[&[u8; 4]],
+    ) -> Vec<u8> {
+        let size = 132 + 12 * tags.len();
+        let mut data = vec![0; 128];
+        data[..4].copy_from_slice(&(size as u32).to_be_bytes());
+        data[8] = version;
+        data[9] = 0x30;
+        data[12..16].copy_from_slice(class);
+        data[16..20].copy_from_slice(space);
+        data[20..24].copy_from_slice(b"XYZ ");
+        data[36..40].copy_from_slice(b"acsp");
+        data[64..68].copy_from_slice(&1u32.to_be_bytes());
+        data[68..80].copy_from_slice(&[0, 0, 0xF6, 0xD6, 0, 1, 0, 0, 0, 0, 0xD3, 0x2D]);
+        data.extend((tags.len() as u32).to_be_bytes());
+        for tag in tags {
+            data.extend(*tag);
+            data.extend((size as u32).to_be_bytes());
+            data.extend(0u32.to_be_bytes());
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icc::fixture::profile;
+
+    #[test]
+    fn test_icc_cmyk_output() {
+        let data = profile(b"prtr", b"CMYK", 2, &[b"A2B0", b"A2B1", b"B2A0", b"B2A1"]);
+        let icc = IccInfo::new(&data).unwrap();
+        assert_eq!(icc.n(), 4);
+        assert_eq!(icc.class(), IccClass::Output);
+        assert_eq!(icc.version(), (2, 3, 0));
+        assert_eq!(icc.rendering_intent(), RenderingIntent::RelativeColorimetric);
+        assert!(icc.has_intent(RenderingIntent::Perceptual));
+        assert!(icc.has_intent(RenderingIntent::AbsoluteColorimetric));
+        assert!(!icc.has_intent(RenderingIntent::Saturation));
+        assert_eq!(icc.validate_output_intent(1), Ok(()));
+
+        let mut chunk = Chunk::new();
+        icc.write(&mut chunk, Ref::new(1));
+        let pdf = String::from_utf8_lossy(chunk.as_bytes());
+        assert!(pdf.contains("/N 4\n  /Alternate /DeviceCMYK"));
+    }
+
+    #[test]
+    fn test_icc_lab() {
+        let data = profile(b"spac", b"Lab ", 4, &[]);
+        let icc = IccInfo::new(&data).unwrap();
+        assert_eq!(icc.validate_pdfa(2), Ok(()));
+        assert_eq!(
+            icc.validate_output_intent(2),
+            Err(ImageError::Forbidden("output intent profile class"))
+        );
+
+        let mut chunk = Chunk::new();
+        icc.write(&mut chunk, Ref::new(1));
+        let pdf = String::from_utf8_lossy(chunk.as_bytes());
+        assert!(
+            pdf.contains("/Alternate [/Lab <<\n    /WhitePoint [0.9642029 1 0.8249054]")
+        );
+        assert!(pdf.contains("/Range [-128 127 -128 127]"));
+        assert!(pdf.contains("/Range [0 100 -128 127 -128 127]"));
+    }
+
+    #[test]
+    fn test_icc_matrix_trc() {
+        let data = profile(b"mntr", b"RGB ", 4, &[b"rXYZ", b"rTRC"]);
+        let icc = IccInfo::new(&data).unwrap();
+        assert!(icc.has_intent(RenderingIntent::Saturation));
+        assert_eq!(icc.validate_output_intent(4), Ok(()));
+    }
+
+    #[test]
+    fn test_icc_errors() {
+        let data = profile(b"mntr", b"RGB ", 5, &[]);
+        let icc = IccInfo::new(&data).unwrap();
+        let version = Err(ImageError::Forbidden("ICC profile version"));
+        assert_eq!(icc.validate_pdfa(2), version);
+
+        let data = profile(b"mntr", b"RGB ", 4, &[]);
+        let icc = IccInfo::new(&data).unwrap();
+        assert_eq!(icc.validate_pdfa(1), version);
+        assert_eq!(icc.validate_pdfa(3), Ok(()));
+
+        let data = profile(b"link", b"RGB ", 4, &[]);
+        let icc = IccInfo::new(&data).unwrap();
+        assert_eq!(icc.validate_pdfa(2), Err(ImageError::Forbidden("ICC profile class")));
+
+        let data = profile(b"prtr", b"6CLR", 2, &[]);
+        let err = IccInfo::new(&data).err();
+        assert_eq!(err, Some(ImageError::Unsupported("profile color space")));
+
+        let mut data = profile(b"prtr", b"CMYK", 2, &[b"A2B0"]);
+        data[136..140].copy_from_slice(&1000u32.to_be_bytes());
+        assert_eq!(IccInfo::new(&data).err(), Some(ImageError::Malformed("tag table")));
+        assert_eq!(IccInfo::new(b"\x89PNG").err(), Some(ImageError::UnknownFormat));
+    }
+}
@@ -0,0 +1,430 @@
+use std::fmt::{self, Display, Formatter};
+
+use super::*;
+use crate::deflate::{self, Deflate};
+
+/// An error that occurs when an image or an ICC profile cannot be read.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum ImageError {
+    /// The data is not in the expected image or profile format.
+    UnknownFormat,
+    /// A part of the image or profile is malformed.
+    Malformed(&'static str),
+    /// The image or profile uses a feature that is not supported.
+    Unsupported(&'static str),
+    /// The image or profile uses a feature that PDF/A forbids.
+    Forbidden(&'static str),
+}
+
+impl Display for ImageError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownFormat => f.write_str("unknown image or profile format"),
+            Self::Malformed(part) => write!(f, "malformed {part}"),
+            Self::Unsupported(feature) => write!(f, "unsupported {feature}"),
+            Self::Forbidden(feature) => write!(f, "{feature} is forbidden in PDF/A"),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+/// Raw pixel data for embedding as an image XObject.
+///
+/// Takes interleaved samples and splits them into the color samples of the
+/// image and, if there is an alpha channel, the samples of its soft mask. An
+/// alpha channel that is fully opaque is dropped. Premultiplied alpha is
+/// expressed through the soft mask's [`/Matte`](ImageXObject::matte) entry,
+/// so the color samples are embedded unchanged.
+///
+/// An image whose visible pixels all have the same color and are either fully
+/// opaque or fully transparent can optionally be written as a
+/// [stencil mask](Self::stencil), which is smaller and keeps sharp edges.
+///
+/// ```
+/// use pdf_writer::{Chunk, PixelFormat, RawImage, Ref};
+///
+/// // A 2x1 image with a red and a half-transparent blue pixel.
+/// let pixels = [255, 0, 0, 255, 0, 0, 255, 128];
+/// let image = RawImage::new(&pixels, 2, 1, PixelFormat::Rgba, 8).unwrap();
+/// assert!(image.has_alpha());
+///
+/// let mut chunk = Chunk::new();
+/// image.write(&mut chunk, Ref::new(1), &mut Ref::new(2));
+/// ```
+pub struct RawImage {
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    bits: u8,
+    /// The color samples, with rows starting at byte boundaries.
+    samples: Vec<u8>,
+    /// The alpha samples, if the alpha channel isn't fully opaque.
+    alpha: Option<Vec<u8>>,
+    /// The stencil mask and its color, if the image has just one color.
+    stencil: Option<(Vec<u8>, Vec<f32>)>,
+    premultiplied: bool,
+    use_stencil: bool,
+    deflate: Option<Deflate>,
+}
+
+/// The channels of the pixels in a [`RawImage`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PixelFormat {
+    /// A gray channel.
+    Gray,
+    /// A gray and an alpha channel.
+    GrayAlpha,
+    /// Red, green and blue channels.
+    Rgb,
+    /// Red, green, blue and alpha channels.
+    Rgba,
+    /// Cyan, magenta, yellow and black channels.
+    Cmyk,
+}
+
+impl PixelFormat {
+    /// The number of color channels, excluding alpha.
+    fn color_channels(self) -> usize {
+        match self {
+            Self::Gray | Self::GrayAlpha => 1,
+            Self::Rgb | Self::Rgba => 3,
+            Self::Cmyk => 4,
+        }
+    }
+
+    /// Whether the pixels have an alpha channel.
+    fn has_alpha(self) -> bool {
+        matches!(self, Self::GrayAlpha | Self::Rgba)
+    }
+}
+
+impl RawImage {
+    /// Prepare interleaved pixel data.
+    ///
+    /// Samples have `bits` bits, which must be 1, 2, 4, 8 or 16. Samples with
+    /// 16 bits are big-endian and samples with fewer than 8 bits are packed
+    /// most significant bit first. Each row starts at a byte boundary.
+    pub fn new(
+        data: &[u8],
+        width: u32,
+        height: u32,
+        format: PixelFormat,
+        bits: u8,
+    ) -> Result<Self, ImageError> {
+        if ![1, 2, 4, 8, 16].contains(&bits) {
+            return Err(ImageError::Unsupported("bit depth"));
+        }
+
+        let malformed = ImageError::Malformed("pixel data");
+        if width == 0
+            || height == 0
+            || width > i32::MAX as u32
+            || height > i32::MAX as u32
+        {
+            return Err(malformed);
+        }
+
+        let colors = format.color_channels();
+        let channels = colors + usize::from(format.has_alpha());
+        let (width_px, height_px) = (width as usize, height as usize);
+        let stride = (width_px * channels * usize::from(bits)).div_ceil(8);
+        if data.len() < stride.checked_mul(height_px).ok_or(malformed)? {
+            return Err(malformed);
+        }
+
+        let max = ((1u32 << bits) - 1) as u16;
+        let mut samples = Packer::new(bits);
+        let mut alpha = Packer::new(bits);
+        let mut stencil = Packer::new(1);
+        let mut opaque = true;
+        let mut single: Option<Option<Vec<u16>>> = None;
+        let mut color = Vec::with_capacity(colors);
+
+        for row in data.chunks_exact(stride.max(1)).take(height_px) {
+            for x in 0..width_px {
+                color.clear();
+                for c in 0..colors {
+                    let sample = read_sample(row, x * channels + c, bits);
+                    samples.push(sample);
+                    color.push(sample);
+                }
+
+                let a = if format.has_alpha() {
+                    let a = read_sample(row, x * channels + colors, bits);
+                    alpha.push(a);
+                    opaque &= a == max;
+                    a
+                } else {
+                    max
+                };
+
+                // Track whether all visible pixels share one color and are
+                // either fully opaque or fully transparent.
+                stencil.push(u16::from(a != max));
+                if a == max {
+                    match &single {
+                        None => single = Some(Some(color.clone())),
+                        Some(Some(first)) if *first != color => single = Some(None),
+                        _ => {}
+                    }
+                } else if a != 0 {
+                    single = Some(None);
+                }
+            }
+
+            samples.end_row();
+            alpha.end_row();
+            stencil.end_row();
+        }
+
+        let stencil = match single {
+            Some(Some(first)) => {
+                let color =
+                    first.iter().map(|&s| f32::from(s) / f32::from(max)).collect();
+                Some((stencil.data, color))
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            width,
+            height,
+            format,
+            bits,
+            samples: samples.data,
+            alpha: (format.has_alpha() && !opaque).then_some(alpha.data),
+            stencil,
+            premultiplied: false,
+            use_stencil: false,
+            deflate: None,
+        })
+    }
+
+    /// Whether the color samples are premultiplied with alpha. Defaults to
+    /// `false`.
+    ///
+    /// If `true`, the soft mask gets a `/Matte` entry with the color black, so
+    /// that viewers undo the premultiplication.
+    pub fn premultiplied(&mut self, premultiplied: bool) -> &mut Self {
+        self.premultiplied = premultiplied;
+        self
+    }
+
+    /// Whether to write an image with a single color as a stencil mask.
+    /// Defaults to `false`.
+    ///
+    /// A stencil mask paints the current fill color, which must be set to the
+    /// [stencil color](Self::stencil_color) before drawing the image.
+    pub fn stencil(&mut self, stencil: bool) -> &mut Self {
+        self.use_stencil = stencil;
+        self
+    }
+
+    /// Compress the streams with a [Deflate function](crate#compression).
+    pub fn deflate(&mut self, deflate: fn(&[u8]) -> Vec<u8>) -> &mut Self {
+        self.deflate = Some(deflate);
+        self
+    }
+
+    /// The width of the image in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the image in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Whether the image is written with a soft mask.
+    pub fn has_alpha(&self) -> bool {
+        self.alpha.is_some() && !self.is_stencil()
+    }
+
+    /// The color of all visible pixels, if the image only has one color and
+    /// no partial transparency.
+    ///
+    /// The components are in the range from zero to one and in the color
+    /// space of the pixel format.
+    pub fn stencil_color(&self) -> Option<&[f32]> {
+        self.stencil.as_ref().map(|(_, color)| color.as_slice())
+    }
+
+    /// Whether the image is written as a stencil mask.
+    pub fn is_stencil(&self) -> bool {
+        self.use_stencil && self.stencil.is_some()
+    }
+
+    /// Write the image XObject.
+    ///
+    /// If the image has a soft mask, its ID is taken from `alloc`.
+    pub fn write(&self, chunk: &mut Chunk, id: Ref, alloc: &mut Ref) {
+        if let (true, Some((mask, _))) = (self.use_stencil, &self.stencil) {
+            let data = deflate::compress(self.deflate, mask);
+            let mut image = chunk.image_xobject(id, &data);
+            self.write_common(&mut image);
+            image.image_mask(true);
+            image.bits_per_component(1);
+            return;
+        }
+
+        let mask_id = self.alpha.as_ref().map(|_| alloc.bump());
+        let data = deflate::compress(self.deflate, &self.samples);
+        let mut image = chunk.image_xobject(id, &data);
+        self.write_common(&mut image);
+        match self.format {
+            PixelFormat::Gray | PixelFormat::GrayAlpha => {
+                image.color_space().device_gray()
+            }
+            PixelFormat::Rgb | PixelFormat::Rgba => image.color_space().device_rgb(),
+            PixelFormat::Cmyk => image.color_space().device_cmyk(),
+        }
+        image.bits_per_component(self.bits.into());
+        if let Some(mask_id) = mask_id {
+            image.s_mask(mask_id);
+        }
+        image.finish();
+
+        if let (Some(mask_id), Some(alpha)) = (mask_id, &self.alpha) {
+            let data = deflate::compress(self.deflate, alpha);
+            let mut mask = chunk.image_xobject(mask_id, &data);
+            self.write_common(&mut mask);
+            mask.color_space().device_gray();
+            mask.bits_per_component(self.bits.into());
+            if self.premultiplied {
+                mask.matte(vec![0.0; self.format.color_channels()]);
+            }
+        }
+    }
+
+    /// Write the attributes shared by the image and its mask.
+    fn write_common(&self, image: &mut ImageXObject) {
+        image.width(self.width as i32);
+        image.height(self.height as i32);
+        deflate::filter(self.deflate, image);
+    }
+}
+
+/// Read the `i`-th sample of a row.
+fn read_sample(row: &[u8], i: usize, bits: u8) -> u16 {
+    match bits {
+        16 => u16::from_be_bytes([row[2 * i], row[2 * i + 1]]),
+        8 => row[i].into(),
+        _ => {
+            let offset = i * usize::from(bits);
+            let shift = 8 - usize::from(bits) - offset % 8;
+            u16::from((row[offset / 8] >> shift) & ((1 << bits) - 1))
+        }
+    }
+}
+
+/// Packs samples into rows that start at byte boundaries.
+struct Packer {
+    data: Vec<u8>,
+    bits: u8,
+    /// The number of bits used in the last byte, zero if it is full.
+    used: u8,
+}
+
+impl Packer {
+    fn new(bits: u8) -> Self {
+        Self { data: vec![], bits, used: 0 }
+    }
+
+    fn push(&mut self, sample: u16) {
+        match self.bits {
+            16 => self.data.extend(sample.to_be_bytes()),
+            8 => self.data.push(sample as u8),
+            bits => {
+                if self.used == 0 {
+                    self.data.push(0);
+                }
+                *self.data.last_mut().unwrap() |=
+                    (sample as u8) << (8 - self.used - bits);
+                self.used = (self.used + bits) % 8;
+            }
+        }
+    }
+
+    fn end_row(&mut self) {
+        self.used = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_image_alpha() {
+        // 16-bit gray with alpha, premultiplied.
+        let data = [0x12, 0x34, 0xFF, 0xFF, 0x00, 0x00, 0x80, 0x00];
+        let mut image = RawImage::new(&data, 2, 1, PixelFormat::GrayAlpha, 16).unwrap();
+        image.premultiplied(true).stencil(true);
+        assert!(image.has_alpha());
+        assert_eq!(image.stencil_color(), None);
+        assert_eq!(image.samples, [0x12, 0x34, 0x00, 0x00]);
+        assert_eq!(image.alpha.as_deref(), Some([0xFF, 0xFF, 0x80, 0x00].as_slice()));
+
+        let mut chunk = Chunk::new();
+        image.write(&mut chunk, Ref::new(1), &mut Ref::new(2));
+        let pdf = String::from_utf8_lossy(chunk.as_bytes());
+        assert!(pdf.contains("/ColorSpace /DeviceGray\n  /BitsPerComponent 16"));
+        assert!(pdf.contains("/SMask 2 0 R"));
+        assert!(pdf.contains("/Matte [0]"));
+        assert_eq!(pdf.matches("/Width 2\n  /Height 1").count(), 2);
+    }
+
+    #[test]
+    fn test_raw_image_opaque() {
+        // 2-bit RGBA with a fully opaque alpha channel and padded rows.
+        let data = [0b00011011, 0b11100111, 0b01001011, 0b11000000, 0b11110000];
+        let image = RawImage::new(&data, 3, 2, PixelFormat::Rgba, 2);
+        assert_eq!(image.err(), Some(ImageError::Malformed("pixel data")));
+
+        let data = [0b00011011, 0b11100111, 0b00011011, 0b11100111];
+        let image = RawImage::new(&data, 2, 2, PixelFormat::Rgba, 2).unwrap();
+        assert!(image.alpha.is_none());
+        assert_eq!(image.samples, [0b00011011, 0b10010000, 0b00011011, 0b10010000]);
+        assert_eq!(image.stencil_color(), None);
+
+        let mut chunk = Chunk::new();
+        image.write(&mut chunk, Ref::new(1), &mut Ref::new(2));
+        let pdf = String::from_utf8_lossy(chunk.as_bytes());
+        assert!(pdf.contains("/ColorSpace /DeviceRGB\n  /BitsPerComponent 2"));
+        assert!(!pdf.contains("/SMask"));
+    }
+
+    #[test]
+    fn test_raw_image_stencil() {
+        // Red text on a transparent background.
+        let data = [255, 0, 0, 255, 0, 0, 0, 0, 255, 0, 0, 255, 255, 0, 0, 255];
+        let mut image = RawImage::new(&data, 2, 2, PixelFormat::Rgba, 8).unwrap();
+        assert_eq!(image.stencil_color(), Some([1.0, 0.0, 0.0].as_slice()));
+        assert!(image.has_alpha());
+
+        image.stencil(true);
+        assert!(image.is_stencil());
+        assert!(!image.has_alpha());
+
+        let mut chunk = Chunk::new();
+        let mut alloc = Ref::new(2);
+        image.write(&mut chunk, Ref::new(1), &mut alloc);
+        assert_eq!(alloc, Ref::new(2));
+        let pdf = chunk.as_bytes();
+        let pdf_str = String::from_utf8_lossy(pdf);
+        assert!(pdf_str.contains("/ImageMask true\n  /BitsPerComponent 1"));
+        assert!(!pdf_str.contains("/ColorSpace"));
+        assert!(pdf.windows(4).any(|w| w == b"\n\x40\x00\n"));
+    }
+
+    #[test]
+    fn test_raw_image_errors() {
+        let err = RawImage::new(&[0; 12], 2, 2, PixelFormat::Cmyk, 3).err();
+        assert_eq!(err, Some(ImageError::Unsupported("bit depth")));
+        let err = RawImage::new(&[], 0, 2, PixelFormat::Gray, 8).err();
+        assert_eq!(err, Some(ImageError::Malformed("pixel data")));
+    }
+}
@@ -0,0 +1,287 @@
+/// Decompress zlib-wrapped Deflate data.
+///
+/// This is only used when image data must be inspected or rearranged before
+/// embedding, so it favors simplicity over speed. Returns `None` if the data
+/// is malformed or decompresses to more than `limit` bytes, which guards
+/// against allocating huge buffers for tiny inputs. The Adler-32 checksum is
+/// not verified.
+pub(crate) fn inflate_zlib(data: &[u8], limit: usize) -> Option<Vec<u8>> {
+    let &[cmf, flg, ..] = data else { return None };
+    if cmf & 0x0F != 8 || (u16::from(cmf) << 8 | u16::from(flg)) % 31 != 0 {
+        return None;
+    }
+
+    // A preset dictionary is not allowed in the contexts we decompress.
+    if flg & 0x20 != 0 {
+        return None;
+    }
+
+    inflate(&data[2..], limit)
+}
+
+/// Decompress raw Deflate data to at most `limit` bytes.
+fn inflate(data: &[u8], limit: usize) -> Option<Vec<u8>> {
+    let mut bits = Bits { data, pos: 0, buf: 0, count: 0 };
+    let mut out = vec![];
+    loop {
+        let last = bits.read(1)? == 1;
+        match bits.read(2)? {
+            0 => stored(&mut bits, &mut out, limit)?,
+            1 => {
+                let (lengths, distances) = fixed_tables();
+                codes(&mut bits, &mut out, limit, &lengths, &distances)?;
+            }
+            2 => {
+                let (lengths, distances) = dynamic_tables(&mut bits)?;
+                codes(&mut bits, &mut out, limit, &lengths, &distances)?;
+            }
+            _ => return None,
+        }
+
+        if last {
+            return Some(out);
+        }
+    }
+}
+
+/// Reads bits least significant first.
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+    buf: u32,
+    count: u32,
+}
+
+impl Bits<'_> {
+    /// Read `n` bits, with `n` at most 24.
+    fn read(&mut self, n: u32) -> Option<u32> {
+        while self.count < n {
+            let byte = *self.data.get(self.pos)?;
+            self.pos += 1;
+            self.buf |= u32::from(byte) << self.count;
+            self.count += 8;
+        }
+
+        let value = self.buf & ((1 << n) - 1);
+        self.buf >>= n;
+        self.count -= n;
+        Some(value)
+    }
+
+    /// Discard the remaining bits of the current byte.
+    fn align(&mut self) {
+        self.buf = 0;
+        self.count = 0;
+    }
+}
+
+/// Copy a stored block.
+fn stored(bits: &mut Bits, out: &mut Vec<u8>, limit: usize) -> Option<()> {
+    bits.align();
+    let header = bits.data.get(bits.pos..bits.pos + 4)?;
+    let len = u16::from_le_bytes([header[0], header[1]]);
+    let nlen = u16::from_le_bytes([header[2], header[3]]);
+    if len != !nlen {
+        return None;
+    }
+
+    if out.len() + usize::from(len) > limit {
+        return None;
+    }
+
+    let start = bits.pos + 4;
+    out.extend_from_slice(bits.data.get(start..start + usize::from(len))?);
+    bits.pos = start + usize::from(len);
+    Some(())
+}
+
+/// A canonical Huffman code.
+struct Huffman {
+    /// The number of codes of each length.
+    counts: [u16; 16],
+    /// The symbols ordered by code.
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Build a code from the code length of each symbol.
+    ///
+    /// Incomplete codes are permitted, but over-subscribed ones are not.
+    fn new(lengths: &[u8]) -> Option<Self> {
+        let mut counts = [0; 16];
+        for &len in lengths {
+            counts[usize::from(len)] += 1;
+        }
+
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = 2 * left - i32::from(count);
+            if left < 0 {
+                return None;
+            }
+        }
+
+        let mut offsets = [0; 16];
+        for len in 1..15 {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+
+        let mut symbols = vec![0; usize::from(offsets[15] + counts[15])];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                let offset = &mut offsets[usize::from(len)];
+                symbols[usize::from(*offset)] = symbol as u16;
+                *offset += 1;
+            }
+        }
+
+        counts[0] = 0;
+        Some(Self { counts, symbols })
+    }
+
+    /// Decode one symbol.
+    fn decode(&self, bits: &mut Bits) -> Option<u16> {
+        let mut code = 0;
+        let mut first = 0;
+        let mut index = 0;
+        for &count in &self.counts[1..] {
+            code |= bits.read(1)? as i32;
+            let count = i32::from(count);
+            if code - first < count {
+                return self.symbols.get((index + code - first) as usize).copied();
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        None
+    }
+}
+
+/// The codes of fixed Huffman blocks.
+fn fixed_tables() -> (Huffman, Huffman) {
+    let mut lengths = [0; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    let lengths = Huffman::new(&lengths).unwrap();
+    let distances = Huffman::new(&[5; 30]).unwrap();
+    (lengths, distances)
+}
+
+/// Read the codes of a dynamic Huffman block.
+fn dynamic_tables(bits: &mut Bits) -> Option<(Huffman, Huffman)> {
+    const ORDER: [usize; 19] =
+        [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+    let num_lengths = bits.read(5)? as usize + 257;
+    let num_distances = bits.read(5)? as usize + 1;
+    let num_codes = bits.read(4)? as usize + 4;
+    if num_lengths > 286 || num_distances > 30 {
+        return None;
+    }
+
+    let mut code_lengths = [0; 19];
+    for &i in &ORDER[..num_codes] {
+        code_lengths[i] = bits.read(3)? as u8;
+    }
+
+    let code = Huffman::new(&code_lengths)?;
+    let mut lengths = vec![];
+    while lengths.len() < num_lengths + num_distances {
+        let (len, repeat) = match code.decode(bits)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => (*lengths.last()?, 3 + bits.read(2)?),
+            17 => (0, 3 + bits.read(3)?),
+            _ => (0, 11 + bits.read(7)?),
+        };
+        lengths.extend(std::iter::repeat_n(len, repeat as usize));
+    }
+
+    if lengths.len() > num_lengths + num_distances || lengths[256] == 0 {
+        return None;
+    }
+
+    let (lengths, distances) = lengths.split_at(num_lengths);
+    Some((Huffman::new(lengths)?, Huffman::new(distances)?))
+}
+
+/// Decode the literals and back references of a compressed block.
+fn codes(
+    bits: &mut Bits,
+    out: &mut Vec<u8>,
+    limit: usize,
+    lengths: &Huffman,
+    distances: &Huffman,
+) -> Option<()> {
+    const LENGTH_BASE: [u16; 29] = [
+        3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83,
+        99, 115, 131, 163, 195, 227, 258,
+    ];
+    const LENGTH_EXTRA: [u8; 29] = [
+        0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5,
+        5, 0,
+    ];
+    const DISTANCE_BASE: [u16; 30] = [
+        1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769,
+        1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+    ];
+    const DISTANCE_EXTRA: [u8; 30] = [
+        0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11,
+        12, 12, 13, 13,
+    ];
+
+    loop {
+        let symbol = usize::from(lengths.decode(bits)?);
+        if symbol < 256 {
+            if out.len() >= limit {
+                return None;
+            }
+            out.push(symbol as u8);
+            continue;
+        } else if symbol == 256 {
+            return Some(());
+        }
+
+        let i = symbol - 257;
+        let len = usize::from(*LENGTH_BASE.get(i)?)
+            + bits.read(LENGTH_EXTRA[i].into())? as usize;
+
+        let i = usize::from(distances.decode(bits)?);
+        let distance = usize::from(*DISTANCE_BASE.get(i)?)
+            + bits.read(DISTANCE_EXTRA[i].into())? as usize;
+
+        let start = out.len().checked_sub(distance)?;
+        if out.len() + len > limit {
+            return None;
+        }
+        for k in 0..len {
+            out.push(out[start + k]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inflate_zlib() {
+        let data: Vec<u8> = (0..20000u32).map(|i| (i * i / 7 % 251) as u8).collect();
+        for level in [0, 1, 6, 10] {
+            let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&data, level);
+            assert_eq!(
+                inflate_zlib(&compressed, 20000).as_deref(),
+                Some(data.as_slice())
+            );
+            assert_eq!(inflate_zlib(&compressed, 19999), None);
+        }
+
+        // Fixed Huffman codes with a back reference.
+        assert_eq!(inflate(&[0x4b, 0x4c, 0x04, 0x02, 0x00], 5).unwrap(), b"aaaaa");
+        assert_eq!(inflate(&[0x4b, 0x4c, 0x04, 0x02, 0x00], 4), None);
+        assert_eq!(inflate_zlib(b"\x78\x9c\x4b", 100), None);
+        assert_eq!(inflate_zlib(b"\x78\x9d\x4b\x4c\x04\x02\x00", 100), None);
+    }
+}
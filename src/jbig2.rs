@@ -0,0 +1,443 @@
+use std::ops::Range;
+
+use super::*;
+use crate::image::ImageError;
+use crate::reader::read_u32;
+
+/// The pages of a JBIG2 file for embedding as image XObjects.
+///
+/// PDF embeds JBIG2 data without the file header, with the segments of each
+/// page in their own [`Filter::Jbig2Decode`] stream. Segments that belong to
+/// no page, like symbol dictionaries shared across pages, go into one
+/// separate stream that the pages reference through
+/// [`/JBIG2Globals`](crate::writers::DecodeParms::jbig2_globals). Files in
+/// the sequential and the random-access organization are supported.
+///
+/// ```no_run
+/// use pdf_writer::{Jbig2File, Pdf, Ref};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("scan.jb2")?;
+/// let file = Jbig2File::new(&data)?;
+///
+/// let mut pdf = Pdf::new();
+/// let globals_id = Ref::new(1);
+/// let globals = file.has_globals().then_some(globals_id);
+/// if globals.is_some() {
+///     file.write_globals(&mut pdf, globals_id);
+/// }
+///
+/// for page in 0..file.num_pages() {
+///     file.write_page(&mut pdf, Ref::new(2 + page as i32), page, globals);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Jbig2File {
+    globals: Vec<u8>,
+    pages: Vec<Jbig2Page>,
+}
+
+/// The segments and the size of a page.
+struct Jbig2Page {
+    number: u32,
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    /// Whether the page information leaves the height to the stripes.
+    unknown_height: bool,
+}
+
+/// A parsed segment header.
+struct Segment<'a> {
+    header: &'a [u8],
+    kind: u8,
+    page: u32,
+    /// The length of the data, `None` if it is unknown.
+    len: Option<usize>,
+}
+
+/// The ID string at the start of JBIG2 files.
+const SIGNATURE: &[u8] = b"\x97JB2\r\n\x1a\n";
+
+/// Segment types with special treatment.
+const PAGE_INFORMATION: u8 = 48;
+const END_OF_PAGE: u8 = 49;
+const END_OF_STRIPE: u8 = 50;
+const END_OF_FILE: u8 = 51;
+
+impl Jbig2File {
+    /// Parse a JBIG2 file and split it into global and page segments.
+    pub fn new(data: &[u8]) -> Result<Self, ImageError> {
+        let rest = data.strip_prefix(SIGNATURE).ok_or(ImageError::UnknownFormat)?;
+        let malformed = ImageError::Malformed("JBIG2 file header");
+        let flags = *rest.first().ok_or(malformed)?;
+        let mut pos = if flags & 2 == 0 { 5 } else { 1 };
+        if rest.len() < pos {
+            return Err(malformed);
+        }
+
+        let truncated = ImageError::Malformed("JBIG2 segment data");
+        let mut segments = vec![];
+        if flags & 1 != 0 {
+            // Sequential: Each segment header is followed by its data.
+            while pos < rest.len() {
+                let segment = Segment::parse(&rest[pos..])?;
+                let start = pos + segment.header.len();
+                let len = match segment.len {
+                    Some(len) => len,
+                    None => unknown_length(segment.kind, &rest[start..])?,
+                };
+                let body = rest.get(start..start + len).ok_or(truncated)?;
+                pos = start + len;
+                let kind = segment.kind;
+                segments.push((segment, body));
+                if kind == END_OF_FILE {
+                    break;
+                }
+            }
+        } else {
+            // Random-access: All headers come first, followed by all data.
+            let mut headers = vec![];
+            while pos < rest.len() {
+                let segment = Segment::parse(&rest[pos..])?;
+                pos += segment.header.len();
+                let kind = segment.kind;
+                headers.push(segment);
+                if kind == END_OF_FILE {
+                    break;
+                }
+            }
+
+            for segment in headers {
+                let len = segment.len.ok_or(truncated)?;
+                let body = rest.get(pos..pos + len).ok_or(truncated)?;
+                pos += len;
+                segments.push((segment, body));
+            }
+        }
+
+        let mut file = Self { globals: vec![], pages: vec![] };
+        for (segment, body) in segments {
+            if matches!(segment.kind, END_OF_PAGE | END_OF_FILE) {
+                continue;
+            }
+
+            if segment.page == 0 {
+                file.globals.extend_from_slice(segment.header);
+                file.globals.extend_from_slice(body);
+                continue;
+            }
+
+            let index =
+                match file.pages.iter().position(|page| page.number == segment.page) {
+                    Some(index) => index,
+                    None => {
+                        let page = Jbig2Page {
+                            number: segment.page,
+                            data: vec![],
+                            width: 0,
+                            height: 0,
+                            unknown_height: false,
+                        };
+                        file.pages.push(page);
+                        file.pages.len() - 1
+                    }
+                };
+
+            // An embedded stream holds a single page, so its segments are
+            // associated with page one, as in the output of jbig2enc.
+            let page = &mut file.pages[index];
+            let start = page.data.len();
+            page.data.extend_from_slice(segment.header);
+            let field = segment.page_field();
+            page.data[start + field.start..start + field.end]
+                .copy_from_slice(&1u32.to_be_bytes()[4 - field.len()..]);
+            page.data.extend_from_slice(body);
+
+            let malformed = ImageError::Malformed("JBIG2 page information");
+            match segment.kind {
+                PAGE_INFORMATION => {
+                    page.width = read_u32(body, 0).ok_or(malformed)?;
+                    page.height = read_u32(body, 4).ok_or(malformed)?;
+                    page.unknown_height = page.height == u32::MAX;
+                }
+                // The height of striped pages may only be known at the end of
+                // the last stripe.
+                END_OF_STRIPE if page.unknown_height => {
+                    page.height = read_u32(body, 0).ok_or(malformed)?.saturating_add(1);
+                }
+                _ => {}
+            }
+        }
+
+        let invalid = |page: &Jbig2Page| {
+            page.width == 0 || page.height == 0 || page.height == u32::MAX
+        };
+
+        if file.pages.iter().any(invalid) {
+            return Err(ImageError::Malformed("JBIG2 page information"));
+        }
+
+        Ok(file)
+    }
+
+    /// The number of pages in the file.
+    pub fn num_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// The width and height of a page in pixels.
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn page_size(&self, index: usize) -> (u32, u32) {
+        let page = &self.pages[index];
+        (page.width, page.height)
+    }
+
+    /// Whether the file has global segments that the pages depend on.
+    pub fn has_globals(&self) -> bool {
+        !self.globals.is_empty()
+    }
+
+    /// Write the stream with the global segments.
+    pub fn write_globals(&self, chunk: &mut Chunk, id: Ref) {
+        chunk.stream(id, &self.globals);
+    }
+
+    /// Write the image XObject for a page.
+    ///
+    /// If the file [has global segments](Self::has_globals), `globals` must
+    /// reference the stream written by [`write_globals`](Self::write_globals).
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn write_page(
+        &self,
+        chunk: &mut Chunk,
+        id: Ref,
+        index: usize,
+        globals: Option<Ref>,
+    ) {
+        let page = &self.pages[index];
+        let mut image = chunk.image_xobject(id, &page.data);
+        image.filter(Filter::Jbig2Decode);
+        if let Some(globals) = globals {
+            image.decode_parms().jbig2_globals(globals);
+        }
+        image.width(page.width as i32);
+        image.height(page.height as i32);
+        image.color_space().device_gray();
+        image.bits_per_component(1);
+    }
+}
+
+impl<'a> Segment<'a> {
+    /// Parse a segment header at the start of the data.
+    fn parse(data: &'a [u8]) -> Result<Self, ImageError> {
+        let malformed = ImageError::Malformed("JBIG2 segment header");
+        let number = read_u32(data, 0).ok_or(malformed)?;
+        let flags = *data.get(4).ok_or(malformed)?;
+        let referred = *data.get(5).ok_or(malformed)?;
+
+        // The number of referred-to segments and the size of their retention
+        // flags.
+        let (count, mut pos) = match referred >> 5 {
+            count @ 0..=4 => (count.into(), 6),
+            7 => {
+                let count = read_u32(data, 5).ok_or(malformed)? & 0x1FFF_FFFF;
+                (count as usize, 9 + (count as usize + 8) / 8)
+            }
+            _ => return Err(malformed),
+        };
+
+        let number_size = match number {
+            0..=256 => 1,
+            257..=65536 => 2,
+            _ => 4,
+        };
+        pos += count.checked_mul(number_size).ok_or(malformed)?;
+
+        let page = if flags & 0x40 != 0 {
+            pos += 4;
+            read_u32(data, pos - 4).ok_or(malformed)?
+        } else {
+            pos += 1;
+            (*data.get(pos - 1).ok_or(malformed)?).into()
+        };
+
+        let len = read_u32(data, pos).ok_or(malformed)?;
+        let header = &data[..pos + 4];
+        let len = (len != u32::MAX).then_some(len as usize);
+        Ok(Self { header, kind: flags & 0x3F, page, len })
+    }
+
+    /// The position of the page association field in the header.
+    fn page_field(&self) -> Range<usize> {
+        let end = self.header.len() - 4;
+        let size = if self.header[4] & 0x40 != 0 { 4 } else { 1 };
+        end - size..end
+    }
+}
+
+/// Determine the length of an immediate generic region segment whose length
+/// is not given in its header by searching for the end of its data.
+fn unknown_length(kind: u8, data: &[u8]) -> Result<usize, ImageError> {
+    let malformed = ImageError::Malformed("JBIG2 segment data");
+    if !matches!(kind, 38 | 39) {
+        return Err(malformed);
+    }
+
+    // The region segment information field is followed by the generic region
+    // flags, whose lowest bit selects MMR coding.
+    let flags = *data.get(17).ok_or(malformed)?;
+    let marker: &[u8] = if flags & 1 != 0 { b"\x00\x00" } else { b"\xff\xac" };
+    let end = data
+        .get(18..)
+        .ok_or(malformed)?
+        .windows(2)
+        .position(|window| window == marker)
+        .ok_or(malformed)?;
+
+    // The marker is followed by the number of rows.
+    let len = 18 + end + 2 + 4;
+    if len > data.len() {
+        return Err(malformed);
+    }
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a segment header.
+    fn header(number: u32, kind: u8, page: u32, referred: &[u32], len: u32) -> Vec<u8> {
+        let mut data = number.to_be_bytes().to_vec();
+        data.push(kind | if page > 255 { 0x40 } else { 0 });
+        data.push((referred.len() as u8) << 5);
+        for &other in referred {
+            data.push(other as u8);
+        }
+        if page > 255 {
+            data.extend(page.to_be_bytes());
+        } else {
+            data.push(page as u8);
+        }
+        data.extend(len.to_be_bytes());
+        data
+    }
+
+    fn page_info(width: u32, height: u32) -> Vec<u8> {
+        let mut data = width.to_be_bytes().to_vec();
+        data.extend(height.to_be_bytes());
+        data.extend([0; 11]);
+        data
+    }
+
+    /// The segments of a two-page file with a shared symbol dictionary.
+    fn segments() -> Vec<(Vec<u8>, Vec<u8>)> {
+        let segment = |number, kind, page, referred: &[u32], data: Vec<u8>| {
+            (header(number, kind, page, referred, data.len() as u32), data)
+        };
+
+        vec![
+            segment(0, 0, 0, &[], b"symbols".to_vec()),
+            segment(1, PAGE_INFORMATION, 1, &[], page_info(30, 20)),
+            segment(2, 6, 1, &[0], b"text".to_vec()),
+            segment(3, END_OF_PAGE, 1, &[], vec![]),
+            segment(4, PAGE_INFORMATION, 300, &[], page_info(40, u32::MAX)),
+            segment(5, 38, 300, &[], b"generic".to_vec()),
+            segment(6, END_OF_STRIPE, 300, &[], 9u32.to_be_bytes().to_vec()),
+            segment(7, END_OF_PAGE, 300, &[], vec![]),
+            segment(8, END_OF_FILE, 0, &[], vec![]),
+        ]
+    }
+
+    fn check(file: &Jbig2File) {
+        let segments = segments();
+        let concat = |indices: &[usize]| -> Vec<u8> {
+            indices
+                .iter()
+                .flat_map(|&i| [segments[i].0.clone(), segments[i].1.clone()])
+                .flatten()
+                .collect()
+        };
+
+        assert_eq!(file.num_pages(), 2);
+        assert_eq!(file.page_size(0), (30, 20));
+        assert_eq!(file.page_size(1), (40, 10));
+        assert_eq!(file.globals, concat(&[0]));
+        assert_eq!(file.pages[0].data, concat(&[1, 2]));
+
+        // The second page is renumbered to one in its four-byte fields.
+        let mut second = concat(&[4, 5, 6]);
+        let mut pos = 0;
+        for &i in &[4, 5, 6] {
+            let (header, body) = &segments[i];
+            let end = pos + header.len() - 4;
+            assert_eq!(second[end - 4..end], 300u32.to_be_bytes());
+            second[end - 4..end].copy_from_slice(&1u32.to_be_bytes());
+            pos += header.len() + body.len();
+        }
+        assert_eq!(file.pages[1].data, second);
+        assert_eq!(Segment::parse(&file.pages[1].data).unwrap().page, 1);
+    }
+
+    #[test]
+    fn test_jbig2_sequential() {
+        let mut data = SIGNATURE.to_vec();
+        data.push(0b01);
+        data.extend(2u32.to_be_bytes());
+        for (header, body) in segments() {
+            data.extend(header);
+            data.extend(body);
+        }
+
+        let file = Jbig2File::new(&data).unwrap();
+        check(&file);
+
+        let mut chunk = Chunk::new();
+        file.write_globals(&mut chunk, Ref::new(1));
+        file.write_page(&mut chunk, Ref::new(2), 0, Some(Ref::new(1)));
+        let pdf = String::from_utf8_lossy(chunk.as_bytes());
+        assert!(pdf.contains("/Filter /JBIG2Decode"));
+        assert!(pdf.contains("/DecodeParms <<\n    /JBIG2Globals 1 0 R\n  >>"));
+        assert!(pdf.contains("/Width 30\n  /Height 20"));
+    }
+
+    #[test]
+    fn test_jbig2_random_access() {
+        let mut data = SIGNATURE.to_vec();
+        data.push(0b10);
+        let segments = segments();
+        for (header, _) in &segments {
+            data.extend(header);
+        }
+        for (_, body) in &segments {
+            data.extend(body);
+        }
+
+        check(&Jbig2File::new(&data).unwrap());
+        assert_eq!(
+            Jbig2File::new(&data[..data.len() - 1]).err(),
+            Some(ImageError::Malformed("JBIG2 segment data"))
+        );
+    }
+
+    #[test]
+    fn test_jbig2_unknown_length() {
+        let mut body = vec![0; 17];
+        body.extend([0, 1, 2, 0xFF, 0xAC, 0, 0, 0, 5]);
+        let mut data = SIGNATURE.to_vec();
+        data.push(0b11);
+        data.extend(header(0, PAGE_INFORMATION, 1, &[], 19));
+        data.extend(page_info(8, 5));
+        data.extend(header(1, 38, 1, &[], u32::MAX));
+        data.extend(&body);
+        data.extend(header(2, END_OF_PAGE, 1, &[], 0));
+
+        let file = Jbig2File::new(&data).unwrap();
+        assert!(!file.has_globals());
+        assert!(file.pages[0].data.ends_with(&body));
+    }
+}
@@ -0,0 +1,320 @@
+use super::*;
+use crate::image::ImageError;
+
+/// A JPEG image for embedding as an image XObject.
+///
+/// PDF decodes JPEG data itself with [`Filter::DctDecode`], so the data is
+/// embedded as is. Only the markers in front of the scan data are read to
+/// determine the dimensions and the color space of the image: One, three and
+/// four components are written as `DeviceGray`, `DeviceRGB` and `DeviceCMYK`,
+/// or as `ICCBased` if the image has an embedded ICC profile with a matching
+/// number of components.
+///
+/// CMYK JPEGs written by Adobe applications, recognizable by their `APP14`
+/// marker, store inverted values. For them, a `/Decode` array that undoes the
+/// inversion is written.
+///
+/// ```no_run
+/// use pdf_writer::{JpegImage, Pdf, Ref};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("photo.jpg")?;
+/// let image = JpegImage::new(&data)?;
+///
+/// let mut pdf = Pdf::new();
+/// let mut alloc = Ref::new(10);
+/// image.write(&mut pdf, Ref::new(1), &mut alloc);
+/// # Ok(())
+/// # }
+/// ```
+pub struct JpegImage<'a> {
+    data: &'a [u8],
+    width: u16,
+    height: u16,
+    components: u8,
+    progressive: bool,
+    /// Whether the image has an Adobe `APP14` marker.
+    adobe: bool,
+    /// The ICC profile assembled from the `APP2` markers.
+    icc: Option<Vec<u8>>,
+}
+
+impl<'a> JpegImage<'a> {
+    /// Parse the markers of a JPEG image.
+    ///
+    /// Fails for variants of JPEG that PDF readers can't decode: Arithmetic
+    /// coding, lossless and hierarchical JPEG and samples with more than eight
+    /// bits.
+    pub fn new(data: &'a [u8]) -> Result<Self, ImageError> {
+        if !data.starts_with(&[0xFF, 0xD8]) {
+            return Err(ImageError::UnknownFormat);
+        }
+
+        let mut image = Self {
+            data,
+            width: 0,
+            height: 0,
+            components: 0,
+            progressive: false,
+            adobe: false,
+            icc: None,
+        };
+
+        let mut icc_chunks = vec![];
+        let mut pos = 2;
+        loop {
+            let malformed = ImageError::Malformed("JPEG marker");
+            if data.get(pos) != Some(&0xFF) {
+                return Err(malformed);
+            }
+
+            // Markers may be preceded by any number of fill bytes.
+            while data.get(pos) == Some(&0xFF) {
+                pos += 1;
+            }
+
+            let marker = *data.get(pos).ok_or(malformed)?;
+            pos += 1;
+            if matches!(marker, 0x01 | 0xD0..=0xD7) {
+                continue;
+            }
+
+            let len = data
+                .get(pos..pos + 2)
+                .map(|bytes| usize::from(u16::from_be_bytes([bytes[0], bytes[1]])))
+                .filter(|&len| len >= 2)
+                .ok_or(malformed)?;
+            let segment = data.get(pos + 2..pos + len).ok_or(malformed)?;
+            pos += len;
+
+            match marker {
+                0xC0..=0xC2 => {
+                    image.parse_frame(segment)?;
+                    image.progressive = marker == 0xC2;
+                }
+                0xC3 | 0xC7 | 0xCB | 0xCF => {
+                    return Err(ImageError::Unsupported("lossless JPEG"));
+                }
+                0xC5 | 0xC6 => return Err(ImageError::Unsupported("hierarchical JPEG")),
+                0xC9 | 0xCA | 0xCC | 0xCD | 0xCE => {
+                    return Err(ImageError::Unsupported("arithmetic coding"));
+                }
+                0xE2 => {
+                    if let Some([seq, count, chunk @ ..]) =
+                        segment.strip_prefix(b"ICC_PROFILE\0")
+                    {
+                        icc_chunks.push((*seq, *count, chunk));
+                    }
+                }
+                0xEE => image.adobe |= segment.starts_with(b"Adobe"),
+                0xDA | 0xD9 => break,
+                _ => {}
+            }
+        }
+
+        if image.components == 0 {
+            return Err(ImageError::Malformed("JPEG frame header"));
+        }
+
+        // Profile chunks are numbered from one and may appear in any order.
+        icc_chunks.sort_by_key(|&(seq, _, _)| seq);
+        let complete = icc_chunks.iter().enumerate().all(|(i, &(seq, count, _))| {
+            usize::from(seq) == i + 1 && usize::from(count) == icc_chunks.len()
+        });
+
+        if complete && !icc_chunks.is_empty() {
+            let icc: Vec<u8> =
+                icc_chunks.iter().flat_map(|&(_, _, chunk)| chunk).copied().collect();
+
+            // A profile for another number of components doesn't describe the
+            // image and would contradict the `/N` entry.
+            let components = i32::from(image.components);
+            if IccInfo::new(&icc).is_ok_and(|info| info.n() == components) {
+                image.icc = Some(icc);
+            }
+        }
+
+        Ok(image)
+    }
+
+    /// Read the dimensions and components from a start of frame segment.
+    fn parse_frame(&mut self, segment: &[u8]) -> Result<(), ImageError> {
+        let malformed = ImageError::Malformed("JPEG frame header");
+        let &[precision, h1, h0, w1, w0, components, ..] = segment else {
+            return Err(malformed);
+        };
+
+        if precision != 8 {
+            return Err(ImageError::Unsupported("JPEG sample precision"));
+        }
+
+        self.height = u16::from_be_bytes([h1, h0]);
+        self.width = u16::from_be_bytes([w1, w0]);
+        if self.height == 0 {
+            return Err(ImageError::Unsupported("JPEG height defined by DNL marker"));
+        } else if self.width == 0 || segment.len() < 6 + 3 * usize::from(components) {
+            return Err(malformed);
+        } else if !matches!(components, 1 | 3 | 4) {
+            return Err(ImageError::Unsupported("number of JPEG components"));
+        }
+
+        self.components = components;
+        Ok(())
+    }
+
+    /// The width of the image in pixels.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// The height of the image in pixels.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// The number of color components: One for grayscale, three for color and
+    /// four for CMYK images.
+    pub fn components(&self) -> u8 {
+        self.components
+    }
+
+    /// Whether the image is encoded progressively. PDF 1.3+.
+    pub fn is_progressive(&self) -> bool {
+        self.progressive
+    }
+
+    /// Whether the CMYK values of the image are stored inverted.
+    pub fn is_inverted(&self) -> bool {
+        self.adobe && self.components == 4
+    }
+
+    /// The embedded ICC profile.
+    ///
+    /// Profiles that are incomplete, malformed or for another number of
+    /// components are dropped.
+    pub fn icc_profile(&self) -> Option<&[u8]> {
+        self.icc.as_deref()
+    }
+
+    /// Write the image XObject.
+    ///
+    /// If the image has an ICC profile, its ID is taken from `alloc`.
+    pub fn write(&self, chunk: &mut Chunk, id: Ref, alloc: &mut Ref) {
+        let icc = self.icc.as_deref().and_then(|icc| IccInfo::new(icc).ok());
+        let icc_id = icc.as_ref().map(|_| alloc.bump());
+
+        let mut image = chunk.image_xobject(id, self.data);
+        image.filter(Filter::DctDecode);
+        image.width(self.width.into());
+        image.height(self.height.into());
+
+        let space = image.color_space();
+        match (icc_id, self.components) {
+            (Some(icc_id), _) => space.icc_based(icc_id),
+            (None, 1) => space.device_gray(),
+            (None, 3) => space.device_rgb(),
+            (None, _) => space.device_cmyk(),
+        }
+
+        image.bits_per_component(8);
+        if self.is_inverted() {
+            image.decode([1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0]);
+        }
+
+        image.finish();
+
+        if let (Some(icc_id), Some(icc)) = (icc_id, icc) {
+            icc.write(chunk, icc_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icc::fixture::profile;
+
+    /// Build the markers of a JPEG file.
+    fn jpeg(sof: u8, precision: u8, components: u8, extra: &[(u8, &[u8])]) -> Vec<u8> {
+        let mut frame = vec![precision, 0, 20, 0, 30, components];
+        for i in 0..components {
+            frame.extend([i + 1, 0x11, 0]);
+        }
+
+        let mut data = vec![0xFF, 0xD8];
+        for (marker, segment) in extra.iter().chain([&(sof, frame.as_slice())]) {
+            data.extend([0xFF, *marker]);
+            data.extend((segment.len() as u16 + 2).to_be_bytes());
+            data.extend(*segment);
+        }
+
+        data.extend([0xFF, 0xDA, 0, 2, 1, 2, 3, 0xFF, 0xD9]);
+        data
+    }
+
+    #[test]
+    fn test_jpeg_cmyk() {
+        let icc = profile(b"prtr", b"CMYK", 2, &[]);
+        let (first, second) = icc.split_at(100);
+        let data = jpeg(
+            0xC2,
+            8,
+            4,
+            &[
+                (0xE2, &[b"ICC_PROFILE\0\x02\x02", second].concat()),
+                (0xEE, b"Adobe\0\x64\0\0\0\0\x02"),
+                (0xE2, &[b"ICC_PROFILE\0\x01\x02", first].concat()),
+            ],
+        );
+
+        let image = JpegImage::new(&data).unwrap();
+        assert_eq!((image.width(), image.height(), image.components()), (30, 20, 4));
+        assert!(image.is_progressive());
+        assert!(image.is_inverted());
+        assert_eq!(image.icc_profile(), Some(icc.as_slice()));
+
+        let mut chunk = Chunk::new();
+        image.write(&mut chunk, Ref::new(1), &mut Ref::new(2));
+        let pdf = String::from_utf8_lossy(chunk.as_bytes());
+        assert!(pdf.contains("/Filter /DCTDecode"));
+        assert!(pdf.contains("/ColorSpace [/ICCBased 2 0 R]"));
+        assert!(pdf.contains("/Decode [1 0 1 0 1 0 1 0]"));
+        assert!(pdf.contains("/N 4\n  /Alternate /DeviceCMYK"));
+    }
+
+    #[test]
+    fn test_jpeg_gray() {
+        // A missing profile chunk drops the profile.
+        let data = jpeg(0xC0, 8, 1, &[(0xE2, b"ICC_PROFILE\0\x01\x02abc")]);
+        let image = JpegImage::new(&data).unwrap();
+        assert_eq!(image.icc_profile(), None);
+
+        // So does a profile for RGB data.
+        let icc = [b"ICC_PROFILE\0\x01\x01", &profile(b"mntr", b"RGB ", 2, &[])[..]];
+        let data = jpeg(0xC0, 8, 1, &[(0xE2, &icc.concat())]);
+        let image = JpegImage::new(&data).unwrap();
+        assert_eq!(image.icc_profile(), None);
+
+        let mut chunk = Chunk::new();
+        image.write(&mut chunk, Ref::new(1), &mut Ref::new(2));
+        let pdf = String::from_utf8_lossy(chunk.as_bytes());
+        assert!(pdf.contains("/ColorSpace /DeviceGray"));
+        assert!(!pdf.contains("/Decode"));
+    }
+
+    #[test]
+    fn test_jpeg_unsupported() {
+        let check = |data: &[u8], feature| {
+            assert_eq!(
+                JpegImage::new(data).err(),
+                Some(ImageError::Unsupported(feature))
+            );
+        };
+
+        check(&jpeg(0xC9, 8, 3, &[]), "arithmetic coding");
+        check(&jpeg(0xC3, 8, 3, &[]), "lossless JPEG");
+        check(&jpeg(0xC1, 12, 3, &[]), "JPEG sample precision");
+        check(&jpeg(0xC0, 8, 2, &[]), "number of JPEG components");
+        assert_eq!(JpegImage::new(b"\x89PNG").err(), Some(ImageError::UnknownFormat));
+    }
+}
@@ -0,0 +1,393 @@
+use super::*;
+use crate::image::ImageError;
+use crate::reader::{read_u16, read_u32};
+use crate::types::SMaskInData;
+
+/// A JPEG 2000 image for embedding as an image XObject. PDF 1.5+.
+///
+/// Accepts JP2 and JPX files as well as raw codestreams. PDF decodes them
+/// itself with [`Filter::JpxDecode`], so the data is embedded as is. The
+/// header boxes and the codestream's `SIZ` marker are read to determine the
+/// dimensions, the channels and the color specification of the image.
+///
+/// Files with a color specification carry their own color space, so no
+/// `/ColorSpace` is written for them. For raw codestreams, the color space is
+/// derived from the number of components, so they must have one, three or
+/// four color channels. Opacity channels are used through
+/// [`/SMaskInData`](crate::writers::ImageXObject::s_mask_in_data).
+///
+/// ```no_run
+/// use pdf_writer::{JpxImage, Pdf, Ref};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("scan.jp2")?;
+/// let image = JpxImage::new(&data)?;
+/// image.validate_pdfa()?;
+///
+/// let mut pdf = Pdf::new();
+/// image.write(&mut pdf, Ref::new(1));
+/// # Ok(())
+/// # }
+/// ```
+pub struct JpxImage<'a> {
+    data: &'a [u8],
+    width: u32,
+    height: u32,
+    /// The bit depth of each codestream component.
+    depths: Vec<u8>,
+    /// The number of palette columns, if the image has a palette.
+    palette_columns: Option<u8>,
+    /// The channels with opacity and whether they are premultiplied.
+    opacity: Vec<(u16, bool)>,
+    /// The method, approximation and enumerated color space of each color
+    /// specification box.
+    colors: Vec<(u8, u8, Option<u32>)>,
+}
+
+/// The signature box at the start of JP2 and JPX files.
+const JP2_SIGNATURE: &[u8] = b"\0\0\0\x0cjP  \r\n\x87\n";
+
+/// The `SOC` and `SIZ` markers at the start of a codestream.
+const CODESTREAM_START: &[u8] = b"\xff\x4f\xff\x51";
+
+impl<'a> JpxImage<'a> {
+    /// Parse the header of a JPEG 2000 image.
+    pub fn new(data: &'a [u8]) -> Result<Self, ImageError> {
+        let mut image = Self {
+            data,
+            width: 0,
+            height: 0,
+            depths: vec![],
+            palette_columns: None,
+            opacity: vec![],
+            colors: vec![],
+        };
+
+        if data.starts_with(JP2_SIGNATURE) {
+            let mut codestream = None;
+            for (kind, body) in boxes(&data[JP2_SIGNATURE.len()..])? {
+                match &kind {
+                    b"jp2h" => image.parse_header(body)?,
+                    b"jp2c" if codestream.is_none() => codestream = Some(body),
+                    _ => {}
+                }
+            }
+
+            let codestream =
+                codestream.ok_or(ImageError::Malformed("JPEG 2000 codestream"))?;
+            image.parse_codestream(codestream)?;
+        } else if data.starts_with(CODESTREAM_START) {
+            image.parse_codestream(data)?;
+        } else {
+            return Err(ImageError::UnknownFormat);
+        }
+
+        if !image.has_color_space() && !matches!(image.color_channels(), 1 | 3 | 4) {
+            return Err(ImageError::Unsupported(
+                "number of JPEG 2000 color channels without color specification",
+            ));
+        }
+
+        Ok(image)
+    }
+
+    /// Read the boxes of the JP2 header box.
+    fn parse_header(&mut self, data: &[u8]) -> Result<(), ImageError> {
+        let malformed = ImageError::Malformed("JP2 header box");
+        for (kind, body) in boxes(data)? {
+            match &kind {
+                b"colr" => {
+                    let &[method, _, approx, ..] = body else { return Err(malformed) };
+                    let space = (method == 1).then(|| read_u32(body, 3)).flatten();
+                    self.colors.push((method, approx, space));
+                }
+                b"pclr" => self.palette_columns = Some(*body.get(2).ok_or(malformed)?),
+                b"cdef" => {
+                    let count = read_u16(body, 0).ok_or(malformed)?;
+                    for i in 0..usize::from(count) {
+                        let channel = read_u16(body, 2 + 6 * i).ok_or(malformed)?;
+                        match read_u16(body, 4 + 6 * i).ok_or(malformed)? {
+                            1 => self.opacity.push((channel, false)),
+                            2 => self.opacity.push((channel, true)),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the image size and components from the `SIZ` marker segment.
+    fn parse_codestream(&mut self, data: &[u8]) -> Result<(), ImageError> {
+        let malformed = ImageError::Malformed("JPEG 2000 codestream");
+        if !data.starts_with(CODESTREAM_START) {
+            return Err(malformed);
+        }
+
+        let siz = &data[4..];
+        let read = |offset| read_u32(siz, offset).ok_or(malformed);
+        let (width, height) = (read(4)?, read(8)?);
+        let (x_offset, y_offset) = (read(12)?, read(16)?);
+        self.width = width.checked_sub(x_offset).ok_or(malformed)?;
+        self.height = height.checked_sub(y_offset).ok_or(malformed)?;
+
+        let count = read_u16(siz, 36).ok_or(malformed)?;
+        for i in 0..usize::from(count) {
+            let depth = *siz.get(38 + 3 * i).ok_or(malformed)?;
+            self.depths.push((depth & 0x7F) + 1);
+        }
+
+        if self.width == 0 || self.height == 0 || self.depths.is_empty() {
+            return Err(malformed);
+        }
+
+        Ok(())
+    }
+
+    /// The width of the image in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the image in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The number of color channels, excluding opacity channels.
+    pub fn color_channels(&self) -> usize {
+        let channels = match self.palette_columns {
+            Some(columns) => usize::from(columns),
+            None => self.depths.len(),
+        };
+        channels.saturating_sub(self.opacity.len())
+    }
+
+    /// The bit depth of the first component.
+    pub fn bit_depth(&self) -> u8 {
+        self.depths[0]
+    }
+
+    /// Whether the image has its own color specification.
+    ///
+    /// This is false for raw codestreams and files whose color specifications
+    /// all use vendor-specific methods.
+    pub fn has_color_space(&self) -> bool {
+        self.colors.iter().any(|&(method, _, _)| matches!(method, 1..=3))
+    }
+
+    /// How to use the opacity channel of the image, if it has one.
+    pub fn s_mask_in_data(&self) -> Option<SMaskInData> {
+        let &(_, premultiplied) = self.opacity.first()?;
+        Some(if premultiplied { SMaskInData::Preblended } else { SMaskInData::Use })
+    }
+
+    /// Check the restrictions that PDF/A-2 and later impose on JPEG 2000
+    /// images.
+    ///
+    /// The image must have one, three or four color channels of the same bit
+    /// depth between 1 and 38. Its color specifications must use the
+    /// enumerated or ICC methods, but not the CIEJab color space, and if there
+    /// are multiple, exactly one must be marked as the best approximation.
+    pub fn validate_pdfa(&self) -> Result<(), ImageError> {
+        if !matches!(self.color_channels(), 1 | 3 | 4) {
+            return Err(ImageError::Forbidden("number of JPEG 2000 color channels"));
+        }
+
+        let depths = self
+            .depths
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| {
+                self.opacity.iter().all(|&(channel, _)| usize::from(channel) != i)
+            })
+            .map(|(_, &depth)| depth);
+        let mut depths = depths.take(self.color_channels());
+        let first = depths.next().unwrap_or(0);
+        if !(1..=38).contains(&first) || depths.any(|depth| depth != first) {
+            return Err(ImageError::Forbidden("JPEG 2000 bit depth"));
+        }
+
+        if self.colors.iter().any(|&(method, _, _)| !matches!(method, 1..=3)) {
+            return Err(ImageError::Forbidden("JPEG 2000 color specification method"));
+        }
+
+        if self.colors.iter().any(|&(_, _, space)| space == Some(19)) {
+            return Err(ImageError::Forbidden("CIEJab color space"));
+        }
+
+        let best = self.colors.iter().filter(|&&(_, approx, _)| approx == 1).count();
+        if self.colors.len() > 1 && best != 1 {
+            return Err(ImageError::Forbidden("ambiguous JPEG 2000 color specification"));
+        }
+
+        Ok(())
+    }
+
+    /// Write the image XObject.
+    pub fn write(&self, chunk: &mut Chunk, id: Ref) {
+        let mut image = chunk.image_xobject(id, self.data);
+        image.filter(Filter::JpxDecode);
+        image.width(self.width as i32);
+        image.height(self.height as i32);
+
+        // Without a color specification, `new` ensured that there are one,
+        // three or four color channels.
+        if !self.has_color_space() {
+            match self.color_channels() {
+                1 => image.color_space().device_gray(),
+                3 => image.color_space().device_rgb(),
+                _ => image.color_space().device_cmyk(),
+            }
+        }
+
+        if let Some(mode) = self.s_mask_in_data() {
+            image.s_mask_in_data(mode);
+        }
+    }
+}
+
+/// The type and contents of a JP2 box.
+type Jp2Box<'a> = ([u8; 4], &'a [u8]);
+
+/// Split data into JP2 boxes.
+fn boxes(mut data: &[u8]) -> Result<Vec<Jp2Box<'_>>, ImageError> {
+    let malformed = ImageError::Malformed("JP2 box");
+    let mut boxes = vec![];
+    while !data.is_empty() {
+        let len = read_u32(data, 0).ok_or(malformed)?;
+        let kind: [u8; 4] = data.get(4..8).ok_or(malformed)?.try_into().unwrap();
+        let (start, len) = match len {
+            0 => (8, data.len()),
+            1 => {
+                let bytes = data.get(8..16).ok_or(malformed)?;
+                let len = u64::from_be_bytes(bytes.try_into().unwrap());
+                (16, usize::try_from(len).map_err(|_| malformed)?)
+            }
+            _ => (8, len as usize),
+        };
+
+        let body = data.get(start..len).ok_or(malformed)?;
+        boxes.push((kind, body));
+        data = &data[len..];
+    }
+    Ok(boxes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build the start of a codestream with the given component depths.
+    fn codestream(width: u32, height: u32, depths: &[u8]) -> Vec<u8> {
+        let mut data = CODESTREAM_START.to_vec();
+        data.extend((38 + 3 * depths.len() as u16).to_be_bytes());
+        data.extend([0, 0]);
+        for value in [width + 5, height + 7, 5, 7, width, height, 0, 0] {
+            data.extend(value.to_be_bytes());
+        }
+        data.extend((depths.len() as u16).to_be_bytes());
+        for &depth in depths {
+            data.extend([depth - 1, 1, 1]);
+        }
+        data.extend(b"\xff\x90\0\x0a");
+        data
+    }
+
+    fn jp2_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut data = (body.len() as u32 + 8).to_be_bytes().to_vec();
+        data.extend(kind);
+        data.extend(body);
+        data
+    }
+
+    fn jp2(header: &[Vec<u8>], codestream: &[u8]) -> Vec<u8> {
+        let mut data = JP2_SIGNATURE.to_vec();
+        data.extend(jp2_box(b"ftyp", b"jp2 \0\0\0\0jp2 "));
+        data.extend(jp2_box(b"jp2h", &header.concat()));
+        data.extend(b"\0\0\0\0jp2c");
+        data.extend(codestream);
+        data
+    }
+
+    fn write(image: &JpxImage) -> String {
+        let mut chunk = Chunk::new();
+        image.write(&mut chunk, Ref::new(1));
+        String::from_utf8_lossy(chunk.as_bytes()).into_owned()
+    }
+
+    #[test]
+    fn test_jpx_raw_codestream() {
+        let data = codestream(30, 20, &[8, 8, 8]);
+        let image = JpxImage::new(&data).unwrap();
+        assert_eq!((image.width(), image.height()), (30, 20));
+        assert_eq!(image.color_channels(), 3);
+        assert!(!image.has_color_space());
+        assert_eq!(image.validate_pdfa(), Ok(()));
+
+        let pdf = write(&image);
+        assert!(pdf.contains("/Filter /JPXDecode"));
+        assert!(pdf.contains("/ColorSpace /DeviceRGB"));
+        assert!(!pdf.contains("/BitsPerComponent"));
+
+        let data = codestream(30, 20, &[8, 8]);
+        assert!(matches!(JpxImage::new(&data), Err(ImageError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_jpx_alpha() {
+        let data = jp2(
+            &[
+                jp2_box(b"ihdr", &[0, 0, 0, 20, 0, 0, 0, 30, 0, 2, 7, 7, 0, 0]),
+                jp2_box(b"colr", &[1, 0, 0, 0, 0, 0, 17]),
+                jp2_box(b"cdef", &[0, 2, 0, 0, 0, 0, 0, 1, 0, 1, 0, 2, 0, 0]),
+            ],
+            &codestream(30, 20, &[8, 8]),
+        );
+
+        let image = JpxImage::new(&data).unwrap();
+        assert_eq!(image.color_channels(), 1);
+        assert!(image.has_color_space());
+        assert_eq!(image.s_mask_in_data(), Some(SMaskInData::Preblended));
+        assert_eq!(image.validate_pdfa(), Ok(()));
+
+        let pdf = write(&image);
+        assert!(!pdf.contains("/ColorSpace"));
+        assert!(pdf.contains("/SMaskInData 2"));
+    }
+
+    #[test]
+    fn test_jpx_pdfa() {
+        let colr = |approx, space: u8| jp2_box(b"colr", &[1, 0, approx, 0, 0, 0, space]);
+        let check = |header: &[Vec<u8>], depths: &[u8]| {
+            JpxImage::new(&jp2(header, &codestream(1, 1, depths)))
+                .unwrap()
+                .validate_pdfa()
+        };
+
+        assert_eq!(
+            check(&[colr(0, 16)], &[8, 8]),
+            Err(ImageError::Forbidden("number of JPEG 2000 color channels"))
+        );
+        assert_eq!(
+            check(&[colr(0, 16)], &[8, 8, 4]),
+            Err(ImageError::Forbidden("JPEG 2000 bit depth"))
+        );
+        assert_eq!(
+            check(&[colr(0, 19)], &[8, 8, 8]),
+            Err(ImageError::Forbidden("CIEJab color space"))
+        );
+        assert_eq!(
+            check(&[colr(0, 16), colr(0, 12)], &[8, 8, 8]),
+            Err(ImageError::Forbidden("ambiguous JPEG 2000 color specification"))
+        );
+        assert_eq!(check(&[colr(1, 16), colr(0, 12)], &[8, 8, 8]), Ok(()));
+        assert_eq!(
+            check(&[jp2_box(b"colr", &[4, 0, 0])], &[8]),
+            Err(ImageError::Forbidden("JPEG 2000 color specification method"))
+        );
+        assert_eq!(JpxImage::new(b"\xff\xd8").err(), Some(ImageError::UnknownFormat));
+    }
+}
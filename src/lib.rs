@@ -67,6 +67,14 @@ std::fs::write("target/empty.pdf", pdf.finish())?;
 
 For more examples, check out the [examples folder] in the repository.
 
+# Compression
+This crate does not compress anything by itself. Streams written through
+[`Chunk::stream`] take data that you compressed yourself. The builders that
+produce their own streams, like [`PngImage`] or [`Type1Program`], instead
+accept a `deflate` function that compresses data with zlib-wrapped Deflate,
+for example `|data| miniz_oxide::deflate::compress_to_vec_zlib(data, 6)`.
+Streams compressed with it are written with [`Filter::FlateDecode`].
+
 # Note
 This crate is rather low-level. It does not allocate or validate indirect
 reference IDs for you and it does not check whether you write all required
@@ -90,18 +98,50 @@ mod actions;
 mod annotations;
 mod attributes;
 mod buf;
+mod ccitt;
+#[cfg(feature = "sfnt")]
+mod cff;
 mod chunk;
 mod color;
+#[cfg(feature = "sfnt")]
+mod colr;
 mod content;
+mod deflate;
+#[cfg(feature = "sfnt")]
+mod embed;
+mod encoding;
 mod files;
 mod font;
 mod forms;
 mod functions;
+mod icc;
+mod image;
+mod inflate;
+mod jbig2;
+mod jpeg;
+mod jpx;
+mod mesh;
+mod metrics;
 mod object;
+mod ops;
+#[cfg(feature = "sfnt")]
+mod outline;
+mod png;
+#[cfg_attr(not(feature = "sfnt"), allow(dead_code))]
+mod reader;
 mod renditions;
 mod renumber;
+mod resources;
+#[cfg(feature = "sfnt")]
+mod sfnt;
 mod structure;
+#[cfg(feature = "sfnt")]
+mod subset;
+mod svg;
 mod transitions;
+mod type1;
+mod type3;
+mod validate;
 mod xobject;
 
 /// Strongly typed writers for specific PDF structures.
@@ -128,7 +168,7 @@ pub mod writers {
     pub use files::{EmbeddedFile, EmbeddingParams, FileSpec};
     pub use font::{
         CidFont, Cmap, Differences, Encoding, FontDescriptor, FontDescriptorOverride,
-        Type0Font, Type1Font, Type3Font, WMode, Widths,
+        Type0Font, Type1Font, Type3Font, VerticalMetrics, WMode, Widths,
     };
     pub use forms::{
         CertificateSeedValue, ChoiceOptions, Field, Form, SignatureFieldLock,
@@ -173,9 +213,11 @@ pub mod types {
         LineCapStyle, LineJoinStyle, MaskType, OverprintMode, ProcSet, RenderingIntent,
         TextRenderingMode,
     };
+    pub use encoding::SimpleEncoding;
     pub use files::AssociationKind;
     pub use font::{
-        CidFontType, CjkClass, FontFlags, FontStretch, GlyphId, SystemInfo, UnicodeCmap,
+        CidFontType, CjkClass, CmapBuilder, FontFlags, FontStretch, GlyphId, SystemInfo,
+        UnicodeCmap,
     };
     pub use forms::{
         AccessPermissions, CertificateKeyUsage, CertificateSeedValueFlags,
@@ -184,7 +226,10 @@ pub mod types {
         SignatureDigestMethod, SignatureLockAction, SignatureSeedValueFlags,
     };
     pub use functions::{InterpolationOrder, PostScriptOp};
+    pub use icc::{IccClass, IccColorSpace};
+    pub use metrics::StandardFont;
     pub use object::Predictor;
+    pub use ops::{Op, Operand, ParseErrorKind, PositionedItem, PropertiesOperand};
     pub use renditions::{MediaClipType, RenditionType, TempFileType};
     pub use structure::{
         BlockLevelRoleSubtype, Direction, InlineLevelRoleSubtype,
@@ -193,17 +238,40 @@ pub mod types {
         StructRoleType, StructRoleType2, TabOrder, TrappingStatus,
     };
     pub use transitions::{TransitionAngle, TransitionStyle};
+    pub use validate::{GraphicsObject, Violation, ViolationKind};
     pub use xobject::SMaskInData;
 }
 
 pub use self::buf::{Buf, Limits};
+pub use self::ccitt::CcittImage;
 pub use self::chunk::{Chunk, Settings};
+#[cfg(feature = "sfnt")]
+pub use self::colr::ColorFont;
 pub use self::content::Content;
+#[cfg(feature = "sfnt")]
+pub use self::embed::EmbeddedFont;
+pub use self::encoding::{char_to_glyph, glyph_to_char, EncodeError, SimpleEncoder};
+pub use self::font::FontError;
+pub use self::functions::FunctionSamples;
+pub use self::icc::IccInfo;
+pub use self::image::{ImageError, PixelFormat, RawImage};
+pub use self::jbig2::Jbig2File;
+pub use self::jpeg::JpegImage;
+pub use self::jpx::JpxImage;
+pub use self::mesh::{LatticeMesh, MeshShading, PatchMesh, TriangleMesh};
 pub use self::object::{
     Array, Date, Dict, Filter, Finish, LanguageIdentifier, Name, Null, Obj, Primitive,
-    Rect, Ref, Rewrite, Str, Stream, TextStr, TextStrLike, TextStrWithLang, TypedArray,
-    TypedDict, Writer,
+    Rect, Ref, Rewrite, Str, Stream, TextStr, TextStrLike, TextStrWithLang, Transform,
+    TypedArray, TypedDict, Writer,
 };
+pub use self::ops::{ContentParser, ParseError};
+#[cfg(feature = "sfnt")]
+pub use self::outline::OutlineFont;
+pub use self::png::PngImage;
+pub use self::resources::ResourceCollector;
+pub use self::svg::SvgPathError;
+pub use self::type1::Type1Program;
+pub use self::type3::Type3Builder;
 
 use std::fmt::{self, Debug, Formatter};
 use std::io::Write;
@@ -0,0 +1,596 @@
+use super::*;
+use crate::writers::StreamShadingType;
+
+/// Builds the data of a _free-form triangle mesh_ shading (type 4).
+///
+/// Triangles are added one by one, or as strips and fans that reuse vertices
+/// of the previous triangle. Each vertex has a color with as many components
+/// as the shading's color space has, or a single parametric value if the
+/// shading has a [function](StreamShading::function).
+///
+/// ```
+/// use pdf_writer::{Chunk, Ref, TriangleMesh};
+///
+/// let mut mesh = TriangleMesh::new(3);
+/// mesh.triangle(
+///     [[0.0, 0.0], [100.0, 0.0], [0.0, 100.0]],
+///     [&[1.0, 0.0, 0.0], &[0.0, 1.0, 0.0], &[0.0, 0.0, 1.0]],
+/// );
+/// mesh.strip([100.0, 100.0], &[1.0, 1.0, 0.0]);
+///
+/// let mut chunk = Chunk::new();
+/// let shading = mesh.encode();
+/// shading.write(&mut chunk, Ref::new(1)).color_space().device_rgb();
+/// ```
+pub struct TriangleMesh {
+    encoder: MeshEncoder,
+}
+
+impl TriangleMesh {
+    /// Create a new mesh whose colors have the given number of components.
+    pub fn new(components: usize) -> Self {
+        Self { encoder: MeshEncoder::new(components) }
+    }
+
+    /// Set the number of bits per coordinate: 8, 16, 24 or 32. Defaults to 16.
+    pub fn bits_per_coordinate(&mut self, bits: u8) -> &mut Self {
+        self.encoder.set_bits_per_coordinate(bits);
+        self
+    }
+
+    /// Set the number of bits per color component: 8 or 16. Defaults to 16.
+    pub fn bits_per_component(&mut self, bits: u8) -> &mut Self {
+        self.encoder.set_bits_per_component(bits);
+        self
+    }
+
+    /// Add a triangle that doesn't share vertices with the previous one.
+    pub fn triangle(&mut self, points: [[f32; 2]; 3], colors: [&[f32]; 3]) -> &mut Self {
+        for (point, color) in points.into_iter().zip(colors) {
+            self.encoder.flag(0);
+            self.encoder.vertex(point, color);
+        }
+        self
+    }
+
+    /// Add a triangle made of the last two vertices of the previous triangle
+    /// and a new vertex.
+    ///
+    /// Panics if there is no previous triangle.
+    pub fn strip(&mut self, point: [f32; 2], color: &[f32]) -> &mut Self {
+        self.continued(1, point, color)
+    }
+
+    /// Add a triangle made of the first and last vertex of the previous
+    /// triangle and a new vertex.
+    ///
+    /// Panics if there is no previous triangle.
+    pub fn fan(&mut self, point: [f32; 2], color: &[f32]) -> &mut Self {
+        self.continued(2, point, color)
+    }
+
+    fn continued(&mut self, flag: u8, point: [f32; 2], color: &[f32]) -> &mut Self {
+        assert!(!self.encoder.items.is_empty(), "mesh must start with a triangle");
+        self.encoder.flag(flag);
+        self.encoder.vertex(point, color);
+        self
+    }
+
+    /// Encode the mesh.
+    pub fn encode(&self) -> MeshShading {
+        self.encoder.encode(StreamShadingType::FreeformGouraud, None)
+    }
+}
+
+/// Builds the data of a _lattice-form triangle mesh_ shading (type 5).
+///
+/// Vertices are added row by row. Each quadrilateral formed by two adjacent
+/// vertices in two adjacent rows is split into two smoothly shaded triangles.
+///
+/// ```
+/// use pdf_writer::{Chunk, LatticeMesh, Ref};
+///
+/// let mut mesh = LatticeMesh::new(2, 1);
+/// mesh.vertex([0.0, 0.0], &[0.0]).vertex([100.0, 0.0], &[0.5]);
+/// mesh.vertex([0.0, 100.0], &[0.5]).vertex([100.0, 100.0], &[1.0]);
+///
+/// let mut chunk = Chunk::new();
+/// let shading = mesh.encode();
+/// shading.write(&mut chunk, Ref::new(1)).color_space().device_gray();
+/// ```
+pub struct LatticeMesh {
+    encoder: MeshEncoder,
+    vertices_per_row: usize,
+}
+
+impl LatticeMesh {
+    /// Create a new lattice with the given number of vertices per row, which
+    /// must be at least two, and colors with the given number of components.
+    pub fn new(vertices_per_row: usize, components: usize) -> Self {
+        assert!(vertices_per_row >= 2, "lattice rows must have at least two vertices");
+        Self {
+            encoder: MeshEncoder::new(components),
+            vertices_per_row,
+        }
+    }
+
+    /// Set the number of bits per coordinate: 8, 16, 24 or 32. Defaults to 16.
+    pub fn bits_per_coordinate(&mut self, bits: u8) -> &mut Self {
+        self.encoder.set_bits_per_coordinate(bits);
+        self
+    }
+
+    /// Set the number of bits per color component: 8 or 16. Defaults to 16.
+    pub fn bits_per_component(&mut self, bits: u8) -> &mut Self {
+        self.encoder.set_bits_per_component(bits);
+        self
+    }
+
+    /// Add the next vertex.
+    pub fn vertex(&mut self, point: [f32; 2], color: &[f32]) -> &mut Self {
+        self.encoder.vertex(point, color);
+        self
+    }
+
+    /// Encode the lattice.
+    ///
+    /// Panics if there are less than two rows or the last row is incomplete.
+    pub fn encode(&self) -> MeshShading {
+        let vertices = self.encoder.colors.len() / self.encoder.components;
+        let rows = vertices / self.vertices_per_row;
+        assert!(
+            rows >= 2 && rows * self.vertices_per_row == vertices,
+            "lattice must consist of at least two complete rows",
+        );
+
+        let vertices_per_row = self.vertices_per_row as i32;
+        self.encoder
+            .encode(StreamShadingType::LatticeGouraud, Some(vertices_per_row))
+    }
+}
+
+/// Builds the data of a _Coons patch mesh_ (type 6) or a _tensor-product
+/// patch mesh_ (type 7) shading.
+///
+/// A patch is given by the 12 points of its boundary, which consists of four
+/// cubic Bézier curves, and by the colors of its four corners. The boundary
+/// starts at a corner and the colors are given in the same order as the
+/// corners appear on it. Tensor-product patches additionally have four
+/// interior control points.
+///
+/// If a patch starts with one of the last three edges of the previous patch,
+/// traversed in the same direction, the shared points and colors are only
+/// encoded once.
+///
+/// ```
+/// use pdf_writer::{Chunk, PatchMesh, Ref};
+///
+/// let mut mesh = PatchMesh::coons(1);
+/// mesh.patch(
+///     &[
+///         [0.0, 0.0], [0.0, 30.0], [0.0, 70.0], [0.0, 100.0],
+///         [30.0, 100.0], [70.0, 100.0], [100.0, 100.0],
+///         [100.0, 70.0], [100.0, 30.0], [100.0, 0.0],
+///         [70.0, 0.0], [30.0, 0.0],
+///     ],
+///     [&[0.0], &[0.3], &[1.0], &[0.6]],
+/// );
+///
+/// let mut chunk = Chunk::new();
+/// let shading = mesh.encode();
+/// shading.write(&mut chunk, Ref::new(1)).color_space().device_gray();
+/// ```
+pub struct PatchMesh {
+    encoder: MeshEncoder,
+    tensor: bool,
+    /// The points and corner colors of the previous patch.
+    previous: Option<(Vec<[f32; 2]>, Vec<f32>)>,
+}
+
+impl PatchMesh {
+    /// Create a new Coons patch mesh whose colors have the given number of
+    /// components.
+    pub fn coons(components: usize) -> Self {
+        Self {
+            encoder: MeshEncoder::new(components),
+            tensor: false,
+            previous: None,
+        }
+    }
+
+    /// Create a new tensor-product patch mesh whose colors have the given
+    /// number of components.
+    pub fn tensor(components: usize) -> Self {
+        Self {
+            encoder: MeshEncoder::new(components),
+            tensor: true,
+            previous: None,
+        }
+    }
+
+    /// Set the number of bits per coordinate: 8, 16, 24 or 32. Defaults to 16.
+    pub fn bits_per_coordinate(&mut self, bits: u8) -> &mut Self {
+        self.encoder.set_bits_per_coordinate(bits);
+        self
+    }
+
+    /// Set the number of bits per color component: 8 or 16. Defaults to 16.
+    pub fn bits_per_component(&mut self, bits: u8) -> &mut Self {
+        self.encoder.set_bits_per_component(bits);
+        self
+    }
+
+    /// Add a patch.
+    ///
+    /// Coons patches have the 12 points of the boundary. Tensor-product
+    /// patches additionally have the four interior points, starting with the
+    /// one next to the first corner and continuing in the direction of the
+    /// boundary. Panics if the number of points doesn't match.
+    pub fn patch(&mut self, points: &[[f32; 2]], colors: [&[f32]; 4]) -> &mut Self {
+        let expected = if self.tensor { 16 } else { 12 };
+        assert_eq!(points.len(), expected, "patch must have {expected} points");
+
+        let n = self.encoder.components;
+        let colors: Vec<f32> = colors
+            .into_iter()
+            .inspect(|color| assert_eq!(color.len(), n, "color must have {n} components"))
+            .flatten()
+            .copied()
+            .collect();
+
+        // The flag of the previous patch's edge that this patch starts with.
+        let shared = self.previous.as_ref().and_then(|(prev_points, prev_colors)| {
+            // The points and colors of the edges, in the order of the flags.
+            let edges = [
+                ([3, 4, 5, 6], [1, 2]),
+                ([6, 7, 8, 9], [2, 3]),
+                ([9, 10, 11, 0], [3, 0]),
+            ];
+            let color = |i: usize| &prev_colors[i * n..(i + 1) * n];
+            (1..).zip(edges).find_map(|(flag, (edge_points, edge_colors))| {
+                let same =
+                    edge_points.iter().zip(points).all(|(&i, p)| prev_points[i] == *p)
+                        && edge_colors
+                            .iter()
+                            .zip(colors.chunks_exact(n))
+                            .all(|(&i, c)| color(i) == c);
+                same.then_some(flag)
+            })
+        });
+
+        let (flag, skip) = match shared {
+            Some(flag) => (flag, 4),
+            None => (0, 0),
+        };
+
+        self.encoder.flag(flag);
+        for &point in &points[skip..] {
+            self.encoder.point(point);
+        }
+        for color in colors.chunks_exact(n).skip(skip / 2) {
+            self.encoder.color(color);
+        }
+
+        self.previous = Some((points.to_vec(), colors));
+        self
+    }
+
+    /// Encode the mesh.
+    pub fn encode(&self) -> MeshShading {
+        let kind = if self.tensor {
+            StreamShadingType::TensorProductPatch
+        } else {
+            StreamShadingType::CoonsPatch
+        };
+        self.encoder.encode(kind, None)
+    }
+}
+
+/// The encoded data of a mesh shading together with the attributes that
+/// describe its layout.
+///
+/// This struct is created by [`TriangleMesh::encode`],
+/// [`LatticeMesh::encode`] and [`PatchMesh::encode`].
+pub struct MeshShading {
+    kind: StreamShadingType,
+    data: Vec<u8>,
+    bits_per_coordinate: u8,
+    bits_per_component: u8,
+    decode: Vec<f32>,
+    vertices_per_row: Option<i32>,
+}
+
+impl MeshShading {
+    /// The encoded data.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The ranges of the coordinates and color components, as written into
+    /// the `/Decode` attribute.
+    pub fn decode(&self) -> &[f32] {
+        &self.decode
+    }
+
+    /// Start writing the shading stream.
+    ///
+    /// Writes the `/ShadingType`, `/BitsPerCoordinate`, `/BitsPerComponent`,
+    /// `/BitsPerFlag`, `/Decode` and `/VerticesPerRow` attributes. The color
+    /// space and, if the colors are parametric values, the function must be
+    /// written through the returned writer.
+    pub fn write<'a>(&'a self, chunk: &'a mut Chunk, id: Ref) -> StreamShading<'a> {
+        let mut shading = chunk.stream_shading(id, &self.data);
+        shading.shading_type(self.kind);
+        shading.bits_per_coordinate(self.bits_per_coordinate.into());
+        shading.bits_per_component(self.bits_per_component.into());
+        match self.vertices_per_row {
+            Some(vertices) => shading.vertices_per_row(vertices),
+            None => shading.bits_per_flag(8),
+        };
+        shading.decode(self.decode.iter().copied());
+        shading
+    }
+}
+
+/// Collects the flags, points and colors of a mesh in stream order.
+///
+/// Flags take up a whole byte and the number of bits per coordinate and
+/// component are multiples of eight, so every vertex and patch starts at a
+/// byte boundary regardless of whether the reader realigns.
+struct MeshEncoder {
+    components: usize,
+    bits_per_coordinate: u8,
+    bits_per_component: u8,
+    items: Vec<MeshItem>,
+    /// The color components, in the order of the color items.
+    colors: Vec<f32>,
+}
+
+enum MeshItem {
+    Flag(u8),
+    Point([f32; 2]),
+    Color,
+}
+
+impl MeshEncoder {
+    fn new(components: usize) -> Self {
+        assert!(components > 0, "colors must have at least one component");
+        Self {
+            components,
+            bits_per_coordinate: 16,
+            bits_per_component: 16,
+            items: vec![],
+            colors: vec![],
+        }
+    }
+
+    fn set_bits_per_coordinate(&mut self, bits: u8) {
+        assert!(
+            matches!(bits, 8 | 16 | 24 | 32),
+            "bits per coordinate must be 8, 16, 24 or 32"
+        );
+        self.bits_per_coordinate = bits;
+    }
+
+    fn set_bits_per_component(&mut self, bits: u8) {
+        assert!(matches!(bits, 8 | 16), "bits per component must be 8 or 16");
+        self.bits_per_component = bits;
+    }
+
+    fn flag(&mut self, flag: u8) {
+        self.items.push(MeshItem::Flag(flag));
+    }
+
+    fn point(&mut self, point: [f32; 2]) {
+        self.items.push(MeshItem::Point(point));
+    }
+
+    fn color(&mut self, color: &[f32]) {
+        let n = self.components;
+        assert_eq!(color.len(), n, "color must have {n} components");
+        self.items.push(MeshItem::Color);
+        self.colors.extend_from_slice(color);
+    }
+
+    fn vertex(&mut self, point: [f32; 2], color: &[f32]) {
+        self.point(point);
+        self.color(color);
+    }
+
+    fn encode(
+        &self,
+        kind: StreamShadingType,
+        vertices_per_row: Option<i32>,
+    ) -> MeshShading {
+        let n = self.components;
+        let mut ranges = vec![(f32::INFINITY, f32::NEG_INFINITY); 2 + n];
+        let mut extend = |i: usize, v: f32| {
+            let (min, max) = &mut ranges[i];
+            *min = min.min(v);
+            *max = max.max(v);
+        };
+
+        for item in &self.items {
+            if let MeshItem::Point([x, y]) = item {
+                extend(0, *x);
+                extend(1, *y);
+            }
+        }
+
+        for color in self.colors.chunks_exact(n) {
+            for (i, &c) in color.iter().enumerate() {
+                extend(2 + i, c);
+            }
+        }
+
+        // Empty and degenerate ranges are widened such that the decode array
+        // stays valid.
+        for (min, max) in &mut ranges {
+            if *min > *max {
+                (*min, *max) = (0.0, 1.0);
+            } else if *min == *max {
+                *max = *min + 1.0;
+            }
+        }
+
+        let mut data = vec![];
+        let mut colors = self.colors.chunks_exact(n);
+        for item in &self.items {
+            match *item {
+                MeshItem::Flag(flag) => data.push(flag),
+                MeshItem::Point([x, y]) => {
+                    let bits = self.bits_per_coordinate;
+                    quantize(&mut data, x, ranges[0], bits);
+                    quantize(&mut data, y, ranges[1], bits);
+                }
+                MeshItem::Color => {
+                    let color = colors.next().unwrap();
+                    for (i, &c) in color.iter().enumerate() {
+                        quantize(&mut data, c, ranges[2 + i], self.bits_per_component);
+                    }
+                }
+            }
+        }
+
+        MeshShading {
+            kind,
+            data,
+            bits_per_coordinate: self.bits_per_coordinate,
+            bits_per_component: self.bits_per_component,
+            decode: ranges.into_iter().flat_map(|(min, max)| [min, max]).collect(),
+            vertices_per_row,
+        }
+    }
+}
+
+/// Map a value from its range to an unsigned integer with the given number of
+/// bits and append it in big-endian order.
+fn quantize(data: &mut Vec<u8>, value: f32, (min, max): (f32, f32), bits: u8) {
+    let scale = ((1u64 << bits) - 1) as f64;
+    let t = (f64::from(value) - f64::from(min)) / (f64::from(max) - f64::from(min));
+    let q = (t * scale).round().clamp(0.0, scale) as u32;
+    data.extend_from_slice(&q.to_be_bytes()[4 - usize::from(bits / 8)..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triangle_mesh() {
+        let mut mesh = TriangleMesh::new(1);
+        mesh.bits_per_coordinate(8).bits_per_component(8);
+        mesh.triangle([[0.0, 0.0], [10.0, 0.0], [0.0, 5.0]], [&[0.0], &[1.0], &[0.5]]);
+        mesh.strip([10.0, 5.0], &[0.0]);
+        mesh.fan([5.0, 10.0], &[1.0]);
+
+        let shading = mesh.encode();
+        assert_eq!(shading.decode(), [0.0, 10.0, 0.0, 10.0, 0.0, 1.0]);
+        assert_eq!(
+            shading.as_bytes(),
+            [
+                0, 0, 0, 0, //
+                0, 255, 0, 255, //
+                0, 0, 128, 128, //
+                1, 255, 128, 0, //
+                2, 128, 255, 255,
+            ]
+        );
+
+        let mut chunk = Chunk::new();
+        shading.write(&mut chunk, Ref::new(1)).color_space().device_gray();
+        let pdf = String::from_utf8_lossy(chunk.as_bytes());
+        assert!(pdf.contains("/ShadingType 4"));
+        assert!(pdf.contains("/BitsPerFlag 8"));
+        assert!(pdf.contains("/Decode [0 10 0 10 0 1]"));
+    }
+
+    #[test]
+    fn test_lattice_mesh() {
+        let mut mesh = LatticeMesh::new(2, 3);
+        mesh.vertex([0.0, 0.0], &[1.0, 0.0, 0.0])
+            .vertex([4.0, 0.0], &[1.0, 0.0, 0.0]);
+        mesh.vertex([0.0, 2.0], &[1.0, 0.0, 1.0])
+            .vertex([4.0, 2.0], &[1.0, 0.0, 1.0]);
+
+        let shading = mesh.encode();
+        assert_eq!(shading.decode(), [0.0, 4.0, 0.0, 2.0, 1.0, 2.0, 0.0, 1.0, 0.0, 1.0]);
+        assert_eq!(
+            shading.as_bytes(),
+            [
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, //
+                255, 255, 0, 0, 0, 0, 0, 0, 0, 0, //
+                0, 0, 255, 255, 0, 0, 0, 0, 255, 255, //
+                255, 255, 255, 255, 0, 0, 0, 0, 255, 255,
+            ]
+        );
+
+        let mut chunk = Chunk::new();
+        shading.write(&mut chunk, Ref::new(1));
+        let pdf = String::from_utf8_lossy(chunk.as_bytes());
+        assert!(pdf.contains("/VerticesPerRow 2"));
+        assert!(!pdf.contains("/BitsPerFlag"));
+    }
+
+    #[test]
+    #[should_panic(expected = "complete rows")]
+    fn test_lattice_mesh_incomplete() {
+        let mut mesh = LatticeMesh::new(2, 1);
+        mesh.vertex([0.0, 0.0], &[0.0]).vertex([1.0, 0.0], &[0.0]);
+        mesh.vertex([0.0, 1.0], &[0.0]);
+        mesh.encode();
+    }
+
+    #[test]
+    fn test_patch_mesh_edge_sharing() {
+        // A square patch with its boundary starting at the bottom left.
+        let square = |x: f32| -> Vec<[f32; 2]> {
+            let mut points = vec![];
+            for i in 0..4 {
+                points.push([x, i as f32]);
+            }
+            for i in 1..4 {
+                points.push([x + i as f32, 3.0]);
+            }
+            for i in (0..3).rev() {
+                points.push([x + 3.0, i as f32]);
+            }
+            for i in (1..3).rev() {
+                points.push([x + i as f32, 0.0]);
+            }
+            points
+        };
+
+        let mut mesh = PatchMesh::tensor(1);
+        mesh.bits_per_coordinate(8).bits_per_component(8);
+        let mut first = square(0.0);
+        first.extend([[1.0, 1.0], [1.0, 2.0], [2.0, 2.0], [2.0, 1.0]]);
+        mesh.patch(&first, [&[0.0], &[0.0], &[1.0], &[1.0]]);
+
+        // The right neighbour starts with the first patch's right edge, running
+        // from top to bottom.
+        let mut second = first[6..10].to_vec();
+        second.extend([[4.0, 0.0], [5.0, 0.0], [6.0, 0.0], [6.0, 1.0]]);
+        second.extend([[6.0, 2.0], [6.0, 3.0], [5.0, 3.0], [4.0, 3.0]]);
+        second.extend([[4.0, 2.0], [4.0, 1.0], [5.0, 1.0], [5.0, 2.0]]);
+        mesh.patch(&second, [&[1.0], &[1.0], &[0.0], &[0.0]]);
+
+        // A patch that doesn't touch the previous one.
+        let mut third = square(10.0);
+        third.extend([[11.0, 1.0], [11.0, 2.0], [12.0, 2.0], [12.0, 1.0]]);
+        mesh.patch(&third, [&[0.0], &[0.0], &[0.0], &[0.0]]);
+
+        let shading = mesh.encode();
+        let data = shading.as_bytes();
+        assert_eq!(data.len(), (1 + 32 + 4) + (1 + 24 + 2) + (1 + 32 + 4));
+        assert_eq!(data[0], 0);
+        assert_eq!(data[37], 2);
+        assert_eq!(data[64], 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "patch must have 12 points")]
+    fn test_patch_mesh_wrong_points() {
+        PatchMesh::coons(1).patch(&[[0.0; 2]; 16], [&[0.0]; 4]);
+    }
+}
@@ -0,0 +1,1082 @@
+use super::*;
+use crate::types::{FontFlags, SimpleEncoding};
+
+/// One of the 14 standard fonts that every conforming PDF reader provides.
+///
+/// The metrics are taken from the Adobe Core14 AFM files. They cover the full
+/// character sets of all fonts, that is the Adobe standard Latin character
+/// set and the Central European glyphs for the Latin fonts, as well as the
+/// kerning pairs of _Helvetica_ and _Times_ for the basic Latin letters and
+/// punctuation.
+///
+/// In PDF/A files, the standard 14 fonts are unavailable, so you must embed the
+/// font data.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StandardFont {
+    /// _Courier_.
+    Courier,
+    /// _Courier-Bold_.
+    CourierBold,
+    /// _Courier-Oblique_.
+    CourierOblique,
+    /// _Courier-BoldOblique_.
+    CourierBoldOblique,
+    /// _Helvetica_.
+    Helvetica,
+    /// _Helvetica-Bold_.
+    HelveticaBold,
+    /// _Helvetica-Oblique_.
+    HelveticaOblique,
+    /// _Helvetica-BoldOblique_.
+    HelveticaBoldOblique,
+    /// _Times-Roman_.
+    TimesRoman,
+    /// _Times-Bold_.
+    TimesBold,
+    /// _Times-Italic_.
+    TimesItalic,
+    /// _Times-BoldItalic_.
+    TimesBoldItalic,
+    /// _Symbol_.
+    Symbol,
+    /// _ZapfDingbats_.
+    ZapfDingbats,
+}
+
+impl StandardFont {
+    /// All standard 14 fonts.
+    pub const ALL: [Self; 14] = [
+        Self::Courier,
+        Self::CourierBold,
+        Self::CourierOblique,
+        Self::CourierBoldOblique,
+        Self::Helvetica,
+        Self::HelveticaBold,
+        Self::HelveticaOblique,
+        Self::HelveticaBoldOblique,
+        Self::TimesRoman,
+        Self::TimesBold,
+        Self::TimesItalic,
+        Self::TimesBoldItalic,
+        Self::Symbol,
+        Self::ZapfDingbats,
+    ];
+
+    /// The PostScript name of the font, for use with
+    /// [`Type1Font::base_font`] and [`FontDescriptor::name`].
+    pub fn to_name(self) -> Name<'static> {
+        Name(match self {
+            Self::Courier => b"Courier",
+            Self::CourierBold => b"Courier-Bold",
+            Self::CourierOblique => b"Courier-Oblique",
+            Self::CourierBoldOblique => b"Courier-BoldOblique",
+            Self::Helvetica => b"Helvetica",
+            Self::HelveticaBold => b"Helvetica-Bold",
+            Self::HelveticaOblique => b"Helvetica-Oblique",
+            Self::HelveticaBoldOblique => b"Helvetica-BoldOblique",
+            Self::TimesRoman => b"Times-Roman",
+            Self::TimesBold => b"Times-Bold",
+            Self::TimesItalic => b"Times-Italic",
+            Self::TimesBoldItalic => b"Times-BoldItalic",
+            Self::Symbol => b"Symbol",
+            Self::ZapfDingbats => b"ZapfDingbats",
+        })
+    }
+
+    /// Look up a standard font by its PostScript name.
+    pub fn from_name(name: Name) -> Option<Self> {
+        Self::ALL.into_iter().find(|font| font.to_name() == name)
+    }
+
+    /// The encoding the font uses if a font dictionary has no `/Encoding`.
+    pub fn built_in_encoding(self) -> SimpleEncoding {
+        match self {
+            Self::Symbol => SimpleEncoding::Symbol,
+            Self::ZapfDingbats => SimpleEncoding::ZapfDingbats,
+            _ => SimpleEncoding::Standard,
+        }
+    }
+
+    /// The advance width of a glyph in thousandths of a unit of text space.
+    ///
+    /// Returns `None` if the font has no glyph with this name.
+    pub fn glyph_width(self, glyph: &str) -> Option<f32> {
+        let (names, widths) = self.widths();
+        let i = names.binary_search(&glyph).ok()?;
+        Some(f32::from(widths[i]))
+    }
+
+    /// The kerning between two adjacent glyphs in thousandths of a unit of
+    /// text space.
+    ///
+    /// Negative values move the glyphs closer together. Returns zero if the
+    /// pair isn't kerned.
+    pub fn kerning(self, left: &str, right: &str) -> f32 {
+        let pairs = self.kerning_pairs();
+        pairs
+            .binary_search_by(|&(l, r, _)| (l, r).cmp(&(left, right)))
+            .map_or(0.0, |i| f32::from(pairs[i].2))
+    }
+
+    /// The width of a sequence of glyphs when shown with the given font size.
+    ///
+    /// Glyphs that the font doesn't have count as zero width.
+    /// [Kerning](Self::kerning), character spacing, and word spacing are not
+    /// applied.
+    pub fn measure_glyphs<'a>(
+        self,
+        glyphs: impl IntoIterator<Item = &'a str>,
+        size: f32,
+    ) -> f32 {
+        let sum: f32 =
+            glyphs.into_iter().filter_map(|glyph| self.glyph_width(glyph)).sum();
+        sum * size / 1000.0
+    }
+
+    /// The advance width of the glyph mapped to a character code in the given
+    /// encoding, in thousandths of a unit of text space.
+    pub fn char_width(self, code: u8, encoding: SimpleEncoding) -> Option<f32> {
+        self.glyph_width(encoding.glyph(code)?)
+    }
+
+    /// The width of a string of character codes when shown with the given font
+    /// size.
+    ///
+    /// Character codes without a glyph count as zero width.
+    /// [Kerning](Self::kerning), character spacing, and word spacing are not
+    /// applied.
+    pub fn measure(self, text: &[u8], encoding: SimpleEncoding, size: f32) -> f32 {
+        let sum: f32 =
+            text.iter().filter_map(|&code| self.char_width(code, encoding)).sum();
+        sum * size / 1000.0
+    }
+
+    /// The font flags for the `/Flags` attribute of a font descriptor.
+    pub fn flags(self) -> FontFlags {
+        let flags = match self {
+            Self::Courier
+            | Self::CourierBold
+            | Self::CourierOblique
+            | Self::CourierBoldOblique => {
+                FontFlags::FIXED_PITCH | FontFlags::SERIF | FontFlags::NON_SYMBOLIC
+            }
+            Self::Helvetica
+            | Self::HelveticaBold
+            | Self::HelveticaOblique
+            | Self::HelveticaBoldOblique => FontFlags::NON_SYMBOLIC,
+            Self::TimesRoman
+            | Self::TimesBold
+            | Self::TimesItalic
+            | Self::TimesBoldItalic => FontFlags::SERIF | FontFlags::NON_SYMBOLIC,
+            Self::Symbol | Self::ZapfDingbats => FontFlags::SYMBOLIC,
+        };
+
+        if self.italic_angle() != 0.0 {
+            flags | FontFlags::ITALIC
+        } else {
+            flags
+        }
+    }
+
+    /// The font weight, 400 for regular and 700 for bold fonts.
+    pub fn weight(self) -> u16 {
+        match self {
+            Self::CourierBold
+            | Self::CourierBoldOblique
+            | Self::HelveticaBold
+            | Self::HelveticaBoldOblique
+            | Self::TimesBold
+            | Self::TimesBoldItalic => 700,
+            _ => 400,
+        }
+    }
+
+    /// The bounding box of all glyphs in glyph space units.
+    pub fn bbox(self) -> Rect {
+        let [x1, y1, x2, y2] = match self {
+            Self::Courier => [-23.0, -250.0, 715.0, 805.0],
+            Self::CourierBold => [-113.0, -250.0, 749.0, 801.0],
+            Self::CourierOblique => [-27.0, -250.0, 849.0, 805.0],
+            Self::CourierBoldOblique => [-57.0, -250.0, 869.0, 801.0],
+            Self::Helvetica => [-166.0, -225.0, 1000.0, 931.0],
+            Self::HelveticaBold => [-170.0, -228.0, 1003.0, 962.0],
+            Self::HelveticaOblique => [-170.0, -225.0, 1116.0, 931.0],
+            Self::HelveticaBoldOblique => [-174.0, -228.0, 1114.0, 962.0],
+            Self::TimesRoman => [-168.0, -218.0, 1000.0, 898.0],
+            Self::TimesBold => [-168.0, -218.0, 1000.0, 935.0],
+            Self::TimesItalic => [-169.0, -217.0, 1010.0, 883.0],
+            Self::TimesBoldItalic => [-200.0, -218.0, 996.0, 921.0],
+            Self::Symbol => [-180.0, -293.0, 1090.0, 1010.0],
+            Self::ZapfDingbats => [-1.0, -143.0, 981.0, 820.0],
+        };
+        Rect::new(x1, y1, x2, y2)
+    }
+
+    /// The angle of the dominant vertical strokes in degrees counter-clockwise
+    /// from the vertical.
+    pub fn italic_angle(self) -> f32 {
+        match self {
+            Self::CourierOblique
+            | Self::CourierBoldOblique
+            | Self::HelveticaOblique
+            | Self::HelveticaBoldOblique => -12.0,
+            Self::TimesItalic => -15.5,
+            Self::TimesBoldItalic => -15.0,
+            _ => 0.0,
+        }
+    }
+
+    /// The maximum height above the baseline reached by glyphs.
+    ///
+    /// For _Symbol_ and _ZapfDingbats_, whose AFM files define no ascender,
+    /// this is the top of the bounding box.
+    pub fn ascent(self) -> f32 {
+        match self {
+            Self::Courier
+            | Self::CourierBold
+            | Self::CourierOblique
+            | Self::CourierBoldOblique => 629.0,
+            Self::Helvetica
+            | Self::HelveticaBold
+            | Self::HelveticaOblique
+            | Self::HelveticaBoldOblique => 718.0,
+            Self::TimesRoman
+            | Self::TimesBold
+            | Self::TimesItalic
+            | Self::TimesBoldItalic => 683.0,
+            Self::Symbol | Self::ZapfDingbats => self.bbox().y2,
+        }
+    }
+
+    /// The maximum depth below the baseline reached by glyphs (a negative
+    /// number).
+    ///
+    /// For _Symbol_ and _ZapfDingbats_, whose AFM files define no descender,
+    /// this is the bottom of the bounding box.
+    pub fn descent(self) -> f32 {
+        match self {
+            Self::Courier
+            | Self::CourierBold
+            | Self::CourierOblique
+            | Self::CourierBoldOblique => -157.0,
+            Self::Helvetica
+            | Self::HelveticaBold
+            | Self::HelveticaOblique
+            | Self::HelveticaBoldOblique => -207.0,
+            Self::TimesRoman
+            | Self::TimesBold
+            | Self::TimesItalic
+            | Self::TimesBoldItalic => -217.0,
+            Self::Symbol | Self::ZapfDingbats => self.bbox().y1,
+        }
+    }
+
+    /// The height of flat capital letters. `None` for _Symbol_ and
+    /// _ZapfDingbats_.
+    pub fn cap_height(self) -> Option<f32> {
+        match self {
+            Self::Courier
+            | Self::CourierBold
+            | Self::CourierOblique
+            | Self::CourierBoldOblique => Some(562.0),
+            Self::Helvetica
+            | Self::HelveticaBold
+            | Self::HelveticaOblique
+            | Self::HelveticaBoldOblique => Some(718.0),
+            Self::TimesRoman => Some(662.0),
+            Self::TimesBold => Some(676.0),
+            Self::TimesItalic => Some(653.0),
+            Self::TimesBoldItalic => Some(669.0),
+            Self::Symbol | Self::ZapfDingbats => None,
+        }
+    }
+
+    /// The height of flat lowercase letters. `None` for _Symbol_ and
+    /// _ZapfDingbats_.
+    pub fn x_height(self) -> Option<f32> {
+        match self {
+            Self::Courier | Self::CourierOblique => Some(426.0),
+            Self::CourierBold | Self::CourierBoldOblique => Some(439.0),
+            Self::Helvetica | Self::HelveticaOblique => Some(523.0),
+            Self::HelveticaBold | Self::HelveticaBoldOblique => Some(532.0),
+            Self::TimesRoman => Some(450.0),
+            Self::TimesBold => Some(461.0),
+            Self::TimesItalic => Some(441.0),
+            Self::TimesBoldItalic => Some(462.0),
+            Self::Symbol | Self::ZapfDingbats => None,
+        }
+    }
+
+    /// The thickness of the dominant vertical stems of glyphs.
+    pub fn stem_v(self) -> f32 {
+        match self {
+            Self::Courier | Self::CourierOblique => 51.0,
+            Self::CourierBold | Self::CourierBoldOblique => 106.0,
+            Self::Helvetica | Self::HelveticaOblique => 88.0,
+            Self::HelveticaBold | Self::HelveticaBoldOblique => 140.0,
+            Self::TimesRoman => 84.0,
+            Self::TimesBold => 139.0,
+            Self::TimesItalic => 76.0,
+            Self::TimesBoldItalic => 121.0,
+            Self::Symbol => 85.0,
+            Self::ZapfDingbats => 90.0,
+        }
+    }
+
+    /// The thickness of the dominant horizontal stems of glyphs.
+    pub fn stem_h(self) -> f32 {
+        match self {
+            Self::Courier | Self::CourierOblique => 51.0,
+            Self::CourierBold | Self::CourierBoldOblique => 84.0,
+            Self::Helvetica | Self::HelveticaOblique => 76.0,
+            Self::HelveticaBold | Self::HelveticaBoldOblique => 118.0,
+            Self::TimesRoman => 28.0,
+            Self::TimesBold => 44.0,
+            Self::TimesItalic => 32.0,
+            Self::TimesBoldItalic => 42.0,
+            Self::Symbol => 92.0,
+            Self::ZapfDingbats => 28.0,
+        }
+    }
+
+    /// The glyph names (sorted) and their widths. The oblique variants of
+    /// Courier and Helvetica share the widths of their upright counterparts.
+    fn widths(self) -> (&'static [&'static str], &'static [u16]) {
+        match self {
+            Self::Courier
+            | Self::CourierBold
+            | Self::CourierOblique
+            | Self::CourierBoldOblique => (&LATIN_GLYPHS, &COURIER_WIDTHS),
+            Self::Helvetica | Self::HelveticaOblique => {
+                (&LATIN_GLYPHS, &HELVETICA_WIDTHS)
+            }
+            Self::HelveticaBold | Self::HelveticaBoldOblique => {
+                (&LATIN_GLYPHS, &HELVETICA_BOLD_WIDTHS)
+            }
+            Self::TimesRoman => (&LATIN_GLYPHS, &TIMES_ROMAN_WIDTHS),
+            Self::TimesBold => (&LATIN_GLYPHS, &TIMES_BOLD_WIDTHS),
+            Self::TimesItalic => (&LATIN_GLYPHS, &TIMES_ITALIC_WIDTHS),
+            Self::TimesBoldItalic => (&LATIN_GLYPHS, &TIMES_BOLD_ITALIC_WIDTHS),
+            Self::Symbol => (&SYMBOL_GLYPHS, &SYMBOL_WIDTHS),
+            Self::ZapfDingbats => (&ZAPF_DINGBATS_GLYPHS, &ZAPF_DINGBATS_WIDTHS),
+        }
+    }
+
+    /// The kerning pairs sorted by the left and then the right glyph name.
+    /// The AFM files of _Courier_, _Symbol_ and _ZapfDingbats_ have none and
+    /// the oblique variants of Helvetica share those of their upright
+    /// counterparts.
+    fn kerning_pairs(self) -> &'static [(&'static str, &'static str, i16)] {
+        match self {
+            Self::Helvetica | Self::HelveticaOblique => &HELVETICA_KERNING,
+            Self::HelveticaBold | Self::HelveticaBoldOblique => &HELVETICA_BOLD_KERNING,
+            Self::TimesRoman => &TIMES_ROMAN_KERNING,
+            Self::TimesBold => &TIMES_BOLD_KERNING,
+            Self::TimesItalic => &TIMES_ITALIC_KERNING,
+            Self::TimesBoldItalic => &TIMES_BOLD_ITALIC_KERNING,
+            _ => &[],
+        }
+    }
+}
+
+#[rustfmt::skip]
+static LATIN_GLYPHS: [&str; 315] = [
+    "A", "AE", "Aacute", "Abreve", "Acircumflex", "Adieresis",
+    "Agrave", "Amacron", "Aogonek", "Aring", "Atilde", "B",
+    "C", "Cacute", "Ccaron", "Ccedilla", "D", "Dcaron",
+    "Dcroat", "Delta", "E", "Eacute", "Ecaron", "Ecircumflex",
+    "Edieresis", "Edotaccent", "Egrave", "Emacron", "Eogonek", "Eth",
+    "Euro", "F", "G", "Gbreve", "Gcommaaccent", "H",
+    "I", "Iacute", "Icircumflex", "Idieresis", "Idotaccent", "Igrave",
+    "Imacron", "Iogonek", "J", "K", "Kcommaaccent", "L",
+    "Lacute", "Lcaron", "Lcommaaccent", "Lslash", "M", "N",
+    "Nacute", "Ncaron", "Ncommaaccent", "Ntilde", "O", "OE",
+    "Oacute", "Ocircumflex", "Odieresis", "Ograve", "Ohungarumlaut", "Omacron",
+    "Oslash", "Otilde", "P", "Q", "R", "Racute",
+    "Rcaron", "Rcommaaccent", "S", "Sacute", "Scaron", "Scedilla",
+    "Scommaaccent", "T", "Tcaron", "Tcommaaccent", "Thorn", "U",
+    "Uacute", "Ucircumflex", "Udieresis", "Ugrave", "Uhungarumlaut", "Umacron",
+    "Uogonek", "Uring", "V", "W", "X", "Y",
+    "Yacute", "Ydieresis", "Z", "Zacute", "Zcaron", "Zdotaccent",
+    "a", "aacute", "abreve", "acircumflex", "acute", "adieresis",
+    "ae", "agrave", "amacron", "ampersand", "aogonek", "aring",
+    "asciicircum", "asciitilde", "asterisk", "at", "atilde", "b",
+    "backslash", "bar", "braceleft", "braceright", "bracketleft", "bracketright",
+    "breve", "brokenbar", "bullet", "c", "cacute", "caron",
+    "ccaron", "ccedilla", "cedilla", "cent", "circumflex", "colon",
+    "comma", "commaaccent", "copyright", "currency", "d", "dagger",
+    "daggerdbl", "dcaron", "dcroat", "degree", "dieresis", "divide",
+    "dollar", "dotaccent", "dotlessi", "e", "eacute", "ecaron",
+    "ecircumflex", "edieresis", "edotaccent", "egrave", "eight", "ellipsis",
+    "emacron", "emdash", "endash", "eogonek", "equal", "eth",
+    "exclam", "exclamdown", "f", "fi", "five", "fl",
+    "florin", "four", "fraction", "g", "gbreve", "gcommaaccent",
+    "germandbls", "grave", "greater", "greaterequal", "guillemotleft", "guillemotright",
+    "guilsinglleft", "guilsinglright", "h", "hungarumlaut", "hyphen", "i",
+    "iacute", "icircumflex", "idieresis", "igrave", "imacron", "iogonek",
+    "j", "k", "kcommaaccent", "l", "lacute", "lcaron",
+    "lcommaaccent", "less", "lessequal", "logicalnot", "lozenge", "lslash",
+    "m", "macron", "minus", "mu", "multiply", "n",
+    "nacute", "ncaron", "ncommaaccent", "nine", "notequal", "ntilde",
+    "numbersign", "o", "oacute", "ocircumflex", "odieresis", "oe",
+    "ogonek", "ograve", "ohungarumlaut", "omacron", "one", "onehalf",
+    "onequarter", "onesuperior", "ordfeminine", "ordmasculine", "oslash", "otilde",
+    "p", "paragraph", "parenleft", "parenright", "partialdiff", "percent",
+    "period", "periodcentered", "perthousand", "plus", "plusminus", "q",
+    "question", "questiondown", "quotedbl", "quotedblbase", "quotedblleft", "quotedblright",
+    "quoteleft", "quoteright", "quotesinglbase", "quotesingle", "r", "racute",
+    "radical", "rcaron", "rcommaaccent", "registered", "ring", "s",
+    "sacute", "scaron", "scedilla", "scommaaccent", "section", "semicolon",
+    "seven", "six", "slash", "space", "sterling", "summation",
+    "t", "tcaron", "tcommaaccent", "thorn", "three", "threequarters",
+    "threesuperior", "tilde", "trademark", "two", "twosuperior", "u",
+    "uacute", "ucircumflex", "udieresis", "ugrave", "uhungarumlaut", "umacron",
+    "underscore", "uogonek", "uring", "v", "w", "x",
+    "y", "yacute", "ydieresis", "yen", "z", "zacute",
+    "zcaron", "zdotaccent", "zero",
+];
+
+static COURIER_WIDTHS: [u16; 315] = [
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600,
+];
+
+static HELVETICA_WIDTHS: [u16; 315] = [
+    667, 1000, 667, 667, 667, 667, 667, 667, 667, 667, 667, 667, 722, 722, 722, 722, 722,
+    722, 722, 612, 667, 667, 667, 667, 667, 667, 667, 667, 667, 722, 556, 611, 778, 778,
+    778, 722, 278, 278, 278, 278, 278, 278, 278, 278, 500, 667, 667, 556, 556, 556, 556,
+    556, 833, 722, 722, 722, 722, 722, 778, 1000, 778, 778, 778, 778, 778, 778, 778, 778,
+    667, 778, 722, 722, 722, 722, 667, 667, 667, 667, 667, 611, 611, 611, 667, 722, 722,
+    722, 722, 722, 722, 722, 722, 722, 667, 944, 667, 667, 667, 667, 611, 611, 611, 611,
+    556, 556, 556, 556, 333, 556, 889, 556, 556, 667, 556, 556, 469, 584, 389, 1015, 556,
+    556, 278, 260, 334, 334, 278, 278, 333, 260, 350, 500, 500, 333, 500, 500, 333, 556,
+    333, 278, 278, 250, 737, 556, 556, 556, 556, 643, 556, 400, 333, 584, 556, 333, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 1000, 556, 1000, 556, 556, 584, 556, 278,
+    333, 278, 500, 556, 500, 556, 556, 167, 556, 556, 556, 611, 333, 584, 549, 556, 556,
+    333, 333, 556, 333, 333, 222, 278, 278, 278, 278, 278, 222, 222, 500, 500, 222, 222,
+    299, 222, 584, 549, 584, 471, 222, 833, 333, 584, 556, 584, 556, 556, 556, 556, 556,
+    549, 556, 556, 556, 556, 556, 556, 944, 333, 556, 556, 556, 556, 834, 834, 333, 370,
+    365, 611, 556, 556, 537, 333, 333, 476, 889, 278, 278, 1000, 584, 584, 556, 556, 611,
+    355, 333, 333, 333, 222, 222, 222, 191, 333, 333, 453, 333, 333, 737, 333, 500, 500,
+    500, 500, 500, 556, 278, 556, 556, 278, 278, 556, 600, 278, 317, 278, 556, 556, 834,
+    333, 333, 1000, 556, 333, 556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 500, 722,
+    500, 500, 500, 500, 556, 500, 500, 500, 500, 556,
+];
+
+static HELVETICA_BOLD_WIDTHS: [u16; 315] = [
+    722, 1000, 722, 722, 722, 722, 722, 722, 722, 722, 722, 722, 722, 722, 722, 722, 722,
+    722, 722, 612, 667, 667, 667, 667, 667, 667, 667, 667, 667, 722, 556, 611, 778, 778,
+    778, 722, 278, 278, 278, 278, 278, 278, 278, 278, 556, 722, 722, 611, 611, 611, 611,
+    611, 833, 722, 722, 722, 722, 722, 778, 1000, 778, 778, 778, 778, 778, 778, 778, 778,
+    667, 778, 722, 722, 722, 722, 667, 667, 667, 667, 667, 611, 611, 611, 667, 722, 722,
+    722, 722, 722, 722, 722, 722, 722, 667, 944, 667, 667, 667, 667, 611, 611, 611, 611,
+    556, 556, 556, 556, 333, 556, 889, 556, 556, 722, 556, 556, 584, 584, 389, 975, 556,
+    611, 278, 280, 389, 389, 333, 333, 333, 280, 350, 556, 556, 333, 556, 556, 333, 556,
+    333, 333, 278, 250, 737, 556, 611, 556, 556, 743, 611, 400, 333, 584, 556, 333, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 1000, 556, 1000, 556, 556, 584, 611, 333,
+    333, 333, 611, 556, 611, 556, 556, 167, 611, 611, 611, 611, 333, 584, 549, 556, 556,
+    333, 333, 611, 333, 333, 278, 278, 278, 278, 278, 278, 278, 278, 556, 556, 278, 278,
+    400, 278, 584, 549, 584, 494, 278, 889, 333, 584, 611, 584, 611, 611, 611, 611, 556,
+    549, 611, 556, 611, 611, 611, 611, 944, 333, 611, 611, 611, 556, 834, 834, 333, 370,
+    365, 611, 611, 611, 556, 333, 333, 494, 889, 278, 278, 1000, 584, 584, 611, 611, 611,
+    474, 500, 500, 500, 278, 278, 278, 238, 389, 389, 549, 389, 389, 737, 333, 556, 556,
+    556, 556, 556, 556, 333, 556, 556, 278, 278, 556, 600, 333, 389, 333, 611, 556, 834,
+    333, 333, 1000, 556, 333, 611, 611, 611, 611, 611, 611, 611, 556, 611, 611, 556, 778,
+    556, 556, 556, 556, 556, 500, 500, 500, 500, 556,
+];
+
+static TIMES_ROMAN_WIDTHS: [u16; 315] = [
+    722, 889, 722, 722, 722, 722, 722, 722, 722, 722, 722, 667, 667, 667, 667, 667, 722,
+    722, 722, 612, 611, 611, 611, 611, 611, 611, 611, 611, 611, 722, 500, 556, 722, 722,
+    722, 722, 333, 333, 333, 333, 333, 333, 333, 333, 389, 722, 722, 611, 611, 611, 611,
+    611, 889, 722, 722, 722, 722, 722, 722, 889, 722, 722, 722, 722, 722, 722, 722, 722,
+    556, 722, 667, 667, 667, 667, 556, 556, 556, 556, 556, 611, 611, 611, 556, 722, 722,
+    722, 722, 722, 722, 722, 722, 722, 722, 944, 722, 722, 722, 722, 611, 611, 611, 611,
+    444, 444, 444, 444, 333, 444, 667, 444, 444, 778, 444, 444, 469, 541, 500, 921, 444,
+    500, 278, 200, 480, 480, 333, 333, 333, 200, 350, 444, 444, 333, 444, 444, 333, 500,
+    333, 278, 250, 250, 760, 500, 500, 500, 500, 588, 500, 400, 333, 564, 500, 333, 278,
+    444, 444, 444, 444, 444, 444, 444, 500, 1000, 444, 1000, 500, 444, 564, 500, 333,
+    333, 333, 556, 500, 556, 500, 500, 167, 500, 500, 500, 500, 333, 564, 549, 500, 500,
+    333, 333, 500, 333, 333, 278, 278, 278, 278, 278, 278, 278, 278, 500, 500, 278, 278,
+    344, 278, 564, 549, 564, 471, 278, 778, 333, 564, 500, 564, 500, 500, 500, 500, 500,
+    549, 500, 500, 500, 500, 500, 500, 722, 333, 500, 500, 500, 500, 750, 750, 300, 276,
+    310, 500, 500, 500, 453, 333, 333, 476, 833, 250, 250, 1000, 564, 564, 500, 444, 444,
+    408, 444, 444, 444, 333, 333, 333, 180, 333, 333, 453, 333, 333, 760, 333, 389, 389,
+    389, 389, 389, 500, 278, 500, 500, 278, 250, 500, 600, 278, 326, 278, 500, 500, 750,
+    300, 333, 980, 500, 300, 500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 722,
+    500, 500, 500, 500, 500, 444, 444, 444, 444, 500,
+];
+
+static TIMES_BOLD_WIDTHS: [u16; 315] = [
+    722, 1000, 722, 722, 722, 722, 722, 722, 722, 722, 722, 667, 722, 722, 722, 722, 722,
+    722, 722, 612, 667, 667, 667, 667, 667, 667, 667, 667, 667, 722, 500, 611, 778, 778,
+    778, 778, 389, 389, 389, 389, 389, 389, 389, 389, 500, 778, 778, 667, 667, 667, 667,
+    667, 944, 722, 722, 722, 722, 722, 778, 1000, 778, 778, 778, 778, 778, 778, 778, 778,
+    611, 778, 722, 722, 722, 722, 556, 556, 556, 556, 556, 667, 667, 667, 611, 722, 722,
+    722, 722, 722, 722, 722, 722, 722, 722, 1000, 722, 722, 722, 722, 667, 667, 667, 667,
+    500, 500, 500, 500, 333, 500, 722, 500, 500, 833, 500, 500, 581, 520, 500, 930, 500,
+    556, 278, 220, 394, 394, 333, 333, 333, 220, 350, 444, 444, 333, 444, 444, 333, 500,
+    333, 333, 250, 250, 747, 500, 556, 500, 500, 672, 556, 400, 333, 570, 500, 333, 278,
+    444, 444, 444, 444, 444, 444, 444, 500, 1000, 444, 1000, 500, 444, 570, 500, 333,
+    333, 333, 556, 500, 556, 500, 500, 167, 500, 500, 500, 556, 333, 570, 549, 500, 500,
+    333, 333, 556, 333, 333, 278, 278, 278, 278, 278, 278, 278, 333, 556, 556, 278, 278,
+    394, 278, 570, 549, 570, 494, 278, 833, 333, 570, 556, 570, 556, 556, 556, 556, 500,
+    549, 556, 500, 500, 500, 500, 500, 722, 333, 500, 500, 500, 500, 750, 750, 300, 300,
+    330, 500, 500, 556, 540, 333, 333, 494, 1000, 250, 250, 1000, 570, 570, 556, 500,
+    500, 555, 500, 500, 500, 333, 333, 333, 278, 444, 444, 549, 444, 444, 747, 333, 389,
+    389, 389, 389, 389, 500, 333, 500, 500, 278, 250, 500, 600, 333, 416, 333, 556, 500,
+    750, 300, 333, 1000, 500, 300, 556, 556, 556, 556, 556, 556, 556, 500, 556, 556, 500,
+    722, 500, 500, 500, 500, 500, 444, 444, 444, 444, 500,
+];
+
+static TIMES_ITALIC_WIDTHS: [u16; 315] = [
+    611, 889, 611, 611, 611, 611, 611, 611, 611, 611, 611, 611, 667, 667, 667, 667, 722,
+    722, 722, 612, 611, 611, 611, 611, 611, 611, 611, 611, 611, 722, 500, 611, 722, 722,
+    722, 722, 333, 333, 333, 333, 333, 333, 333, 333, 444, 667, 667, 556, 556, 611, 556,
+    556, 833, 667, 667, 667, 667, 667, 722, 944, 722, 722, 722, 722, 722, 722, 722, 722,
+    611, 722, 611, 611, 611, 611, 500, 500, 500, 500, 500, 556, 556, 556, 611, 722, 722,
+    722, 722, 722, 722, 722, 722, 722, 611, 833, 611, 556, 556, 556, 556, 556, 556, 556,
+    500, 500, 500, 500, 333, 500, 667, 500, 500, 778, 500, 500, 422, 541, 500, 920, 500,
+    500, 278, 275, 400, 400, 389, 389, 333, 275, 350, 444, 444, 333, 444, 444, 333, 500,
+    333, 333, 250, 250, 760, 500, 500, 500, 500, 544, 500, 400, 333, 675, 500, 333, 278,
+    444, 444, 444, 444, 444, 444, 444, 500, 889, 444, 889, 500, 444, 675, 500, 333, 389,
+    278, 500, 500, 500, 500, 500, 167, 500, 500, 500, 500, 333, 675, 549, 500, 500, 333,
+    333, 500, 333, 333, 278, 278, 278, 278, 278, 278, 278, 278, 444, 444, 278, 278, 300,
+    278, 675, 549, 675, 471, 278, 722, 333, 675, 500, 675, 500, 500, 500, 500, 500, 549,
+    500, 500, 500, 500, 500, 500, 667, 333, 500, 500, 500, 500, 750, 750, 300, 276, 310,
+    500, 500, 500, 523, 333, 333, 476, 833, 250, 250, 1000, 675, 675, 500, 500, 500, 420,
+    556, 556, 556, 333, 333, 333, 214, 389, 389, 453, 389, 389, 760, 333, 389, 389, 389,
+    389, 389, 500, 333, 500, 500, 278, 250, 500, 600, 278, 300, 278, 500, 500, 750, 300,
+    333, 980, 500, 300, 500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 444, 667, 444,
+    444, 444, 444, 500, 389, 389, 389, 389, 500,
+];
+
+static TIMES_BOLD_ITALIC_WIDTHS: [u16; 315] = [
+    667, 944, 667, 667, 667, 667, 667, 667, 667, 667, 667, 667, 667, 667, 667, 667, 722,
+    722, 722, 612, 667, 667, 667, 667, 667, 667, 667, 667, 667, 722, 500, 667, 722, 722,
+    722, 778, 389, 389, 389, 389, 389, 389, 389, 389, 500, 667, 667, 611, 611, 611, 611,
+    611, 889, 722, 722, 722, 722, 722, 722, 944, 722, 722, 722, 722, 722, 722, 722, 722,
+    611, 722, 667, 667, 667, 667, 556, 556, 556, 556, 556, 611, 611, 611, 611, 722, 722,
+    722, 722, 722, 722, 722, 722, 722, 667, 889, 667, 611, 611, 611, 611, 611, 611, 611,
+    500, 500, 500, 500, 333, 500, 722, 500, 500, 778, 500, 500, 570, 570, 500, 832, 500,
+    500, 278, 220, 348, 348, 333, 333, 333, 220, 350, 444, 444, 333, 444, 444, 333, 500,
+    333, 333, 250, 250, 747, 500, 500, 500, 500, 608, 500, 400, 333, 570, 500, 333, 278,
+    444, 444, 444, 444, 444, 444, 444, 500, 1000, 444, 1000, 500, 444, 570, 500, 389,
+    389, 333, 556, 500, 556, 500, 500, 167, 500, 500, 500, 500, 333, 570, 549, 500, 500,
+    333, 333, 556, 333, 333, 278, 278, 278, 278, 278, 278, 278, 278, 500, 500, 278, 278,
+    382, 278, 570, 549, 606, 494, 278, 778, 333, 606, 576, 570, 556, 556, 556, 556, 500,
+    549, 556, 500, 500, 500, 500, 500, 722, 333, 500, 500, 500, 500, 750, 750, 300, 266,
+    300, 500, 500, 500, 500, 333, 333, 494, 833, 250, 250, 1000, 570, 570, 500, 500, 500,
+    555, 500, 500, 500, 333, 333, 333, 278, 389, 389, 549, 389, 389, 747, 333, 389, 389,
+    389, 389, 389, 500, 333, 500, 500, 278, 250, 500, 600, 278, 366, 278, 500, 500, 750,
+    300, 333, 1000, 500, 300, 556, 556, 556, 556, 556, 556, 556, 500, 556, 556, 444, 667,
+    500, 444, 444, 444, 500, 389, 389, 389, 389, 500,
+];
+
+#[rustfmt::skip]
+static SYMBOL_GLYPHS: [&str; 190] = [
+    "Alpha", "Beta", "Chi", "Delta", "Epsilon", "Eta",
+    "Euro", "Gamma", "Ifraktur", "Iota", "Kappa", "Lambda",
+    "Mu", "Nu", "Omega", "Omicron", "Phi", "Pi",
+    "Psi", "Rfraktur", "Rho", "Sigma", "Tau", "Theta",
+    "Upsilon", "Upsilon1", "Xi", "Zeta", "aleph", "alpha",
+    "ampersand", "angle", "angleleft", "angleright", "apple", "approxequal",
+    "arrowboth", "arrowdblboth", "arrowdbldown", "arrowdblleft", "arrowdblright", "arrowdblup",
+    "arrowdown", "arrowhorizex", "arrowleft", "arrowright", "arrowup", "arrowvertex",
+    "asteriskmath", "bar", "beta", "braceex", "braceleft", "braceleftbt",
+    "braceleftmid", "bracelefttp", "braceright", "bracerightbt", "bracerightmid", "bracerighttp",
+    "bracketleft", "bracketleftbt", "bracketleftex", "bracketlefttp", "bracketright", "bracketrightbt",
+    "bracketrightex", "bracketrighttp", "bullet", "carriagereturn", "chi", "circlemultiply",
+    "circleplus", "club", "colon", "comma", "congruent", "copyrightsans",
+    "copyrightserif", "degree", "delta", "diamond", "divide", "dotmath",
+    "eight", "element", "ellipsis", "emptyset", "epsilon", "equal",
+    "equivalence", "eta", "exclam", "existential", "five", "florin",
+    "four", "fraction", "gamma", "gradient", "greater", "greaterequal",
+    "heart", "infinity", "integral", "integralbt", "integralex", "integraltp",
+    "intersection", "iota", "kappa", "lambda", "less", "lessequal",
+    "logicaland", "logicalnot", "logicalor", "lozenge", "minus", "minute",
+    "mu", "multiply", "nine", "notelement", "notequal", "notsubset",
+    "nu", "numbersign", "omega", "omega1", "omicron", "one",
+    "parenleft", "parenleftbt", "parenleftex", "parenlefttp", "parenright", "parenrightbt",
+    "parenrightex", "parenrighttp", "partialdiff", "percent", "period", "perpendicular",
+    "phi", "phi1", "pi", "plus", "plusminus", "product",
+    "propersubset", "propersuperset", "proportional", "psi", "question", "radical",
+    "radicalex", "reflexsubset", "reflexsuperset", "registersans", "registerserif", "rho",
+    "second", "semicolon", "seven", "sigma", "sigma1", "similar",
+    "six", "slash", "space", "spade", "suchthat", "summation",
+    "tau", "therefore", "theta", "theta1", "three", "trademarksans",
+    "trademarkserif", "two", "underscore", "union", "universal", "upsilon",
+    "weierstrass", "xi", "zero", "zeta",
+];
+
+static SYMBOL_WIDTHS: [u16; 190] = [
+    722, 667, 722, 612, 611, 722, 750, 603, 686, 333, 722, 686, 889, 722, 768, 722, 763,
+    768, 795, 795, 556, 592, 611, 741, 690, 620, 645, 611, 823, 631, 778, 768, 329, 329,
+    790, 549, 1042, 1042, 603, 987, 987, 603, 603, 1000, 987, 987, 603, 603, 500, 200,
+    549, 494, 480, 494, 494, 494, 480, 494, 494, 494, 333, 384, 384, 384, 333, 384, 384,
+    384, 460, 658, 549, 768, 768, 753, 278, 250, 549, 790, 790, 400, 494, 753, 549, 250,
+    500, 713, 1000, 823, 439, 549, 549, 603, 333, 549, 500, 500, 500, 167, 411, 713, 549,
+    549, 753, 713, 274, 686, 686, 686, 768, 329, 549, 549, 549, 549, 603, 713, 603, 494,
+    549, 247, 576, 549, 500, 713, 549, 713, 521, 500, 686, 713, 549, 500, 333, 384, 384,
+    384, 333, 384, 384, 384, 494, 833, 250, 658, 521, 603, 549, 549, 549, 823, 713, 713,
+    713, 686, 444, 549, 500, 713, 713, 790, 790, 549, 411, 278, 500, 603, 439, 549, 500,
+    278, 250, 753, 439, 713, 439, 863, 521, 631, 500, 786, 890, 500, 500, 768, 713, 576,
+    987, 493, 500, 494,
+];
+
+#[rustfmt::skip]
+static ZAPF_DINGBATS_GLYPHS: [&str; 202] = [
+    "a1", "a10", "a100", "a101", "a102", "a103",
+    "a104", "a105", "a106", "a107", "a108", "a109",
+    "a11", "a110", "a111", "a112", "a117", "a118",
+    "a119", "a12", "a120", "a121", "a122", "a123",
+    "a124", "a125", "a126", "a127", "a128", "a129",
+    "a13", "a130", "a131", "a132", "a133", "a134",
+    "a135", "a136", "a137", "a138", "a139", "a14",
+    "a140", "a141", "a142", "a143", "a144", "a145",
+    "a146", "a147", "a148", "a149", "a15", "a150",
+    "a151", "a152", "a153", "a154", "a155", "a156",
+    "a157", "a158", "a159", "a16", "a160", "a161",
+    "a162", "a163", "a164", "a165", "a166", "a167",
+    "a168", "a169", "a17", "a170", "a171", "a172",
+    "a173", "a174", "a175", "a176", "a177", "a178",
+    "a179", "a18", "a180", "a181", "a182", "a183",
+    "a184", "a185", "a186", "a187", "a188", "a189",
+    "a19", "a190", "a191", "a192", "a193", "a194",
+    "a195", "a196", "a197", "a198", "a199", "a2",
+    "a20", "a200", "a201", "a202", "a203", "a204",
+    "a205", "a206", "a21", "a22", "a23", "a24",
+    "a25", "a26", "a27", "a28", "a29", "a3",
+    "a30", "a31", "a32", "a33", "a34", "a35",
+    "a36", "a37", "a38", "a39", "a4", "a40",
+    "a41", "a42", "a43", "a44", "a45", "a46",
+    "a47", "a48", "a49", "a5", "a50", "a51",
+    "a52", "a53", "a54", "a55", "a56", "a57",
+    "a58", "a59", "a6", "a60", "a61", "a62",
+    "a63", "a64", "a65", "a66", "a67", "a68",
+    "a69", "a7", "a70", "a71", "a72", "a73",
+    "a74", "a75", "a76", "a77", "a78", "a79",
+    "a8", "a81", "a82", "a83", "a84", "a85",
+    "a86", "a87", "a88", "a89", "a9", "a90",
+    "a91", "a92", "a93", "a94", "a95", "a96",
+    "a97", "a98", "a99", "space",
+];
+
+static ZAPF_DINGBATS_WIDTHS: [u16; 202] = [
+    974, 692, 668, 732, 544, 544, 910, 911, 667, 760, 760, 626, 960, 694, 595, 776, 690,
+    791, 790, 939, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 549, 788, 788, 788,
+    788, 788, 788, 788, 788, 788, 788, 855, 788, 788, 788, 788, 788, 788, 788, 788, 788,
+    788, 911, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 933, 894, 838, 924, 1016,
+    458, 924, 918, 927, 928, 928, 945, 834, 873, 828, 924, 917, 930, 931, 463, 883, 836,
+    974, 867, 696, 874, 760, 946, 865, 967, 831, 873, 927, 755, 970, 918, 748, 836, 771,
+    888, 748, 771, 888, 867, 961, 846, 696, 874, 974, 762, 759, 509, 410, 762, 761, 571,
+    677, 763, 760, 759, 754, 786, 980, 788, 788, 790, 793, 794, 816, 823, 789, 841, 823,
+    719, 833, 816, 831, 923, 744, 723, 749, 790, 792, 695, 789, 776, 768, 792, 759, 707,
+    708, 682, 701, 826, 815, 494, 789, 789, 707, 687, 696, 689, 786, 787, 713, 791, 552,
+    785, 791, 873, 761, 762, 759, 892, 892, 788, 784, 537, 438, 138, 277, 415, 509, 410,
+    234, 234, 390, 577, 390, 276, 276, 317, 317, 334, 334, 392, 392, 668, 278,
+];
+
+#[rustfmt::skip]
+static HELVETICA_KERNING: [(&str, &str, i16); 214] = [
+    ("A", "C", -30), ("A", "G", -30), ("A", "O", -30), ("A", "Q", -30), ("A", "T", -120),
+    ("A", "U", -50), ("A", "V", -70), ("A", "W", -50), ("A", "Y", -100), ("A", "u", -30),
+    ("A", "v", -40), ("A", "w", -40), ("A", "y", -40), ("B", "U", -10),
+    ("B", "comma", -20), ("B", "period", -20), ("C", "comma", -30), ("C", "period", -30),
+    ("D", "A", -40), ("D", "V", -70), ("D", "W", -40), ("D", "Y", -90),
+    ("D", "comma", -70), ("D", "period", -70), ("F", "A", -80), ("F", "a", -50),
+    ("F", "comma", -150), ("F", "e", -30), ("F", "o", -30), ("F", "period", -150),
+    ("F", "r", -45), ("J", "A", -20), ("J", "a", -20), ("J", "comma", -30),
+    ("J", "period", -30), ("J", "u", -20), ("K", "O", -50), ("K", "e", -40),
+    ("K", "o", -40), ("K", "u", -30), ("K", "y", -50), ("L", "T", -110), ("L", "V", -110),
+    ("L", "W", -70), ("L", "Y", -140), ("L", "quotedblright", -140),
+    ("L", "quoteright", -160), ("L", "y", -30), ("O", "A", -20), ("O", "T", -40),
+    ("O", "V", -50), ("O", "W", -30), ("O", "X", -60), ("O", "Y", -70),
+    ("O", "comma", -40), ("O", "period", -40), ("P", "A", -120), ("P", "a", -40),
+    ("P", "comma", -180), ("P", "e", -50), ("P", "o", -50), ("P", "period", -180),
+    ("Q", "U", -10), ("R", "O", -20), ("R", "T", -30), ("R", "U", -40), ("R", "V", -50),
+    ("R", "W", -30), ("R", "Y", -50), ("S", "comma", -20), ("S", "period", -20),
+    ("T", "A", -120), ("T", "O", -40), ("T", "a", -120), ("T", "colon", -20),
+    ("T", "comma", -120), ("T", "e", -120), ("T", "hyphen", -140), ("T", "o", -120),
+    ("T", "period", -120), ("T", "r", -120), ("T", "semicolon", -20), ("T", "u", -120),
+    ("T", "w", -120), ("T", "y", -120), ("U", "A", -40), ("U", "comma", -40),
+    ("U", "period", -40), ("V", "A", -80), ("V", "G", -40), ("V", "O", -40),
+    ("V", "a", -70), ("V", "colon", -40), ("V", "comma", -125), ("V", "e", -80),
+    ("V", "hyphen", -80), ("V", "o", -80), ("V", "period", -125), ("V", "semicolon", -40),
+    ("V", "u", -70), ("W", "A", -50), ("W", "O", -20), ("W", "a", -40),
+    ("W", "comma", -80), ("W", "e", -30), ("W", "hyphen", -40), ("W", "o", -30),
+    ("W", "period", -80), ("W", "u", -30), ("W", "y", -20), ("Y", "A", -110),
+    ("Y", "O", -85), ("Y", "a", -140), ("Y", "colon", -60), ("Y", "comma", -140),
+    ("Y", "e", -140), ("Y", "hyphen", -140), ("Y", "i", -20), ("Y", "o", -140),
+    ("Y", "period", -140), ("Y", "semicolon", -60), ("Y", "u", -110), ("Y", "v", -110),
+    ("a", "v", -20), ("a", "w", -20), ("a", "y", -30), ("b", "b", -10),
+    ("b", "comma", -40), ("b", "l", -20), ("b", "period", -40), ("b", "u", -20),
+    ("b", "v", -20), ("b", "y", -20), ("c", "comma", -15), ("c", "k", -20),
+    ("colon", "space", -50), ("comma", "quotedblright", -100),
+    ("comma", "quoteright", -100), ("e", "comma", -15), ("e", "period", -15),
+    ("e", "v", -30), ("e", "w", -20), ("e", "x", -30), ("e", "y", -20), ("f", "a", -30),
+    ("f", "comma", -30), ("f", "dotlessi", -28), ("f", "e", -30), ("f", "o", -30),
+    ("f", "period", -30), ("f", "quotedblright", 60), ("f", "quoteright", 50),
+    ("g", "r", -10), ("h", "y", -30), ("k", "e", -20), ("k", "o", -20), ("m", "u", -10),
+    ("m", "y", -15), ("n", "u", -10), ("n", "v", -20), ("n", "y", -15),
+    ("o", "comma", -40), ("o", "period", -40), ("o", "v", -15), ("o", "w", -15),
+    ("o", "x", -30), ("o", "y", -30), ("p", "comma", -35), ("p", "period", -35),
+    ("p", "y", -30), ("period", "quotedblright", -100), ("period", "quoteright", -100),
+    ("period", "space", -60), ("quotedblright", "space", -40),
+    ("quoteleft", "quoteleft", -57), ("quoteright", "d", -50),
+    ("quoteright", "quoteright", -57), ("quoteright", "r", -50), ("quoteright", "s", -50),
+    ("quoteright", "space", -70), ("r", "a", -10), ("r", "colon", 30),
+    ("r", "comma", -50), ("r", "hyphen", -20), ("r", "period", -50),
+    ("r", "semicolon", 30), ("s", "comma", -15), ("s", "period", -15), ("s", "w", -30),
+    ("semicolon", "space", -50), ("space", "T", -50), ("space", "V", -50),
+    ("space", "W", -40), ("space", "Y", -90), ("space", "quotedblleft", -30),
+    ("space", "quoteleft", -60), ("v", "a", -25), ("v", "comma", -80), ("v", "e", -25),
+    ("v", "o", -25), ("v", "period", -80), ("w", "a", -15), ("w", "comma", -60),
+    ("w", "e", -10), ("w", "o", -10), ("w", "period", -60), ("x", "e", -30),
+    ("y", "a", -20), ("y", "comma", -100), ("y", "e", -20), ("y", "o", -20),
+    ("y", "period", -100), ("z", "e", -15), ("z", "o", -15),
+];
+
+#[rustfmt::skip]
+static HELVETICA_BOLD_KERNING: [(&str, &str, i16); 210] = [
+    ("A", "C", -40), ("A", "G", -50), ("A", "O", -40), ("A", "Q", -40), ("A", "T", -90),
+    ("A", "U", -50), ("A", "V", -80), ("A", "W", -60), ("A", "Y", -110), ("A", "u", -30),
+    ("A", "v", -40), ("A", "w", -30), ("A", "y", -30), ("B", "A", -30), ("B", "U", -10),
+    ("D", "A", -40), ("D", "V", -40), ("D", "W", -40), ("D", "Y", -70),
+    ("D", "comma", -30), ("D", "period", -30), ("F", "A", -80), ("F", "a", -20),
+    ("F", "comma", -100), ("F", "period", -100), ("J", "A", -20), ("J", "comma", -20),
+    ("J", "period", -20), ("J", "u", -20), ("K", "O", -30), ("K", "e", -15),
+    ("K", "o", -35), ("K", "u", -30), ("K", "y", -40), ("L", "T", -90), ("L", "V", -110),
+    ("L", "W", -80), ("L", "Y", -120), ("L", "quotedblright", -140),
+    ("L", "quoteright", -140), ("L", "y", -30), ("O", "A", -50), ("O", "T", -40),
+    ("O", "V", -50), ("O", "W", -50), ("O", "X", -50), ("O", "Y", -70),
+    ("O", "comma", -40), ("O", "period", -40), ("P", "A", -100), ("P", "a", -30),
+    ("P", "comma", -120), ("P", "e", -30), ("P", "o", -40), ("P", "period", -120),
+    ("Q", "U", -10), ("Q", "comma", 20), ("Q", "period", 20), ("R", "O", -20),
+    ("R", "T", -20), ("R", "U", -20), ("R", "V", -50), ("R", "W", -40), ("R", "Y", -50),
+    ("T", "A", -90), ("T", "O", -40), ("T", "a", -80), ("T", "colon", -40),
+    ("T", "comma", -80), ("T", "e", -60), ("T", "hyphen", -120), ("T", "o", -80),
+    ("T", "period", -80), ("T", "r", -80), ("T", "semicolon", -40), ("T", "u", -90),
+    ("T", "w", -60), ("T", "y", -60), ("U", "A", -50), ("U", "comma", -30),
+    ("U", "period", -30), ("V", "A", -80), ("V", "G", -50), ("V", "O", -50),
+    ("V", "a", -60), ("V", "colon", -40), ("V", "comma", -120), ("V", "e", -50),
+    ("V", "hyphen", -80), ("V", "o", -90), ("V", "period", -120), ("V", "semicolon", -40),
+    ("V", "u", -60), ("W", "A", -60), ("W", "O", -20), ("W", "a", -40),
+    ("W", "colon", -10), ("W", "comma", -80), ("W", "e", -35), ("W", "hyphen", -40),
+    ("W", "o", -60), ("W", "period", -80), ("W", "semicolon", -10), ("W", "u", -45),
+    ("W", "y", -20), ("Y", "A", -110), ("Y", "O", -70), ("Y", "a", -90),
+    ("Y", "colon", -50), ("Y", "comma", -100), ("Y", "e", -80), ("Y", "o", -100),
+    ("Y", "period", -100), ("Y", "semicolon", -50), ("Y", "u", -100), ("a", "g", -10),
+    ("a", "v", -15), ("a", "w", -15), ("a", "y", -20), ("b", "b", -10), ("b", "l", -10),
+    ("b", "u", -20), ("b", "v", -20), ("b", "y", -20), ("c", "h", -10), ("c", "k", -20),
+    ("c", "l", -20), ("c", "y", -10), ("colon", "space", -40),
+    ("comma", "quotedblright", -120), ("comma", "quoteright", -120),
+    ("comma", "space", -40), ("d", "d", -10), ("d", "v", -15), ("d", "w", -15),
+    ("d", "y", -15), ("e", "comma", 10), ("e", "period", 20), ("e", "v", -15),
+    ("e", "w", -15), ("e", "x", -15), ("e", "y", -15), ("f", "comma", -10),
+    ("f", "e", -10), ("f", "o", -20), ("f", "period", -10), ("f", "quotedblright", 30),
+    ("f", "quoteright", 30), ("g", "e", 10), ("g", "g", -10), ("h", "y", -20),
+    ("k", "o", -15), ("l", "w", -15), ("l", "y", -15), ("m", "u", -20), ("m", "y", -30),
+    ("n", "u", -10), ("n", "v", -40), ("n", "y", -20), ("o", "v", -20), ("o", "w", -15),
+    ("o", "x", -30), ("o", "y", -20), ("p", "y", -15), ("period", "quotedblright", -120),
+    ("period", "quoteright", -120), ("period", "space", -40),
+    ("quotedblright", "space", -80), ("quoteleft", "quoteleft", -46),
+    ("quoteright", "d", -80), ("quoteright", "l", -20), ("quoteright", "quoteright", -46),
+    ("quoteright", "r", -40), ("quoteright", "s", -60), ("quoteright", "space", -80),
+    ("quoteright", "v", -20), ("r", "c", -20), ("r", "comma", -60), ("r", "d", -20),
+    ("r", "g", -15), ("r", "hyphen", -20), ("r", "o", -20), ("r", "period", -60),
+    ("r", "q", -20), ("r", "s", -15), ("r", "t", 20), ("r", "v", 10), ("r", "y", 10),
+    ("s", "w", -15), ("semicolon", "space", -40), ("space", "T", -100),
+    ("space", "V", -80), ("space", "W", -80), ("space", "Y", -120),
+    ("space", "quotedblleft", -80), ("space", "quoteleft", -60), ("v", "a", -20),
+    ("v", "comma", -80), ("v", "o", -30), ("v", "period", -80), ("w", "comma", -40),
+    ("w", "o", -20), ("w", "period", -40), ("x", "e", -10), ("y", "a", -30),
+    ("y", "comma", -80), ("y", "e", -10), ("y", "o", -25), ("y", "period", -80),
+    ("z", "e", 10),
+];
+
+#[rustfmt::skip]
+static TIMES_ROMAN_KERNING: [(&str, &str, i16); 173] = [
+    ("A", "C", -40), ("A", "G", -40), ("A", "O", -55), ("A", "Q", -55), ("A", "T", -111),
+    ("A", "U", -55), ("A", "V", -135), ("A", "W", -90), ("A", "Y", -105),
+    ("A", "quoteright", -111), ("A", "v", -74), ("A", "w", -92), ("A", "y", -92),
+    ("B", "A", -35), ("B", "U", -10), ("D", "A", -40), ("D", "V", -40), ("D", "W", -30),
+    ("D", "Y", -55), ("F", "A", -74), ("F", "a", -15), ("F", "comma", -80),
+    ("F", "o", -15), ("F", "period", -80), ("J", "A", -60), ("K", "O", -30),
+    ("K", "e", -25), ("K", "o", -35), ("K", "u", -15), ("K", "y", -25), ("L", "T", -92),
+    ("L", "V", -100), ("L", "W", -74), ("L", "Y", -100), ("L", "quoteright", -92),
+    ("L", "y", -55), ("N", "A", -35), ("O", "A", -35), ("O", "T", -40), ("O", "V", -50),
+    ("O", "W", -35), ("O", "X", -40), ("O", "Y", -50), ("P", "A", -92), ("P", "a", -15),
+    ("P", "comma", -111), ("P", "period", -111), ("Q", "U", -10), ("R", "O", -40),
+    ("R", "T", -60), ("R", "U", -40), ("R", "V", -80), ("R", "W", -55), ("R", "Y", -65),
+    ("T", "A", -93), ("T", "O", -18), ("T", "a", -80), ("T", "colon", -50),
+    ("T", "comma", -74), ("T", "e", -70), ("T", "hyphen", -92), ("T", "i", -35),
+    ("T", "o", -80), ("T", "period", -74), ("T", "r", -35), ("T", "semicolon", -55),
+    ("T", "u", -45), ("T", "w", -80), ("T", "y", -80), ("U", "A", -40), ("V", "A", -135),
+    ("V", "G", -15), ("V", "O", -40), ("V", "a", -111), ("V", "colon", -74),
+    ("V", "comma", -129), ("V", "e", -111), ("V", "hyphen", -100), ("V", "i", -60),
+    ("V", "o", -129), ("V", "period", -129), ("V", "semicolon", -74), ("V", "u", -75),
+    ("W", "A", -120), ("W", "O", -10), ("W", "a", -80), ("W", "colon", -37),
+    ("W", "comma", -92), ("W", "e", -80), ("W", "hyphen", -65), ("W", "i", -40),
+    ("W", "o", -80), ("W", "period", -92), ("W", "semicolon", -37), ("W", "u", -50),
+    ("W", "y", -73), ("Y", "A", -120), ("Y", "O", -30), ("Y", "a", -100),
+    ("Y", "colon", -92), ("Y", "comma", -129), ("Y", "e", -100), ("Y", "hyphen", -111),
+    ("Y", "i", -55), ("Y", "o", -110), ("Y", "period", -129), ("Y", "semicolon", -92),
+    ("Y", "u", -111), ("a", "v", -20), ("a", "w", -15), ("b", "period", -40),
+    ("b", "u", -20), ("b", "v", -15), ("c", "y", -15), ("comma", "quotedblright", -70),
+    ("comma", "quoteright", -70), ("e", "g", -15), ("e", "v", -25), ("e", "w", -25),
+    ("e", "x", -15), ("e", "y", -15), ("f", "a", -10), ("f", "dotlessi", -50),
+    ("f", "f", -25), ("f", "i", -20), ("f", "quoteright", 55), ("g", "a", -5),
+    ("h", "y", -5), ("i", "v", -25), ("k", "e", -10), ("k", "o", -10), ("k", "y", -15),
+    ("l", "w", -10), ("n", "v", -40), ("n", "y", -15), ("o", "v", -15), ("o", "w", -25),
+    ("o", "y", -10), ("p", "y", -10), ("period", "quotedblright", -70),
+    ("period", "quoteright", -70), ("quotedblleft", "A", -80), ("quoteleft", "A", -80),
+    ("quoteleft", "quoteleft", -74), ("quoteright", "d", -50), ("quoteright", "l", -10),
+    ("quoteright", "quoteright", -74), ("quoteright", "r", -50), ("quoteright", "s", -55),
+    ("quoteright", "space", -74), ("quoteright", "t", -18), ("quoteright", "v", -50),
+    ("r", "comma", -40), ("r", "g", -18), ("r", "hyphen", -20), ("r", "period", -55),
+    ("space", "A", -55), ("space", "T", -18), ("space", "V", -50), ("space", "W", -30),
+    ("space", "Y", -90), ("v", "a", -25), ("v", "comma", -65), ("v", "e", -15),
+    ("v", "o", -20), ("v", "period", -65), ("w", "a", -10), ("w", "comma", -65),
+    ("w", "o", -10), ("w", "period", -65), ("x", "e", -15), ("y", "comma", -65),
+    ("y", "period", -65),
+];
+
+#[rustfmt::skip]
+static TIMES_BOLD_KERNING: [(&str, &str, i16); 187] = [
+    ("A", "C", -55), ("A", "G", -55), ("A", "O", -45), ("A", "Q", -45), ("A", "T", -95),
+    ("A", "U", -50), ("A", "V", -145), ("A", "W", -130), ("A", "Y", -100),
+    ("A", "p", -25), ("A", "quoteright", -74), ("A", "u", -50), ("A", "v", -100),
+    ("A", "w", -90), ("A", "y", -74), ("B", "A", -30), ("B", "U", -10), ("D", "A", -35),
+    ("D", "V", -40), ("D", "W", -40), ("D", "Y", -40), ("D", "period", -20),
+    ("F", "A", -90), ("F", "a", -25), ("F", "comma", -92), ("F", "e", -25),
+    ("F", "o", -25), ("F", "period", -110), ("J", "A", -30), ("J", "a", -15),
+    ("J", "e", -15), ("J", "o", -15), ("J", "period", -20), ("J", "u", -15),
+    ("K", "O", -30), ("K", "e", -25), ("K", "o", -25), ("K", "u", -15), ("K", "y", -45),
+    ("L", "T", -92), ("L", "V", -92), ("L", "W", -92), ("L", "Y", -92),
+    ("L", "quotedblright", -20), ("L", "quoteright", -110), ("L", "y", -55),
+    ("N", "A", -20), ("O", "A", -40), ("O", "T", -40), ("O", "V", -50), ("O", "W", -50),
+    ("O", "X", -40), ("O", "Y", -50), ("P", "A", -74), ("P", "a", -10),
+    ("P", "comma", -92), ("P", "e", -20), ("P", "o", -20), ("P", "period", -110),
+    ("Q", "U", -10), ("Q", "period", -20), ("R", "O", -30), ("R", "T", -40),
+    ("R", "U", -30), ("R", "V", -55), ("R", "W", -35), ("R", "Y", -35), ("T", "A", -90),
+    ("T", "O", -18), ("T", "a", -92), ("T", "colon", -74), ("T", "comma", -74),
+    ("T", "e", -92), ("T", "hyphen", -92), ("T", "i", -18), ("T", "o", -92),
+    ("T", "period", -90), ("T", "r", -74), ("T", "semicolon", -74), ("T", "u", -92),
+    ("T", "w", -74), ("T", "y", -34), ("U", "A", -60), ("U", "comma", -50),
+    ("U", "period", -50), ("V", "A", -135), ("V", "G", -30), ("V", "O", -45),
+    ("V", "a", -92), ("V", "colon", -92), ("V", "comma", -129), ("V", "e", -100),
+    ("V", "hyphen", -74), ("V", "i", -37), ("V", "o", -100), ("V", "period", -145),
+    ("V", "semicolon", -92), ("V", "u", -92), ("W", "A", -120), ("W", "O", -10),
+    ("W", "a", -65), ("W", "colon", -55), ("W", "comma", -92), ("W", "e", -65),
+    ("W", "hyphen", -37), ("W", "i", -18), ("W", "o", -75), ("W", "period", -92),
+    ("W", "semicolon", -55), ("W", "u", -50), ("W", "y", -60), ("Y", "A", -110),
+    ("Y", "O", -35), ("Y", "a", -85), ("Y", "colon", -92), ("Y", "comma", -92),
+    ("Y", "e", -111), ("Y", "hyphen", -92), ("Y", "i", -37), ("Y", "o", -111),
+    ("Y", "period", -92), ("Y", "semicolon", -92), ("Y", "u", -92), ("a", "v", -25),
+    ("b", "b", -10), ("b", "period", -40), ("b", "u", -20), ("b", "v", -15),
+    ("comma", "quotedblright", -45), ("comma", "quoteright", -55), ("d", "w", -15),
+    ("e", "v", -15), ("f", "comma", -15), ("f", "dotlessi", -35), ("f", "i", -25),
+    ("f", "o", -25), ("f", "period", -15), ("f", "quotedblright", 50),
+    ("f", "quoteright", 55), ("g", "period", -15), ("h", "y", -15), ("i", "v", -10),
+    ("k", "e", -10), ("k", "o", -15), ("k", "y", -15), ("n", "v", -40), ("o", "v", -10),
+    ("o", "w", -10), ("period", "quotedblright", -55), ("period", "quoteright", -55),
+    ("quotedblleft", "A", -10), ("quoteleft", "A", -10), ("quoteleft", "quoteleft", -63),
+    ("quoteright", "d", -20), ("quoteright", "quoteright", -63), ("quoteright", "r", -20),
+    ("quoteright", "s", -37), ("quoteright", "space", -74), ("quoteright", "v", -20),
+    ("r", "c", -18), ("r", "comma", -92), ("r", "e", -18), ("r", "g", -10),
+    ("r", "hyphen", -37), ("r", "n", -15), ("r", "o", -18), ("r", "p", -10),
+    ("r", "period", -100), ("r", "q", -18), ("r", "v", -10), ("space", "A", -55),
+    ("space", "T", -30), ("space", "V", -45), ("space", "W", -30), ("space", "Y", -55),
+    ("v", "a", -10), ("v", "comma", -55), ("v", "e", -10), ("v", "o", -10),
+    ("v", "period", -70), ("w", "comma", -55), ("w", "o", -10), ("w", "period", -70),
+    ("y", "comma", -55), ("y", "e", -10), ("y", "o", -25), ("y", "period", -70),
+];
+
+#[rustfmt::skip]
+static TIMES_ITALIC_KERNING: [(&str, &str, i16); 183] = [
+    ("A", "C", -30), ("A", "G", -35), ("A", "O", -40), ("A", "Q", -40), ("A", "T", -37),
+    ("A", "U", -50), ("A", "V", -105), ("A", "W", -95), ("A", "Y", -55),
+    ("A", "quoteright", -37), ("A", "u", -20), ("A", "v", -55), ("A", "w", -55),
+    ("A", "y", -55), ("B", "A", -25), ("B", "U", -10), ("D", "A", -35), ("D", "V", -40),
+    ("D", "W", -40), ("D", "Y", -40), ("F", "A", -115), ("F", "a", -75),
+    ("F", "comma", -135), ("F", "e", -75), ("F", "i", -45), ("F", "o", -105),
+    ("F", "period", -135), ("F", "r", -55), ("J", "A", -40), ("J", "a", -35),
+    ("J", "comma", -25), ("J", "e", -25), ("J", "o", -25), ("J", "period", -25),
+    ("J", "u", -35), ("K", "O", -50), ("K", "e", -35), ("K", "o", -40), ("K", "u", -40),
+    ("K", "y", -40), ("L", "T", -20), ("L", "V", -55), ("L", "W", -55), ("L", "Y", -20),
+    ("L", "quoteright", -37), ("L", "y", -30), ("N", "A", -27), ("O", "A", -55),
+    ("O", "T", -40), ("O", "V", -50), ("O", "W", -50), ("O", "X", -40), ("O", "Y", -50),
+    ("P", "A", -90), ("P", "a", -80), ("P", "comma", -135), ("P", "e", -80),
+    ("P", "o", -80), ("P", "period", -135), ("Q", "U", -10), ("R", "O", -40),
+    ("R", "U", -40), ("R", "V", -18), ("R", "W", -18), ("R", "Y", -18), ("T", "A", -50),
+    ("T", "O", -18), ("T", "a", -92), ("T", "colon", -55), ("T", "comma", -74),
+    ("T", "e", -92), ("T", "hyphen", -74), ("T", "i", -55), ("T", "o", -92),
+    ("T", "period", -74), ("T", "r", -55), ("T", "semicolon", -65), ("T", "u", -55),
+    ("T", "w", -74), ("T", "y", -74), ("U", "A", -40), ("U", "comma", -25),
+    ("U", "period", -25), ("V", "A", -60), ("V", "O", -30), ("V", "a", -111),
+    ("V", "colon", -65), ("V", "comma", -129), ("V", "e", -111), ("V", "hyphen", -55),
+    ("V", "i", -74), ("V", "o", -111), ("V", "period", -129), ("V", "semicolon", -74),
+    ("V", "u", -74), ("W", "A", -60), ("W", "O", -25), ("W", "a", -92),
+    ("W", "colon", -65), ("W", "comma", -92), ("W", "e", -92), ("W", "hyphen", -37),
+    ("W", "i", -55), ("W", "o", -92), ("W", "period", -92), ("W", "semicolon", -65),
+    ("W", "u", -55), ("W", "y", -70), ("Y", "A", -50), ("Y", "O", -15), ("Y", "a", -92),
+    ("Y", "colon", -65), ("Y", "comma", -92), ("Y", "e", -92), ("Y", "hyphen", -74),
+    ("Y", "i", -74), ("Y", "o", -92), ("Y", "period", -92), ("Y", "semicolon", -65),
+    ("Y", "u", -92), ("a", "g", -10), ("b", "period", -40), ("b", "u", -20),
+    ("c", "h", -15), ("c", "k", -20), ("comma", "quotedblright", -140),
+    ("comma", "quoteright", -140), ("e", "b", -10), ("e", "comma", -10), ("e", "g", -40),
+    ("e", "period", -15), ("e", "v", -15), ("e", "w", -15), ("e", "x", -20),
+    ("e", "y", -30), ("f", "comma", -10), ("f", "dotlessi", -60), ("f", "f", -18),
+    ("f", "i", -20), ("f", "period", -15), ("f", "quoteright", 92), ("g", "comma", -10),
+    ("g", "e", -10), ("g", "g", -10), ("g", "period", -15), ("k", "e", -10),
+    ("k", "o", -10), ("k", "y", -10), ("n", "v", -40), ("o", "g", -10), ("o", "v", -10),
+    ("period", "quotedblright", -140), ("period", "quoteright", -140),
+    ("quoteleft", "quoteleft", -111), ("quoteright", "d", -25),
+    ("quoteright", "quoteright", -111), ("quoteright", "r", -25),
+    ("quoteright", "s", -40), ("quoteright", "space", -111), ("quoteright", "t", -30),
+    ("quoteright", "v", -10), ("r", "a", -15), ("r", "c", -37), ("r", "comma", -111),
+    ("r", "d", -37), ("r", "e", -37), ("r", "g", -37), ("r", "hyphen", -20),
+    ("r", "o", -45), ("r", "period", -111), ("r", "q", -37), ("r", "s", -10),
+    ("space", "A", -18), ("space", "T", -18), ("space", "V", -35), ("space", "W", -40),
+    ("space", "Y", -75), ("v", "comma", -74), ("v", "period", -74), ("w", "comma", -74),
+    ("w", "period", -74), ("y", "comma", -55), ("y", "period", -55),
+];
+
+#[rustfmt::skip]
+static TIMES_BOLD_ITALIC_KERNING: [(&str, &str, i16); 170] = [
+    ("A", "C", -65), ("A", "G", -60), ("A", "O", -50), ("A", "Q", -55), ("A", "T", -55),
+    ("A", "U", -50), ("A", "V", -95), ("A", "W", -100), ("A", "Y", -70),
+    ("A", "quoteright", -74), ("A", "u", -30), ("A", "v", -74), ("A", "w", -74),
+    ("A", "y", -74), ("B", "A", -25), ("B", "U", -10), ("D", "A", -25), ("D", "V", -50),
+    ("D", "W", -40), ("D", "Y", -50), ("F", "A", -100), ("F", "a", -95),
+    ("F", "comma", -129), ("F", "e", -100), ("F", "i", -40), ("F", "o", -70),
+    ("F", "period", -129), ("F", "r", -50), ("J", "A", -25), ("J", "a", -40),
+    ("J", "comma", -10), ("J", "e", -40), ("J", "o", -40), ("J", "period", -10),
+    ("J", "u", -40), ("K", "O", -30), ("K", "e", -25), ("K", "o", -25), ("K", "u", -20),
+    ("K", "y", -20), ("L", "T", -18), ("L", "V", -37), ("L", "W", -37), ("L", "Y", -37),
+    ("L", "quoteright", -55), ("L", "y", -37), ("N", "A", -30), ("O", "A", -40),
+    ("O", "T", -40), ("O", "V", -50), ("O", "W", -50), ("O", "X", -40), ("O", "Y", -50),
+    ("P", "A", -85), ("P", "a", -40), ("P", "comma", -129), ("P", "e", -50),
+    ("P", "o", -55), ("P", "period", -129), ("Q", "U", -10), ("R", "O", -40),
+    ("R", "T", -30), ("R", "U", -40), ("R", "V", -18), ("R", "W", -18), ("R", "Y", -18),
+    ("T", "A", -55), ("T", "O", -18), ("T", "a", -92), ("T", "colon", -74),
+    ("T", "comma", -92), ("T", "e", -92), ("T", "hyphen", -92), ("T", "i", -37),
+    ("T", "o", -95), ("T", "period", -92), ("T", "r", -37), ("T", "semicolon", -74),
+    ("T", "u", -37), ("T", "w", -37), ("T", "y", -37), ("U", "A", -45), ("V", "A", -85),
+    ("V", "G", -10), ("V", "O", -30), ("V", "a", -111), ("V", "colon", -74),
+    ("V", "comma", -129), ("V", "e", -111), ("V", "hyphen", -70), ("V", "i", -55),
+    ("V", "o", -111), ("V", "period", -129), ("V", "semicolon", -74), ("V", "u", -55),
+    ("W", "A", -74), ("W", "O", -15), ("W", "a", -85), ("W", "colon", -55),
+    ("W", "comma", -74), ("W", "e", -90), ("W", "hyphen", -50), ("W", "i", -37),
+    ("W", "o", -80), ("W", "period", -74), ("W", "semicolon", -55), ("W", "u", -55),
+    ("W", "y", -55), ("Y", "A", -74), ("Y", "O", -25), ("Y", "a", -92),
+    ("Y", "colon", -92), ("Y", "comma", -92), ("Y", "e", -111), ("Y", "hyphen", -92),
+    ("Y", "i", -55), ("Y", "o", -111), ("Y", "period", -74), ("Y", "semicolon", -92),
+    ("Y", "u", -92), ("b", "b", -10), ("b", "period", -40), ("b", "u", -20),
+    ("c", "h", -10), ("c", "k", -10), ("comma", "quotedblright", -95),
+    ("comma", "quoteright", -95), ("e", "b", -10), ("f", "comma", -10),
+    ("f", "dotlessi", -30), ("f", "e", -10), ("f", "f", -18), ("f", "o", -10),
+    ("f", "period", -10), ("f", "quoteright", 55), ("k", "e", -30), ("k", "o", -10),
+    ("n", "v", -40), ("o", "v", -15), ("o", "w", -25), ("o", "x", -10), ("o", "y", -10),
+    ("period", "quotedblright", -95), ("period", "quoteright", -95),
+    ("quoteleft", "quoteleft", -74), ("quoteright", "d", -15),
+    ("quoteright", "quoteright", -74), ("quoteright", "r", -15), ("quoteright", "s", -74),
+    ("quoteright", "space", -74), ("quoteright", "t", -37), ("quoteright", "v", -15),
+    ("r", "comma", -65), ("r", "period", -65), ("space", "A", -37), ("space", "V", -70),
+    ("space", "W", -70), ("space", "Y", -70), ("v", "comma", -37), ("v", "e", -15),
+    ("v", "o", -15), ("v", "period", -37), ("w", "a", -10), ("w", "comma", -37),
+    ("w", "e", -10), ("w", "o", -15), ("w", "period", -37), ("x", "e", -10),
+    ("y", "comma", -37), ("y", "period", -37),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_font_metrics() {
+        assert_eq!(StandardFont::Helvetica.glyph_width("W"), Some(944.0));
+        assert_eq!(StandardFont::Helvetica.glyph_width("iacute"), Some(278.0));
+        assert_eq!(StandardFont::HelveticaBoldOblique.glyph_width("g"), Some(611.0));
+        assert_eq!(StandardFont::TimesItalic.glyph_width("A"), Some(611.0));
+        assert_eq!(StandardFont::CourierBold.glyph_width("germandbls"), Some(600.0));
+        assert_eq!(StandardFont::TimesRoman.glyph_width("Lcaron"), Some(611.0));
+        assert_eq!(StandardFont::Symbol.glyph_width("alpha"), Some(631.0));
+        assert_eq!(StandardFont::ZapfDingbats.glyph_width("a191"), Some(918.0));
+        assert_eq!(StandardFont::TimesRoman.glyph_width("alpha"), None);
+
+        let hello = ["H", "e", "l", "l", "o"];
+        assert_eq!(StandardFont::Helvetica.measure_glyphs(hello, 10.0), 22.78);
+
+        let helvetica = StandardFont::Helvetica;
+        assert_eq!(helvetica.char_width(0xE9, SimpleEncoding::WinAnsi), Some(556.0));
+        assert_eq!(helvetica.char_width(0x80, SimpleEncoding::WinAnsi), Some(556.0));
+        assert_eq!(helvetica.char_width(0xE4, SimpleEncoding::Standard), None);
+        assert_eq!(helvetica.measure(b"Hello", SimpleEncoding::WinAnsi, 10.0), 22.78);
+
+        for font in StandardFont::ALL {
+            assert_eq!(StandardFont::from_name(font.to_name()), Some(font));
+            let encoding = font.built_in_encoding();
+            assert!((32..=126).all(|c| font.char_width(c, encoding).is_some()));
+        }
+
+        assert_eq!(StandardFont::TimesBoldItalic.flags().bits(), 0b110_0010);
+    }
+
+    #[test]
+    fn test_standard_font_kerning() {
+        assert_eq!(StandardFont::Helvetica.kerning("A", "V"), -70.0);
+        assert_eq!(StandardFont::HelveticaOblique.kerning("T", "period"), -120.0);
+        assert_eq!(StandardFont::TimesRoman.kerning("quoteright", "s"), -55.0);
+        assert_eq!(StandardFont::TimesBold.kerning("f", "quoteright"), 55.0);
+        assert_eq!(StandardFont::TimesItalic.kerning("A", "V"), -105.0);
+        assert_eq!(StandardFont::HelveticaBold.kerning("L", "Y"), -120.0);
+        assert_eq!(StandardFont::Helvetica.kerning("Lcaron", "T"), 0.0);
+        assert_eq!(StandardFont::Helvetica.kerning("V", "A"), -80.0);
+        assert_eq!(StandardFont::Helvetica.kerning("H", "e"), 0.0);
+        assert_eq!(StandardFont::Courier.kerning("A", "V"), 0.0);
+        assert_eq!(StandardFont::Symbol.kerning("alpha", "beta"), 0.0);
+    }
+
+    #[test]
+    fn test_standard_font_writers() {
+        test_obj!(
+            |obj| obj.start::<FontDescriptor>().standard_metrics(StandardFont::Symbol),
+            b"<<",
+            b"  /Type /FontDescriptor",
+            b"  /FontName /Symbol",
+            b"  /Flags 4",
+            b"  /FontBBox [-180 -293 1090 1010]",
+            b"  /ItalicAngle 0",
+            b"  /Ascent 1010",
+            b"  /Descent -293",
+            b"  /StemV 85",
+            b"  /StemH 92",
+            b">>",
+        );
+
+        let mut w = Chunk::new();
+        w.type1_font(Ref::new(1))
+            .standard_widths(StandardFont::Helvetica, SimpleEncoding::WinAnsi);
+        let text = std::str::from_utf8(w.as_bytes()).unwrap();
+        assert!(text.contains("/FirstChar 32\n  /LastChar 255\n  /Widths [278 278 355"));
+    }
+}
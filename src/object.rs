@@ -502,6 +502,207 @@ impl Sealed for Rect {
 
 impl Primitive for Rect {}
 
+/// An affine transformation matrix.
+///
+/// The matrix maps a point (x, y) to (sx·x + kx·y + tx, ky·x + sy·y + ty). It
+/// is written as the array `[sx ky kx sy tx ty]`, which is the same as the
+/// `[a b c d e f]` notation in the PDF specification.
+///
+/// All methods that take a matrix accept both a `Transform` and a bare
+/// `[f32; 6]` array.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Transform {
+    /// The horizontal scaling factor.
+    pub sx: f32,
+    /// The vertical skewing factor.
+    pub ky: f32,
+    /// The horizontal skewing factor.
+    pub kx: f32,
+    /// The vertical scaling factor.
+    pub sy: f32,
+    /// The horizontal translation.
+    pub tx: f32,
+    /// The vertical translation.
+    pub ty: f32,
+}
+
+impl Transform {
+    /// Create a new transform from its six components.
+    #[inline]
+    pub fn new(sx: f32, ky: f32, kx: f32, sy: f32, tx: f32, ty: f32) -> Self {
+        Self { sx, ky, kx, sy, tx, ty }
+    }
+
+    /// The identity transform.
+    #[inline]
+    pub fn identity() -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0)
+    }
+
+    /// A translation by `tx` and `ty`.
+    #[inline]
+    pub fn translate(tx: f32, ty: f32) -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, tx, ty)
+    }
+
+    /// A scaling by `sx` horizontally and `sy` vertically.
+    #[inline]
+    pub fn scale(sx: f32, sy: f32) -> Self {
+        Self::new(sx, 0.0, 0.0, sy, 0.0, 0.0)
+    }
+
+    /// A counter-clockwise rotation by `degrees` around the origin.
+    #[inline]
+    pub fn rotate(degrees: f32) -> Self {
+        Self::rotate_radians(degrees.to_radians())
+    }
+
+    /// A counter-clockwise rotation by `radians` around the origin.
+    #[inline]
+    pub fn rotate_radians(radians: f32) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self::new(cos, sin, -sin, cos, 0.0, 0.0)
+    }
+
+    /// A skew that rotates the x-axis by `x_degrees` counter-clockwise and
+    /// the y-axis by `y_degrees` clockwise.
+    #[inline]
+    pub fn skew(x_degrees: f32, y_degrees: f32) -> Self {
+        let ky = x_degrees.to_radians().tan();
+        let kx = y_degrees.to_radians().tan();
+        Self::new(1.0, ky, kx, 1.0, 0.0, 0.0)
+    }
+
+    /// Whether this is the identity transform.
+    #[inline]
+    pub fn is_identity(self) -> bool {
+        self == Self::identity()
+    }
+
+    /// The transform that first applies `self` and then `next`.
+    #[inline]
+    pub fn then(self, next: Self) -> Self {
+        Self {
+            sx: self.sx * next.sx + self.ky * next.kx,
+            ky: self.sx * next.ky + self.ky * next.sy,
+            kx: self.kx * next.sx + self.sy * next.kx,
+            sy: self.kx * next.ky + self.sy * next.sy,
+            tx: self.tx * next.sx + self.ty * next.kx + next.tx,
+            ty: self.tx * next.ky + self.ty * next.sy + next.ty,
+        }
+    }
+
+    /// The transform that first applies `prev` and then `self`.
+    ///
+    /// This is how the [`cm`](crate::Content::transform) operator modifies the
+    /// current transformation matrix.
+    #[inline]
+    pub fn pre_concat(self, prev: Self) -> Self {
+        prev.then(self)
+    }
+
+    /// The inverse transform, if there is one.
+    pub fn invert(self) -> Option<Self> {
+        let det = self.sx * self.sy - self.ky * self.kx;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        Some(Self {
+            sx: self.sy / det,
+            ky: -self.ky / det,
+            kx: -self.kx / det,
+            sy: self.sx / det,
+            tx: (self.kx * self.ty - self.sy * self.tx) / det,
+            ty: (self.ky * self.tx - self.sx * self.ty) / det,
+        })
+    }
+
+    /// Map a point through the transform.
+    #[inline]
+    pub fn map_point(self, x: f32, y: f32) -> (f32, f32) {
+        (self.sx * x + self.kx * y + self.tx, self.ky * x + self.sy * y + self.ty)
+    }
+
+    /// Map the four corners of a rectangle through the transform.
+    ///
+    /// The corners are in the same order as in [`Rect::to_quad_points`].
+    pub fn map_quad(self, rect: Rect) -> [f32; 8] {
+        let [x1, y1, x2, y2, x3, y3, x4, y4] = rect.to_quad_points();
+        let (x1, y1) = self.map_point(x1, y1);
+        let (x2, y2) = self.map_point(x2, y2);
+        let (x3, y3) = self.map_point(x3, y3);
+        let (x4, y4) = self.map_point(x4, y4);
+        [x1, y1, x2, y2, x3, y3, x4, y4]
+    }
+
+    /// The axis-aligned bounding box of a rectangle mapped through the
+    /// transform.
+    ///
+    /// This is useful, for example, to determine the area that a
+    /// [form XObject](crate::writers::FormXObject) with a
+    /// [matrix](crate::writers::FormXObject::matrix) covers, or to compute
+    /// an annotation's rectangle.
+    pub fn map_bbox(self, rect: Rect) -> Rect {
+        let quad = self.map_quad(rect);
+        let mut bbox = Rect::new(quad[0], quad[1], quad[0], quad[1]);
+        for point in quad.chunks_exact(2) {
+            bbox.x1 = bbox.x1.min(point[0]);
+            bbox.y1 = bbox.y1.min(point[1]);
+            bbox.x2 = bbox.x2.max(point[0]);
+            bbox.y2 = bbox.y2.max(point[1]);
+        }
+        bbox
+    }
+
+    /// Convert the transform into its six components.
+    #[inline]
+    pub fn to_array(self) -> [f32; 6] {
+        [self.sx, self.ky, self.kx, self.sy, self.tx, self.ty]
+    }
+}
+
+impl Default for Transform {
+    #[inline]
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl From<[f32; 6]> for Transform {
+    #[inline]
+    fn from([sx, ky, kx, sy, tx, ty]: [f32; 6]) -> Self {
+        Self::new(sx, ky, kx, sy, tx, ty)
+    }
+}
+
+impl From<Transform> for [f32; 6] {
+    #[inline]
+    fn from(transform: Transform) -> Self {
+        transform.to_array()
+    }
+}
+
+impl Sealed for Transform {
+    const STARTS_WITH_DELIMITER: bool = true;
+
+    #[inline]
+    fn write(self, buf: &mut Buf) {
+        buf.push(b'[');
+        for (i, value) in self.to_array().into_iter().enumerate() {
+            if i > 0 {
+                buf.push(b' ');
+            }
+            buf.push_val(value);
+        }
+        buf.push(b']');
+
+        buf.limits.register_array_len(6);
+    }
+}
+
+impl Primitive for Transform {}
+
 /// A date, written as a text string.
 ///
 /// A field is only respected if all superior fields are supplied. For example,
@@ -1651,6 +1852,34 @@ mod tests {
         test_primitive!(Name(b"\n"), br"/#0A");
     }
 
+    #[test]
+    fn test_transforms() {
+        let t = Transform::translate(10.0, 20.0);
+        let s = Transform::scale(2.0, 3.0);
+        assert_eq!(t.then(s), Transform::new(2.0, 0.0, 0.0, 3.0, 20.0, 60.0));
+        assert_eq!(t.pre_concat(s), Transform::new(2.0, 0.0, 0.0, 3.0, 10.0, 20.0));
+        assert_eq!(t.then(s).map_point(1.0, 1.0), (22.0, 63.0));
+        assert_eq!(Transform::rotate(90.0).map_point(1.0, 0.0).1, 1.0);
+
+        let m = Transform::new(2.0, 1.0, -1.0, 3.0, 5.0, -7.0);
+        let (x, y) = m.map_point(4.0, 6.0);
+        let (x, y) = m.invert().unwrap().map_point(x, y);
+        assert!((x - 4.0).abs() < 1e-5 && (y - 6.0).abs() < 1e-5);
+
+        let m = Transform::scale(2.0, 4.0).then(Transform::translate(6.0, -8.0));
+        assert_eq!(m.invert(), Some(Transform::new(0.5, 0.0, 0.0, 0.25, -3.0, 2.0)));
+        assert!(m.then(m.invert().unwrap()).is_identity());
+        assert_eq!(Transform::scale(0.0, 1.0).invert(), None);
+
+        let rect = Rect::new(0.0, 0.0, 2.0, 1.0);
+        assert_eq!(
+            Transform::rotate(90.0).then(t).map_bbox(rect),
+            Rect::new(9.0, 20.0, 10.0, 22.0)
+        );
+
+        test_primitive!(t.then(s), b"[2 0 0 3 20 60]");
+    }
+
     #[test]
     fn test_dates() {
         test_primitive!(Date::new(2021), b"(D:2021)");
@@ -0,0 +1,1350 @@
+use std::fmt::{self, Display, Formatter};
+
+use super::*;
+use crate::types::{LineCapStyle, LineJoinStyle, RenderingIntent, TextRenderingMode};
+
+/// A typed content stream operation.
+///
+/// There is one variant for each operator that [`Content`] can write. Each
+/// variant holds the operands that the corresponding [`Content`] method takes.
+/// Operations that cannot be represented by a typed variant (e.g. because the
+/// operator is unknown to this crate or the operands don't fit) are preserved
+/// as [`Op::Other`], so that parsing and writing a content stream never loses
+/// information.
+///
+/// Operations are created by a [`ContentParser`] and can be written into a
+/// content stream with [`Op::write`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Op {
+    /// `w`: Set the stroke line width.
+    SetLineWidth(f32),
+    /// `J`: Set the line cap style.
+    SetLineCap(LineCapStyle),
+    /// `j`: Set the line join style.
+    SetLineJoin(LineJoinStyle),
+    /// `M`: Set the miter limit.
+    SetMiterLimit(f32),
+    /// `d`: Set the line dash pattern (array and phase).
+    SetDashPattern(Vec<f32>, f32),
+    /// `ri`: Set the color rendering intent.
+    SetRenderingIntent(RenderingIntent),
+    /// `i`: Set the flatness tolerance.
+    SetFlatness(i32),
+    /// `gs`: Set the parameters from a named `ExtGState` dictionary.
+    SetParameters(Vec<u8>),
+    /// `q`: Save the graphics state.
+    SaveState,
+    /// `Q`: Restore the graphics state.
+    RestoreState,
+    /// `cm`: Pre-concatenate a matrix with the current transformation matrix.
+    Transform([f32; 6]),
+    /// `m`: Begin a new subpath.
+    MoveTo(f32, f32),
+    /// `l`: Append a straight line.
+    LineTo(f32, f32),
+    /// `c`: Append a cubic Bézier segment.
+    CubicTo(f32, f32, f32, f32, f32, f32),
+    /// `v`: Append a cubic Bézier segment with the current point as the first
+    /// control point.
+    CubicToInitial(f32, f32, f32, f32),
+    /// `y`: Append a cubic Bézier segment with the end point as the second
+    /// control point.
+    CubicToFinal(f32, f32, f32, f32),
+    /// `h`: Close the current subpath.
+    ClosePath,
+    /// `re`: Append a rectangle (x, y, width, height).
+    Rect(f32, f32, f32, f32),
+    /// `S`: Stroke the current path.
+    Stroke,
+    /// `s`: Close and stroke the current path.
+    CloseAndStroke,
+    /// `f`: Fill the current path using the nonzero winding number rule.
+    FillNonzero,
+    /// `f*`: Fill the current path using the even-odd rule.
+    FillEvenOdd,
+    /// `B`: Fill using the nonzero winding number rule and stroke.
+    FillNonzeroAndStroke,
+    /// `B*`: Fill using the even-odd rule and stroke.
+    FillEvenOddAndStroke,
+    /// `b`: Close, fill using the nonzero winding number rule and stroke.
+    CloseFillNonzeroAndStroke,
+    /// `b*`: Close, fill using the even-odd rule and stroke.
+    CloseFillEvenOddAndStroke,
+    /// `n`: End the current path without filling or stroking it.
+    EndPath,
+    /// `W`: Clip using the nonzero winding number rule.
+    ClipNonzero,
+    /// `W*`: Clip using the even-odd rule.
+    ClipEvenOdd,
+    /// `BT`: Begin a text object.
+    BeginText,
+    /// `ET`: End a text object.
+    EndText,
+    /// `Tc`: Set the character spacing.
+    SetCharSpacing(f32),
+    /// `Tw`: Set the word spacing.
+    SetWordSpacing(f32),
+    /// `Tz`: Set the horizontal scaling.
+    SetHorizontalScaling(f32),
+    /// `TL`: Set the leading.
+    SetLeading(f32),
+    /// `Tf`: Set font and font size.
+    SetFont(Vec<u8>, f32),
+    /// `Tr`: Set the text rendering mode.
+    SetTextRenderingMode(TextRenderingMode),
+    /// `Ts`: Set the rise.
+    SetRise(f32),
+    /// `Td`: Move to the start of the next line.
+    NextLine(f32, f32),
+    /// `TD`: Move to the start of the next line and set the leading.
+    NextLineAndSetLeading(f32, f32),
+    /// `Tm`: Set the text matrix.
+    SetTextMatrix([f32; 6]),
+    /// `T*`: Move to the start of the next line using the leading.
+    NextLineUsingLeading,
+    /// `Tj`: Show text.
+    Show(Vec<u8>),
+    /// `'`: Move to the next line and show text.
+    NextLineShow(Vec<u8>),
+    /// `"`: Move to the next line, show text and set word and character
+    /// spacing (in this order).
+    NextLineShowAndSetWordAndCharSpacing(f32, f32, Vec<u8>),
+    /// `TJ`: Show text with individual glyph positioning.
+    ShowPositioned(Vec<PositionedItem>),
+    /// `d0`: Start a Type 3 glyph with color information.
+    StartColorGlyph(f32),
+    /// `d1`: Start a Type 3 glyph with only shape information (width and
+    /// bounding box).
+    StartShapeGlyph(f32, f32, f32, f32, f32),
+    /// `CS`: Set the stroke color space.
+    SetStrokeColorSpace(Vec<u8>),
+    /// `cs`: Set the fill color space.
+    SetFillColorSpace(Vec<u8>),
+    /// `SCN`: Set the stroke color.
+    SetStrokeColor(Vec<f32>),
+    /// `SCN`: Set the stroke pattern with an optional tint.
+    SetStrokePattern(Vec<f32>, Vec<u8>),
+    /// `scn`: Set the fill color.
+    SetFillColor(Vec<f32>),
+    /// `scn`: Set the fill pattern with an optional tint.
+    SetFillPattern(Vec<f32>, Vec<u8>),
+    /// `G`: Set the stroke color in `DeviceGray`.
+    SetStrokeGray(f32),
+    /// `g`: Set the fill color in `DeviceGray`.
+    SetFillGray(f32),
+    /// `RG`: Set the stroke color in `DeviceRGB`.
+    SetStrokeRgb(f32, f32, f32),
+    /// `rg`: Set the fill color in `DeviceRGB`.
+    SetFillRgb(f32, f32, f32),
+    /// `K`: Set the stroke color in `DeviceCMYK`.
+    SetStrokeCmyk(f32, f32, f32, f32),
+    /// `k`: Set the fill color in `DeviceCMYK`.
+    SetFillCmyk(f32, f32, f32, f32),
+    /// `sh`: Paint a named shading.
+    Shading(Vec<u8>),
+    /// `Do`: Paint a named external object.
+    XObject(Vec<u8>),
+    /// `MP`: A marked-content point.
+    MarkedContentPoint(Vec<u8>),
+    /// `DP`: A marked-content point with properties.
+    MarkedContentPointWithProperties(Vec<u8>, PropertiesOperand),
+    /// `BMC`: Begin a marked-content sequence.
+    BeginMarkedContent(Vec<u8>),
+    /// `BDC`: Begin a marked-content sequence with properties.
+    BeginMarkedContentWithProperties(Vec<u8>, PropertiesOperand),
+    /// `EMC`: End a marked-content sequence.
+    EndMarkedContent,
+    /// `BX`: Begin a compatibility section.
+    BeginCompat,
+    /// `EX`: End a compatibility section.
+    EndCompat,
+    /// Any other operation.
+    Other {
+        /// The operator.
+        operator: String,
+        /// The operands in the order in which they precede the operator.
+        operands: Vec<Operand>,
+    },
+}
+
+/// An item of a [`TJ`](Op::ShowPositioned) operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PositionedItem {
+    /// A string to show.
+    Show(Vec<u8>),
+    /// An adjustment in thousands of units of text space.
+    Adjust(f32),
+}
+
+/// The property list of a [`BDC`](Op::BeginMarkedContentWithProperties) or
+/// [`DP`](Op::MarkedContentPointWithProperties) operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertiesOperand {
+    /// An inline property list dictionary.
+    Inline(Vec<(Vec<u8>, Operand)>),
+    /// The name of a property list in the resource dictionary.
+    Named(Vec<u8>),
+}
+
+/// An operand of a content stream operation.
+///
+/// Names and strings hold their decoded bytes, i.e. without escape sequences.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    /// The null object.
+    Null,
+    /// A boolean.
+    Bool(bool),
+    /// An integer number.
+    Integer(i32),
+    /// A real number.
+    Real(f32),
+    /// A name.
+    Name(Vec<u8>),
+    /// A string.
+    Str(Vec<u8>),
+    /// An array.
+    Array(Vec<Operand>),
+    /// A dictionary.
+    Dict(Vec<(Vec<u8>, Operand)>),
+}
+
+impl Operand {
+    /// The value of the operand if it is a number.
+    pub fn as_f32(&self) -> Option<f32> {
+        match *self {
+            Self::Integer(v) => Some(v as f32),
+            Self::Real(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// The value of the operand if it is an integer.
+    pub fn as_i32(&self) -> Option<i32> {
+        match *self {
+            Self::Integer(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// The bytes of the operand if it is a name.
+    pub fn as_name(&self) -> Option<&[u8]> {
+        match self {
+            Self::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The bytes of the operand if it is a string.
+    pub fn as_str(&self) -> Option<&[u8]> {
+        match self {
+            Self::Str(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Write the operand into an object.
+    pub fn write(&self, obj: Obj) {
+        match self {
+            Self::Null => obj.primitive(Null),
+            Self::Bool(v) => obj.primitive(*v),
+            Self::Integer(v) => obj.primitive(*v),
+            Self::Real(v) => obj.primitive(*v),
+            Self::Name(name) => obj.primitive(Name(name)),
+            Self::Str(string) => obj.primitive(Str(string)),
+            Self::Array(items) => {
+                let mut array = obj.array();
+                for item in items {
+                    item.write(array.push());
+                }
+            }
+            Self::Dict(pairs) => write_pairs(&mut obj.dict(), pairs),
+        }
+    }
+}
+
+fn write_pairs(dict: &mut Dict, pairs: &[(Vec<u8>, Operand)]) {
+    for (key, value) in pairs {
+        value.write(dict.insert(Name(key)));
+    }
+}
+
+impl Op {
+    /// Create a typed operation from an operator and its operands.
+    ///
+    /// Falls back to [`Op::Other`] if the operator is unknown or the operands
+    /// don't match what the corresponding [`Content`] method would write.
+    pub fn from_parts(operator: &str, operands: Vec<Operand>) -> Self {
+        Self::typed(operator, &operands)
+            .unwrap_or_else(|| Self::Other { operator: operator.into(), operands })
+    }
+
+    fn typed(operator: &str, operands: &[Operand]) -> Option<Self> {
+        Some(match operator {
+            "w" => Self::SetLineWidth(num(operands).filter(|w| *w >= 0.0)?),
+            "J" => Self::SetLineCap(match int(operands)? {
+                0 => LineCapStyle::ButtCap,
+                1 => LineCapStyle::RoundCap,
+                2 => LineCapStyle::ProjectingSquareCap,
+                _ => return None,
+            }),
+            "j" => Self::SetLineJoin(match int(operands)? {
+                0 => LineJoinStyle::MiterJoin,
+                1 => LineJoinStyle::RoundJoin,
+                2 => LineJoinStyle::BevelJoin,
+                _ => return None,
+            }),
+            "M" => Self::SetMiterLimit(num(operands)?),
+            "d" => match operands {
+                [Operand::Array(array), phase] => Self::SetDashPattern(
+                    array.iter().map(Operand::as_f32).collect::<Option<_>>()?,
+                    phase.as_f32()?,
+                ),
+                _ => return None,
+            },
+            "ri" => Self::SetRenderingIntent(match name(operands)? {
+                b"AbsoluteColorimetric" => RenderingIntent::AbsoluteColorimetric,
+                b"RelativeColorimetric" => RenderingIntent::RelativeColorimetric,
+                b"Saturation" => RenderingIntent::Saturation,
+                b"Perceptual" => RenderingIntent::Perceptual,
+                _ => return None,
+            }),
+            "i" => Self::SetFlatness(int(operands).filter(|t| matches!(t, 0..=100))?),
+            "gs" => Self::SetParameters(name(operands)?.into()),
+            "q" => none(operands, Self::SaveState)?,
+            "Q" => none(operands, Self::RestoreState)?,
+            "cm" => Self::Transform(nums(operands)?),
+            "m" => {
+                let [x, y] = nums(operands)?;
+                Self::MoveTo(x, y)
+            }
+            "l" => {
+                let [x, y] = nums(operands)?;
+                Self::LineTo(x, y)
+            }
+            "c" => {
+                let [x1, y1, x2, y2, x3, y3] = nums(operands)?;
+                Self::CubicTo(x1, y1, x2, y2, x3, y3)
+            }
+            "v" => {
+                let [x2, y2, x3, y3] = nums(operands)?;
+                Self::CubicToInitial(x2, y2, x3, y3)
+            }
+            "y" => {
+                let [x1, y1, x3, y3] = nums(operands)?;
+                Self::CubicToFinal(x1, y1, x3, y3)
+            }
+            "h" => none(operands, Self::ClosePath)?,
+            "re" => {
+                let [x, y, w, h] = nums(operands)?;
+                Self::Rect(x, y, w, h)
+            }
+            "S" => none(operands, Self::Stroke)?,
+            "s" => none(operands, Self::CloseAndStroke)?,
+            "f" => none(operands, Self::FillNonzero)?,
+            "f*" => none(operands, Self::FillEvenOdd)?,
+            "B" => none(operands, Self::FillNonzeroAndStroke)?,
+            "B*" => none(operands, Self::FillEvenOddAndStroke)?,
+            "b" => none(operands, Self::CloseFillNonzeroAndStroke)?,
+            "b*" => none(operands, Self::CloseFillEvenOddAndStroke)?,
+            "n" => none(operands, Self::EndPath)?,
+            "W" => none(operands, Self::ClipNonzero)?,
+            "W*" => none(operands, Self::ClipEvenOdd)?,
+            "BT" => none(operands, Self::BeginText)?,
+            "ET" => none(operands, Self::EndText)?,
+            "Tc" => Self::SetCharSpacing(num(operands)?),
+            "Tw" => Self::SetWordSpacing(num(operands)?),
+            "Tz" => Self::SetHorizontalScaling(num(operands)?),
+            "TL" => Self::SetLeading(num(operands)?),
+            "Tf" => match operands {
+                [Operand::Name(font), size] => {
+                    Self::SetFont(font.clone(), size.as_f32()?)
+                }
+                _ => return None,
+            },
+            "Tr" => Self::SetTextRenderingMode(match int(operands)? {
+                0 => TextRenderingMode::Fill,
+                1 => TextRenderingMode::Stroke,
+                2 => TextRenderingMode::FillStroke,
+                3 => TextRenderingMode::Invisible,
+                4 => TextRenderingMode::FillClip,
+                5 => TextRenderingMode::StrokeClip,
+                6 => TextRenderingMode::FillStrokeClip,
+                7 => TextRenderingMode::Clip,
+                _ => return None,
+            }),
+            "Ts" => Self::SetRise(num(operands)?),
+            "Td" => {
+                let [x, y] = nums(operands)?;
+                Self::NextLine(x, y)
+            }
+            "TD" => {
+                let [x, y] = nums(operands)?;
+                Self::NextLineAndSetLeading(x, y)
+            }
+            "Tm" => Self::SetTextMatrix(nums(operands)?),
+            "T*" => none(operands, Self::NextLineUsingLeading)?,
+            "Tj" => Self::Show(string(operands)?.into()),
+            "'" => Self::NextLineShow(string(operands)?.into()),
+            "\"" => match operands {
+                [word, char, Operand::Str(text)] => {
+                    Self::NextLineShowAndSetWordAndCharSpacing(
+                        word.as_f32()?,
+                        char.as_f32()?,
+                        text.clone(),
+                    )
+                }
+                _ => return None,
+            },
+            "TJ" => match operands {
+                [Operand::Array(items)] => Self::ShowPositioned(
+                    items
+                        .iter()
+                        .map(|item| match item {
+                            Operand::Str(text) => {
+                                Some(PositionedItem::Show(text.clone()))
+                            }
+                            _ => item.as_f32().map(PositionedItem::Adjust),
+                        })
+                        .collect::<Option<_>>()?,
+                ),
+                _ => return None,
+            },
+            "d0" => match nums(operands)? {
+                [wx, 0.0] => Self::StartColorGlyph(wx),
+                _ => return None,
+            },
+            "d1" => match nums(operands)? {
+                [wx, 0.0, ll_x, ll_y, ur_x, ur_y] => {
+                    Self::StartShapeGlyph(wx, ll_x, ll_y, ur_x, ur_y)
+                }
+                _ => return None,
+            },
+            "CS" => Self::SetStrokeColorSpace(name(operands)?.into()),
+            "cs" => Self::SetFillColorSpace(name(operands)?.into()),
+            "SCN" => match color_or_pattern(operands)? {
+                (tint, Some(pattern)) => Self::SetStrokePattern(tint, pattern),
+                (color, None) => Self::SetStrokeColor(color),
+            },
+            "scn" => match color_or_pattern(operands)? {
+                (tint, Some(pattern)) => Self::SetFillPattern(tint, pattern),
+                (color, None) => Self::SetFillColor(color),
+            },
+            "G" => Self::SetStrokeGray(num(operands)?),
+            "g" => Self::SetFillGray(num(operands)?),
+            "RG" => {
+                let [r, g, b] = nums(operands)?;
+                Self::SetStrokeRgb(r, g, b)
+            }
+            "rg" => {
+                let [r, g, b] = nums(operands)?;
+                Self::SetFillRgb(r, g, b)
+            }
+            "K" => {
+                let [c, m, y, k] = nums(operands)?;
+                Self::SetStrokeCmyk(c, m, y, k)
+            }
+            "k" => {
+                let [c, m, y, k] = nums(operands)?;
+                Self::SetFillCmyk(c, m, y, k)
+            }
+            "sh" => Self::Shading(name(operands)?.into()),
+            "Do" => Self::XObject(name(operands)?.into()),
+            "MP" => Self::MarkedContentPoint(name(operands)?.into()),
+            "DP" => {
+                let (tag, properties) = tag_and_properties(operands)?;
+                Self::MarkedContentPointWithProperties(tag, properties)
+            }
+            "BMC" => Self::BeginMarkedContent(name(operands)?.into()),
+            "BDC" => {
+                let (tag, properties) = tag_and_properties(operands)?;
+                Self::BeginMarkedContentWithProperties(tag, properties)
+            }
+            "EMC" => none(operands, Self::EndMarkedContent)?,
+            "BX" => none(operands, Self::BeginCompat)?,
+            "EX" => none(operands, Self::EndCompat)?,
+            _ => return None,
+        })
+    }
+
+    /// The operator of this operation.
+    pub fn operator(&self) -> &str {
+        match self {
+            Self::SetLineWidth(..) => "w",
+            Self::SetLineCap(..) => "J",
+            Self::SetLineJoin(..) => "j",
+            Self::SetMiterLimit(..) => "M",
+            Self::SetDashPattern(..) => "d",
+            Self::SetRenderingIntent(..) => "ri",
+            Self::SetFlatness(..) => "i",
+            Self::SetParameters(..) => "gs",
+            Self::SaveState => "q",
+            Self::RestoreState => "Q",
+            Self::Transform(..) => "cm",
+            Self::MoveTo(..) => "m",
+            Self::LineTo(..) => "l",
+            Self::CubicTo(..) => "c",
+            Self::CubicToInitial(..) => "v",
+            Self::CubicToFinal(..) => "y",
+            Self::ClosePath => "h",
+            Self::Rect(..) => "re",
+            Self::Stroke => "S",
+            Self::CloseAndStroke => "s",
+            Self::FillNonzero => "f",
+            Self::FillEvenOdd => "f*",
+            Self::FillNonzeroAndStroke => "B",
+            Self::FillEvenOddAndStroke => "B*",
+            Self::CloseFillNonzeroAndStroke => "b",
+            Self::CloseFillEvenOddAndStroke => "b*",
+            Self::EndPath => "n",
+            Self::ClipNonzero => "W",
+            Self::ClipEvenOdd => "W*",
+            Self::BeginText => "BT",
+            Self::EndText => "ET",
+            Self::SetCharSpacing(..) => "Tc",
+            Self::SetWordSpacing(..) => "Tw",
+            Self::SetHorizontalScaling(..) => "Tz",
+            Self::SetLeading(..) => "TL",
+            Self::SetFont(..) => "Tf",
+            Self::SetTextRenderingMode(..) => "Tr",
+            Self::SetRise(..) => "Ts",
+            Self::NextLine(..) => "Td",
+            Self::NextLineAndSetLeading(..) => "TD",
+            Self::SetTextMatrix(..) => "Tm",
+            Self::NextLineUsingLeading => "T*",
+            Self::Show(..) => "Tj",
+            Self::NextLineShow(..) => "'",
+            Self::NextLineShowAndSetWordAndCharSpacing(..) => "\"",
+            Self::ShowPositioned(..) => "TJ",
+            Self::StartColorGlyph(..) => "d0",
+            Self::StartShapeGlyph(..) => "d1",
+            Self::SetStrokeColorSpace(..) => "CS",
+            Self::SetFillColorSpace(..) => "cs",
+            Self::SetStrokeColor(..) | Self::SetStrokePattern(..) => "SCN",
+            Self::SetFillColor(..) | Self::SetFillPattern(..) => "scn",
+            Self::SetStrokeGray(..) => "G",
+            Self::SetFillGray(..) => "g",
+            Self::SetStrokeRgb(..) => "RG",
+            Self::SetFillRgb(..) => "rg",
+            Self::SetStrokeCmyk(..) => "K",
+            Self::SetFillCmyk(..) => "k",
+            Self::Shading(..) => "sh",
+            Self::XObject(..) => "Do",
+            Self::MarkedContentPoint(..) => "MP",
+            Self::MarkedContentPointWithProperties(..) => "DP",
+            Self::BeginMarkedContent(..) => "BMC",
+            Self::BeginMarkedContentWithProperties(..) => "BDC",
+            Self::EndMarkedContent => "EMC",
+            Self::BeginCompat => "BX",
+            Self::EndCompat => "EX",
+            Self::Other { operator, .. } => operator,
+        }
+    }
+
+    /// Write the operation into a content stream using the corresponding
+    /// [`Content`] method.
+    pub fn write(&self, content: &mut Content) {
+        match self {
+            Self::SetLineWidth(width) => content.set_line_width(*width),
+            Self::SetLineCap(cap) => content.set_line_cap(*cap),
+            Self::SetLineJoin(join) => content.set_line_join(*join),
+            Self::SetMiterLimit(limit) => content.set_miter_limit(*limit),
+            Self::SetDashPattern(array, phase) => {
+                content.set_dash_pattern(array.iter().copied(), *phase)
+            }
+            Self::SetRenderingIntent(intent) => content.set_rendering_intent(*intent),
+            Self::SetFlatness(tolerance) => content.set_flatness(*tolerance),
+            Self::SetParameters(dict) => content.set_parameters(Name(dict)),
+            Self::SaveState => content.save_state(),
+            Self::RestoreState => content.restore_state(),
+            Self::Transform(matrix) => content.transform(*matrix),
+            Self::MoveTo(x, y) => content.move_to(*x, *y),
+            Self::LineTo(x, y) => content.line_to(*x, *y),
+            Self::CubicTo(x1, y1, x2, y2, x3, y3) => {
+                content.cubic_to(*x1, *y1, *x2, *y2, *x3, *y3)
+            }
+            Self::CubicToInitial(x2, y2, x3, y3) => {
+                content.cubic_to_initial(*x2, *y2, *x3, *y3)
+            }
+            Self::CubicToFinal(x1, y1, x3, y3) => {
+                content.cubic_to_final(*x1, *y1, *x3, *y3)
+            }
+            Self::ClosePath => content.close_path(),
+            Self::Rect(x, y, w, h) => content.rect(*x, *y, *w, *h),
+            Self::Stroke => content.stroke(),
+            Self::CloseAndStroke => content.close_and_stroke(),
+            Self::FillNonzero => content.fill_nonzero(),
+            Self::FillEvenOdd => content.fill_even_odd(),
+            Self::FillNonzeroAndStroke => content.fill_nonzero_and_stroke(),
+            Self::FillEvenOddAndStroke => content.fill_even_odd_and_stroke(),
+            Self::CloseFillNonzeroAndStroke => content.close_fill_nonzero_and_stroke(),
+            Self::CloseFillEvenOddAndStroke => content.close_fill_even_odd_and_stroke(),
+            Self::EndPath => content.end_path(),
+            Self::ClipNonzero => content.clip_nonzero(),
+            Self::ClipEvenOdd => content.clip_even_odd(),
+            Self::BeginText => content.begin_text(),
+            Self::EndText => content.end_text(),
+            Self::SetCharSpacing(spacing) => content.set_char_spacing(*spacing),
+            Self::SetWordSpacing(spacing) => content.set_word_spacing(*spacing),
+            Self::SetHorizontalScaling(scaling) => {
+                content.set_horizontal_scaling(*scaling)
+            }
+            Self::SetLeading(leading) => content.set_leading(*leading),
+            Self::SetFont(font, size) => content.set_font(Name(font), *size),
+            Self::SetTextRenderingMode(mode) => content.set_text_rendering_mode(*mode),
+            Self::SetRise(rise) => content.set_rise(*rise),
+            Self::NextLine(x, y) => content.next_line(*x, *y),
+            Self::NextLineAndSetLeading(x, y) => {
+                content.next_line_and_set_leading(*x, *y)
+            }
+            Self::SetTextMatrix(matrix) => content.set_text_matrix(*matrix),
+            Self::NextLineUsingLeading => content.next_line_using_leading(),
+            Self::Show(text) => content.show(Str(text)),
+            Self::NextLineShow(text) => content.next_line_show(Str(text)),
+            Self::NextLineShowAndSetWordAndCharSpacing(word, char, text) => content
+                .next_line_show_and_set_word_and_char_spacing(*word, *char, Str(text)),
+            Self::ShowPositioned(items) => {
+                write_positioned(content.show_positioned(), items);
+                content
+            }
+            Self::StartColorGlyph(wx) => content.start_color_glyph(*wx),
+            Self::StartShapeGlyph(wx, ll_x, ll_y, ur_x, ur_y) => {
+                content.start_shape_glyph(*wx, *ll_x, *ll_y, *ur_x, *ur_y)
+            }
+            Self::SetStrokeColorSpace(space) => {
+                content.set_stroke_color_space(Name(space))
+            }
+            Self::SetFillColorSpace(space) => content.set_fill_color_space(Name(space)),
+            Self::SetStrokeColor(color) => {
+                content.set_stroke_color(color.iter().copied())
+            }
+            Self::SetStrokePattern(tint, name) => {
+                content.set_stroke_pattern(tint.iter().copied(), Name(name))
+            }
+            Self::SetFillColor(color) => content.set_fill_color(color.iter().copied()),
+            Self::SetFillPattern(tint, name) => {
+                content.set_fill_pattern(tint.iter().copied(), Name(name))
+            }
+            Self::SetStrokeGray(gray) => content.set_stroke_gray(*gray),
+            Self::SetFillGray(gray) => content.set_fill_gray(*gray),
+            Self::SetStrokeRgb(r, g, b) => content.set_stroke_rgb(*r, *g, *b),
+            Self::SetFillRgb(r, g, b) => content.set_fill_rgb(*r, *g, *b),
+            Self::SetStrokeCmyk(c, m, y, k) => content.set_stroke_cmyk(*c, *m, *y, *k),
+            Self::SetFillCmyk(c, m, y, k) => content.set_fill_cmyk(*c, *m, *y, *k),
+            Self::Shading(name) => content.shading(Name(name)),
+            Self::XObject(name) => content.x_object(Name(name)),
+            Self::MarkedContentPoint(tag) => content.marked_content_point(Name(tag)),
+            Self::MarkedContentPointWithProperties(tag, properties) => {
+                write_properties(
+                    content.marked_content_point_with_properties(Name(tag)),
+                    properties,
+                );
+                content
+            }
+            Self::BeginMarkedContent(tag) => content.begin_marked_content(Name(tag)),
+            Self::BeginMarkedContentWithProperties(tag, properties) => {
+                write_properties(
+                    content.begin_marked_content_with_properties(Name(tag)),
+                    properties,
+                );
+                content
+            }
+            Self::EndMarkedContent => content.end_marked_content(),
+            Self::BeginCompat => content.begin_compat(),
+            Self::EndCompat => content.end_compat(),
+            Self::Other { operator, operands } => {
+                write_operands(content.op(operator), operands);
+                content
+            }
+        };
+    }
+}
+
+fn write_positioned(mut show: ShowPositioned, items: &[PositionedItem]) {
+    let mut array = show.items();
+    for item in items {
+        match item {
+            PositionedItem::Show(text) => array.show(Str(text)),
+            PositionedItem::Adjust(amount) => array.adjust(*amount),
+        };
+    }
+}
+
+fn write_operands(mut op: Operation, operands: &[Operand]) {
+    for operand in operands {
+        operand.write(op.obj());
+    }
+}
+
+fn write_properties(mut mc: MarkContent, properties: &PropertiesOperand) {
+    match properties {
+        PropertiesOperand::Inline(pairs) => write_pairs(&mut mc.properties(), pairs),
+        PropertiesOperand::Named(name) => mc.properties_named(Name(name)),
+    }
+}
+
+/// Match an operation without operands.
+fn none(operands: &[Operand], op: Op) -> Option<Op> {
+    operands.is_empty().then_some(op)
+}
+
+/// Match a single number operand.
+fn num(operands: &[Operand]) -> Option<f32> {
+    let [value] = nums(operands)?;
+    Some(value)
+}
+
+/// Match a single integer operand.
+fn int(operands: &[Operand]) -> Option<i32> {
+    match operands {
+        [operand] => operand.as_i32(),
+        _ => None,
+    }
+}
+
+/// Match exactly `N` number operands.
+fn nums<const N: usize>(operands: &[Operand]) -> Option<[f32; N]> {
+    if operands.len() != N {
+        return None;
+    }
+
+    let mut values = [0.0; N];
+    for (value, operand) in values.iter_mut().zip(operands) {
+        *value = operand.as_f32()?;
+    }
+    Some(values)
+}
+
+/// Match a single name operand.
+fn name(operands: &[Operand]) -> Option<&[u8]> {
+    match operands {
+        [operand] => operand.as_name(),
+        _ => None,
+    }
+}
+
+/// Match a single string operand.
+fn string(operands: &[Operand]) -> Option<&[u8]> {
+    match operands {
+        [operand] => operand.as_str(),
+        _ => None,
+    }
+}
+
+/// Match a sequence of numbers, optionally followed by a pattern name.
+fn color_or_pattern(operands: &[Operand]) -> Option<(Vec<f32>, Option<Vec<u8>>)> {
+    let (pattern, numbers) = match operands {
+        [rest @ .., Operand::Name(name)] => (Some(name.clone()), rest),
+        _ => (None, operands),
+    };
+    let numbers = numbers.iter().map(Operand::as_f32).collect::<Option<_>>()?;
+    Some((numbers, pattern))
+}
+
+/// Match a tag followed by a property list.
+fn tag_and_properties(operands: &[Operand]) -> Option<(Vec<u8>, PropertiesOperand)> {
+    match operands {
+        [Operand::Name(tag), Operand::Dict(pairs)] => {
+            Some((tag.clone(), PropertiesOperand::Inline(pairs.clone())))
+        }
+        [Operand::Name(tag), Operand::Name(name)] => {
+            Some((tag.clone(), PropertiesOperand::Named(name.clone())))
+        }
+        _ => None,
+    }
+}
+
+/// A parser for content streams.
+///
+/// This is an iterator over the [operations](Op) in a content stream. Once
+/// an error was encountered, the iterator yields it and then stops.
+///
+/// ```
+/// use pdf_writer::types::Op;
+/// use pdf_writer::{Content, ContentParser};
+///
+/// let ops: Vec<Op> = ContentParser::new(b"1 0 0 rg 0 0 10 10 re f")
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(
+///     ops,
+///     [Op::SetFillRgb(1.0, 0.0, 0.0), Op::Rect(0.0, 0.0, 10.0, 10.0), Op::FillNonzero]
+/// );
+///
+/// // Replace red with blue and write the operations back.
+/// let mut content = Content::new();
+/// for op in ops {
+///     match op {
+///         Op::SetFillRgb(1.0, 0.0, 0.0) => Op::SetFillRgb(0.0, 0.0, 1.0),
+///         op => op,
+///     }
+///     .write(&mut content);
+/// }
+///
+/// assert_eq!(content.finish().as_slice(), b"0 0 1 rg\n0 0 10 10 re\nf");
+/// ```
+pub struct ContentParser<'a> {
+    data: &'a [u8],
+    cursor: usize,
+    operands: Vec<Operand>,
+}
+
+impl<'a> ContentParser<'a> {
+    /// Create a new parser for the bytes of a content stream.
+    ///
+    /// The data must already be decoded, i.e. no filters are applied.
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, cursor: 0, operands: vec![] }
+    }
+
+    /// The offset in the content stream up to which it was parsed.
+    pub fn offset(&self) -> usize {
+        self.cursor
+    }
+
+    fn next_op(&mut self) -> Result<Option<Op>, ParseError> {
+        loop {
+            self.skip_whitespace();
+            if self.cursor >= self.data.len() {
+                return if self.operands.is_empty() {
+                    Ok(None)
+                } else {
+                    Err(self.error(ParseErrorKind::MissingOperator))
+                };
+            }
+
+            match self.parse_token()? {
+                Token::Operand(operand) => self.operands.push(operand),
+                Token::Operator(start) => {
+                    let bytes = &self.data[start..self.cursor];
+                    if bytes == b"BI" {
+                        return Err(ParseError::new(start, ParseErrorKind::InlineImage));
+                    }
+
+                    let operator = std::str::from_utf8(bytes).map_err(|_| {
+                        ParseError::new(start, ParseErrorKind::InvalidOperator)
+                    })?;
+
+                    let operands = std::mem::take(&mut self.operands);
+                    return Ok(Some(Op::from_parts(operator, operands)));
+                }
+            }
+        }
+    }
+
+    /// Parse an operand or operator.
+    fn parse_token(&mut self) -> Result<Token, ParseError> {
+        let start = self.cursor;
+        Ok(match self.data[start] {
+            b'/' => Token::Operand(Operand::Name(self.parse_name()?)),
+            b'(' => Token::Operand(Operand::Str(self.parse_literal_str()?)),
+            b'<' if self.data.get(start + 1) == Some(&b'<') => {
+                Token::Operand(Operand::Dict(self.parse_dict()?))
+            }
+            b'<' => Token::Operand(Operand::Str(self.parse_hex_str()?)),
+            b'[' => Token::Operand(Operand::Array(self.parse_array()?)),
+            b')' | b'>' | b']' | b'{' | b'}' => {
+                return Err(self.error(ParseErrorKind::UnexpectedDelimiter));
+            }
+            _ => {
+                let word = self.eat_regular();
+                match word {
+                    b"true" => Token::Operand(Operand::Bool(true)),
+                    b"false" => Token::Operand(Operand::Bool(false)),
+                    b"null" => Token::Operand(Operand::Null),
+                    [b'0'..=b'9' | b'+' | b'-' | b'.', ..] => {
+                        Token::Operand(parse_number(word).ok_or(ParseError::new(
+                            start,
+                            ParseErrorKind::InvalidNumber,
+                        ))?)
+                    }
+                    _ => Token::Operator(start),
+                }
+            }
+        })
+    }
+
+    /// Parse a direct object inside of an array or dictionary.
+    fn parse_object(&mut self) -> Result<Operand, ParseError> {
+        let start = self.cursor;
+        match self.parse_token()? {
+            Token::Operand(operand) => Ok(operand),
+            Token::Operator(_) => {
+                Err(ParseError::new(start, ParseErrorKind::UnexpectedOperator))
+            }
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<Vec<u8>, ParseError> {
+        self.cursor += 1;
+        let start = self.cursor;
+        let raw = self.eat_regular();
+        let mut name = Vec::with_capacity(raw.len());
+        let mut iter = raw.iter().copied();
+        while let Some(byte) = iter.next() {
+            if byte == b'#' {
+                let hi = iter.next().and_then(hex_value);
+                let lo = iter.next().and_then(hex_value);
+                let (Some(hi), Some(lo)) = (hi, lo) else {
+                    return Err(ParseError::new(start, ParseErrorKind::InvalidName));
+                };
+                name.push(hi << 4 | lo);
+            } else {
+                name.push(byte);
+            }
+        }
+        Ok(name)
+    }
+
+    fn parse_literal_str(&mut self) -> Result<Vec<u8>, ParseError> {
+        let start = self.cursor;
+        self.cursor += 1;
+
+        let mut string = vec![];
+        let mut depth = 1;
+        loop {
+            let Some(&byte) = self.data.get(self.cursor) else {
+                return Err(ParseError::new(start, ParseErrorKind::UnterminatedString));
+            };
+
+            self.cursor += 1;
+            match byte {
+                b'(' => depth += 1,
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(string);
+                    }
+                }
+                b'\\' => {
+                    let Some(&escaped) = self.data.get(self.cursor) else { continue };
+                    self.cursor += 1;
+                    match escaped {
+                        b'n' => string.push(b'\n'),
+                        b'r' => string.push(b'\r'),
+                        b't' => string.push(b'\t'),
+                        b'b' => string.push(b'\x08'),
+                        b'f' => string.push(b'\x0c'),
+                        b'0'..=b'7' => {
+                            let mut value = escaped - b'0';
+                            for _ in 0..2 {
+                                match self.data.get(self.cursor) {
+                                    Some(&digit @ b'0'..=b'7') => {
+                                        value = value.wrapping_mul(8) + (digit - b'0');
+                                        self.cursor += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            string.push(value);
+                        }
+                        // A backslash at the end of a line continues the
+                        // string on the next line.
+                        b'\r' => {
+                            if self.data.get(self.cursor) == Some(&b'\n') {
+                                self.cursor += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        // This includes `\(`, `\)` and `\\`. An unknown escape
+                        // sequence is ignored, but the character is kept.
+                        _ => string.push(escaped),
+                    }
+                    continue;
+                }
+                // An end-of-line marker in a string is always read as `\n`.
+                b'\r' => {
+                    if self.data.get(self.cursor) == Some(&b'\n') {
+                        self.cursor += 1;
+                    }
+                    string.push(b'\n');
+                    continue;
+                }
+                _ => {}
+            }
+
+            string.push(byte);
+        }
+    }
+
+    fn parse_hex_str(&mut self) -> Result<Vec<u8>, ParseError> {
+        let start = self.cursor;
+        self.cursor += 1;
+
+        let mut string = vec![];
+        let mut pending = None;
+        loop {
+            let Some(&byte) = self.data.get(self.cursor) else {
+                return Err(ParseError::new(start, ParseErrorKind::UnterminatedString));
+            };
+
+            self.cursor += 1;
+            if byte == b'>' {
+                // A missing final digit is assumed to be zero.
+                string.extend(pending.map(|hi: u8| hi << 4));
+                return Ok(string);
+            } else if is_whitespace(byte) {
+                continue;
+            }
+
+            let Some(value) = hex_value(byte) else {
+                return Err(ParseError::new(self.cursor - 1, ParseErrorKind::InvalidHex));
+            };
+
+            match pending.take() {
+                Some(hi) => string.push(hi << 4 | value),
+                None => pending = Some(value),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Vec<Operand>, ParseError> {
+        let start = self.cursor;
+        self.cursor += 1;
+
+        let mut items = vec![];
+        loop {
+            self.skip_whitespace();
+            match self.data.get(self.cursor) {
+                None => {
+                    return Err(ParseError::new(start, ParseErrorKind::UnterminatedArray))
+                }
+                Some(b']') => {
+                    self.cursor += 1;
+                    return Ok(items);
+                }
+                Some(_) => items.push(self.parse_object()?),
+            }
+        }
+    }
+
+    fn parse_dict(&mut self) -> Result<Vec<(Vec<u8>, Operand)>, ParseError> {
+        let start = self.cursor;
+        self.cursor += 2;
+
+        let mut pairs = vec![];
+        loop {
+            self.skip_whitespace();
+            match self.data.get(self.cursor) {
+                None => {
+                    return Err(ParseError::new(start, ParseErrorKind::UnterminatedDict))
+                }
+                Some(b'>') if self.data.get(self.cursor + 1) == Some(&b'>') => {
+                    self.cursor += 2;
+                    return Ok(pairs);
+                }
+                Some(b'/') => {
+                    let key = self.parse_name()?;
+                    self.skip_whitespace();
+                    if self.cursor >= self.data.len() {
+                        return Err(ParseError::new(
+                            start,
+                            ParseErrorKind::UnterminatedDict,
+                        ));
+                    }
+                    pairs.push((key, self.parse_object()?));
+                }
+                Some(_) => return Err(self.error(ParseErrorKind::InvalidDictKey)),
+            }
+        }
+    }
+
+    /// Skip whitespace and comments.
+    fn skip_whitespace(&mut self) {
+        while let Some(&byte) = self.data.get(self.cursor) {
+            if byte == b'%' {
+                while self
+                    .data
+                    .get(self.cursor)
+                    .is_some_and(|&b| !matches!(b, b'\n' | b'\r'))
+                {
+                    self.cursor += 1;
+                }
+            } else if is_whitespace(byte) {
+                self.cursor += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Eat a sequence of regular characters.
+    fn eat_regular(&mut self) -> &'a [u8] {
+        let start = self.cursor;
+        while self.data.get(self.cursor).copied().is_some_and(is_regular) {
+            self.cursor += 1;
+        }
+        &self.data[start..self.cursor]
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError::new(self.cursor, kind)
+    }
+}
+
+impl Iterator for ContentParser<'_> {
+    type Item = Result<Op, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.next_op();
+        if result.is_err() {
+            self.cursor = self.data.len();
+            self.operands.clear();
+        }
+        result.transpose()
+    }
+}
+
+enum Token {
+    Operand(Operand),
+    Operator(usize),
+}
+
+/// Parse an integer or real number.
+fn parse_number(word: &[u8]) -> Option<Operand> {
+    let string = std::str::from_utf8(word).ok()?;
+    if !string.contains('.') {
+        if let Ok(int) = string.parse::<i32>() {
+            return Some(Operand::Integer(int));
+        }
+    }
+
+    // Rust's float grammar is a superset of PDF's, so we have to exclude
+    // exponents and special values.
+    if !string.bytes().all(|b| matches!(b, b'0'..=b'9' | b'+' | b'-' | b'.')) {
+        return None;
+    }
+
+    string.parse::<f32>().ok().map(Operand::Real)
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Whether a byte is whitespace according to PDF syntax conventions.
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b'\0' | b'\t' | b'\n' | b'\x0c' | b'\r' | b' ')
+}
+
+/// Whether a byte is neither whitespace nor a delimiter.
+fn is_regular(byte: u8) -> bool {
+    !is_whitespace(byte)
+        && !matches!(
+            byte,
+            b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+        )
+}
+
+/// An error that occurred while parsing a content stream.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ParseError {
+    offset: usize,
+    kind: ParseErrorKind,
+}
+
+impl ParseError {
+    fn new(offset: usize, kind: ParseErrorKind) -> Self {
+        Self { offset, kind }
+    }
+
+    /// The byte offset in the content stream at which the error occurred.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// What kind of error occurred.
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} at offset {}", self.kind, self.offset)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The kind of a [`ParseError`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// A number was malformed.
+    InvalidNumber,
+    /// A name contained an invalid `#` escape sequence.
+    InvalidName,
+    /// A hexadecimal string contained a non-hexadecimal character.
+    InvalidHex,
+    /// An operator was not valid UTF-8.
+    InvalidOperator,
+    /// A dictionary key was not a name.
+    InvalidDictKey,
+    /// A string was not terminated.
+    UnterminatedString,
+    /// An array was not terminated.
+    UnterminatedArray,
+    /// A dictionary was not terminated.
+    UnterminatedDict,
+    /// A closing delimiter appeared without a matching opening one.
+    UnexpectedDelimiter,
+    /// An operator appeared inside of an array or dictionary.
+    UnexpectedOperator,
+    /// The stream ended with operands that were not followed by an operator.
+    MissingOperator,
+    /// An inline image was encountered. These are not supported.
+    InlineImage,
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad(match self {
+            Self::InvalidNumber => "invalid number",
+            Self::InvalidName => "invalid name",
+            Self::InvalidHex => "invalid hexadecimal string",
+            Self::InvalidOperator => "invalid operator",
+            Self::InvalidDictKey => "dictionary key must be a name",
+            Self::UnterminatedString => "unterminated string",
+            Self::UnterminatedArray => "unterminated array",
+            Self::UnterminatedDict => "unterminated dictionary",
+            Self::UnexpectedDelimiter => "unexpected delimiter",
+            Self::UnexpectedOperator => "unexpected operator",
+            Self::MissingOperator => "missing operator",
+            Self::InlineImage => "inline images are not supported",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(data: &[u8]) -> Vec<Op> {
+        ContentParser::new(data).collect::<Result<_, _>>().unwrap()
+    }
+
+    fn rewrite(ops: &[Op], settings: Settings) -> Vec<u8> {
+        let mut content = Content::with_settings(settings);
+        for op in ops {
+            op.write(&mut content);
+        }
+        content.finish().into_vec()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for settings in [Settings::default(), Settings { pretty: false }] {
+            let mut content = Content::with_settings(settings);
+            content
+                .save_state()
+                .transform([1.0, 0.0, 0.0, 1.0, 5.5, -3.0])
+                .set_line_width(2.0)
+                .set_line_cap(LineCapStyle::RoundCap)
+                .set_dash_pattern([3.0, 1.0], 0.5)
+                .set_rendering_intent(RenderingIntent::Perceptual)
+                .set_parameters(Name(b"GS1"))
+                .move_to(0.0, 0.0)
+                .cubic_to(1.0, 2.0, 3.0, 4.0, 5.0, 6.0)
+                .cubic_to_initial(1.0, 2.0, 3.0, 4.0)
+                .close_path()
+                .set_stroke_color_space(Name(b"Pattern"))
+                .set_stroke_pattern([0.25], Name(b"P1"))
+                .set_fill_cmyk(0.1, 0.2, 0.3, 0.4)
+                .fill_even_odd_and_stroke()
+                .restore_state()
+                .begin_text()
+                .set_font(Name(b"F#1"), 12.0)
+                .set_text_rendering_mode(TextRenderingMode::FillClip)
+                .show(Str(b"(Hi)\\\n"))
+                .next_line_show_and_set_word_and_char_spacing(1.0, 2.0, Str(b"\xff"));
+            content.show_positioned().items().show(Str(b"A")).adjust(-20.0);
+            content.end_text();
+            content
+                .begin_marked_content_with_properties(Name(b"Span"))
+                .properties()
+                .actual_text(TextStr("Ä"))
+                .pair(Name(b"Nested"), Name(b"x"))
+                .insert(Name(b"Arr"))
+                .array()
+                .items([1, 2]);
+            content
+                .end_marked_content()
+                .marked_content_point_with_properties(Name(b"Tag"))
+                .properties_named(Name(b"MC0"));
+            content.op("sc").operands([0.5, 0.5]);
+
+            let expected = content.finish().into_vec();
+            let ops = parse(&expected);
+            assert_eq!(ops.len(), 27);
+            assert_eq!(rewrite(&ops, settings), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_syntax() {
+        let ops = parse(
+            b"%comment\n/F1 -.5 Tf<48 65 6C6>Tj(a\\101\\\nb\\(\r\n)'\
+            [(x)-1.5 3]TJ 1 2 3 sc/P0 scn 2 0 d0 1 0 0 1 0 0 0 d1",
+        );
+
+        assert_eq!(
+            ops,
+            [
+                Op::SetFont(b"F1".to_vec(), -0.5),
+                Op::Show(b"Hel`".to_vec()),
+                Op::NextLineShow(b"aAb(\n".to_vec()),
+                Op::ShowPositioned(vec![
+                    PositionedItem::Show(b"x".to_vec()),
+                    PositionedItem::Adjust(-1.5),
+                    PositionedItem::Adjust(3.0),
+                ]),
+                Op::Other {
+                    operator: "sc".into(),
+                    operands: vec![
+                        Operand::Integer(1),
+                        Operand::Integer(2),
+                        Operand::Integer(3),
+                    ],
+                },
+                Op::SetFillPattern(vec![], b"P0".to_vec()),
+                Op::StartColorGlyph(2.0),
+                Op::Other {
+                    operator: "d1".into(),
+                    operands: [1, 0, 0, 1, 0, 0, 0].map(Operand::Integer).to_vec(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_fallback() {
+        let ops = parse(b"-1 w 3 J 0.5 i 1 m /Foo ri");
+        assert!(ops.iter().all(|op| matches!(op, Op::Other { .. })));
+        assert_eq!(rewrite(&ops, Settings::default()), b"-1 w\n3 J\n0.5 i\n1 m\n/Foo ri");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |data: &[u8]| {
+            ContentParser::new(data)
+                .find_map(Result::err)
+                .map(|err| (err.offset(), err.kind()))
+        };
+
+        assert_eq!(error(b"q 1 2"), Some((5, ParseErrorKind::MissingOperator)));
+        assert_eq!(error(b"(abc"), Some((0, ParseErrorKind::UnterminatedString)));
+        assert_eq!(error(b"[1 m]"), Some((3, ParseErrorKind::UnexpectedOperator)));
+        assert_eq!(error(b"1.2.3 w"), Some((0, ParseErrorKind::InvalidNumber)));
+        assert_eq!(error(b"q ) Q"), Some((2, ParseErrorKind::UnexpectedDelimiter)));
+        assert_eq!(error(b"<</A 1 2>> BDC"), Some((7, ParseErrorKind::InvalidDictKey)));
+        assert_eq!(error(b"BI /W 1 ID x EI"), Some((0, ParseErrorKind::InlineImage)));
+        assert_eq!(error(b"<1x>"), Some((2, ParseErrorKind::InvalidHex)));
+    }
+}
@@ -0,0 +1,661 @@
+use crate::cff::{Cff, Cff2};
+use crate::reader::{read_i16, Reader};
+use crate::sfnt::{Face, Glyf};
+use crate::{Content, FontError, Rect, Transform};
+
+/// Converts glyph outlines of a TrueType or OpenType font into paths.
+///
+/// This is useful to draw text as vector shapes, e.g. to outline all text for
+/// prepress or in the content of [Type 3 glyphs](Content::start_shape_glyph).
+/// Outlines come from the `glyf`, `CFF ` or `CFF2` table. Quadratic TrueType
+/// curves are converted to cubic ones exactly. Hinting is not applied and
+/// variable fonts are drawn in their default instance.
+///
+/// ```no_run
+/// use pdf_writer::{Content, OutlineFont, Transform};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("NotoSans-Regular.ttf")?;
+/// let font = OutlineFont::new(&data, 0)?;
+///
+/// // Draw "Hi" at 12pt with the baseline at (72, 720).
+/// let size = 12.0;
+/// let scale = size / f32::from(font.units_per_em());
+/// let mut x = 72.0;
+/// let mut content = Content::new();
+/// for c in "Hi".chars() {
+///     let glyph = font.glyph_id(c).unwrap_or(0);
+///     let ts = Transform::scale(scale, scale).then(Transform::translate(x, 720.0));
+///     font.draw_glyph(&mut content, glyph, ts);
+///     x += font.glyph_width(glyph).unwrap_or(0.0) / 1000.0 * size;
+/// }
+/// content.fill_nonzero();
+/// # Ok(())
+/// # }
+/// ```
+pub struct OutlineFont<'a> {
+    face: Face<'a>,
+    outlines: Outlines<'a>,
+}
+
+impl<'a> OutlineFont<'a> {
+    /// Parse a font from TrueType or OpenType data.
+    ///
+    /// For font collections, `index` selects the face. For all other fonts, it
+    /// must be zero.
+    pub fn new(data: &'a [u8], index: u32) -> Result<Self, FontError> {
+        let face = Face::parse(data, index)?;
+        let outlines = Outlines::parse(&face)?;
+        Ok(Self { face, outlines })
+    }
+
+    /// The number of glyphs in the font.
+    pub fn num_glyphs(&self) -> u16 {
+        self.face.num_glyphs()
+    }
+
+    /// The number of font units per em, which outlines are measured in.
+    pub fn units_per_em(&self) -> u16 {
+        self.face.units_per_em()
+    }
+
+    /// The glyph for a character according to the font's `cmap` table.
+    pub fn glyph_id(&self, c: char) -> Option<u16> {
+        self.face.glyph_id(c)
+    }
+
+    /// The advance width of a glyph in thousandths of an em, that is, in text
+    /// space units for a font size of one.
+    pub fn glyph_width(&self, glyph: u16) -> Option<f32> {
+        self.face.advance(glyph).map(|advance| self.face.to_em(advance))
+    }
+
+    /// Append the outline of a glyph to the current path.
+    ///
+    /// The `transform` maps from font units to the user space of the content
+    /// stream. Each contour becomes a closed subpath; the caller paints the
+    /// path, typically with [`fill_nonzero`](Content::fill_nonzero).
+    ///
+    /// Returns `false` and writes nothing if the font has no such glyph or if
+    /// its outline is malformed.
+    pub fn draw_glyph(
+        &self,
+        content: &mut Content,
+        glyph: u16,
+        transform: impl Into<[f32; 6]>,
+    ) -> bool {
+        let Some(segments) = self.outlines.outline(glyph) else { return false };
+        let transform = Transform::from(transform.into());
+        let segments: Vec<_> = segments
+            .into_iter()
+            .map(|segment| segment.transform(transform))
+            .collect();
+        write_path(content, &segments);
+        true
+    }
+
+    /// The bounding box of a glyph's outline after applying a transform.
+    ///
+    /// This is the box of the outline's points, including the control points
+    /// of curves, so it may be slightly larger than the exact bounds. Returns
+    /// `None` for glyphs without an outline.
+    pub fn glyph_bbox(&self, glyph: u16, transform: impl Into<[f32; 6]>) -> Option<Rect> {
+        let segments = self.outlines.outline(glyph)?;
+        let [x1, y1, x2, y2] =
+            outline_bbox(&segments, Transform::from(transform.into()))?;
+        Some(Rect::new(x1, y1, x2, y2))
+    }
+}
+
+/// A segment of a glyph outline in font units.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum Segment {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    QuadTo(f32, f32, f32, f32),
+    CubicTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+impl Segment {
+    /// Apply an affine transform to the points of the segment.
+    fn transform(self, ts: Transform) -> Self {
+        match self {
+            Self::MoveTo(x, y) => {
+                let (x, y) = ts.map_point(x, y);
+                Self::MoveTo(x, y)
+            }
+            Self::LineTo(x, y) => {
+                let (x, y) = ts.map_point(x, y);
+                Self::LineTo(x, y)
+            }
+            Self::QuadTo(x1, y1, x, y) => {
+                let (x1, y1) = ts.map_point(x1, y1);
+                let (x, y) = ts.map_point(x, y);
+                Self::QuadTo(x1, y1, x, y)
+            }
+            Self::CubicTo(x1, y1, x2, y2, x, y) => {
+                let (x1, y1) = ts.map_point(x1, y1);
+                let (x2, y2) = ts.map_point(x2, y2);
+                let (x, y) = ts.map_point(x, y);
+                Self::CubicTo(x1, y1, x2, y2, x, y)
+            }
+            Self::Close => Self::Close,
+        }
+    }
+}
+
+/// The glyph outlines of a font, from the `glyf`, `CFF ` or `CFF2` table.
+#[derive(Debug, Clone)]
+pub(crate) enum Outlines<'a> {
+    Glyf(Glyf<'a>),
+    Cff(Cff<'a>),
+    Cff2(Cff2<'a>),
+}
+
+impl<'a> Outlines<'a> {
+    /// Find the outlines of a face.
+    pub(crate) fn parse(face: &Face<'a>) -> Result<Self, FontError> {
+        match face.table(b"CFF ") {
+            Some(table) => Ok(Self::Cff(Cff::parse(table)?)),
+            None => match face.table(b"CFF2") {
+                Some(table) => Ok(Self::Cff2(Cff2::parse(table)?)),
+                None => Ok(Self::Glyf(Glyf::parse(face)?)),
+            },
+        }
+    }
+
+    /// The outline of a glyph. Empty for glyphs without an outline and `None`
+    /// if the glyph doesn't exist or is malformed.
+    pub(crate) fn outline(&self, glyph: u16) -> Option<Vec<Segment>> {
+        let mut segments = vec![];
+        match self {
+            Self::Glyf(glyf) => {
+                glyf_outline(glyf, glyph, Transform::identity(), 0, &mut segments)?
+            }
+            Self::Cff(cff) => cff.outline(glyph, &mut segments)?,
+            Self::Cff2(cff2) => cff2.outline(glyph, &mut segments)?,
+        }
+        Some(segments)
+    }
+}
+
+/// Write the segments of an outline as a path.
+pub(crate) fn write_path(content: &mut Content, segments: &[Segment]) {
+    for &segment in segments {
+        match segment {
+            Segment::MoveTo(x, y) => content.move_to(x, y),
+            Segment::LineTo(x, y) => content.line_to(x, y),
+            Segment::QuadTo(x1, y1, x, y) => content.quad_to(x1, y1, x, y),
+            Segment::CubicTo(x1, y1, x2, y2, x, y) => {
+                content.cubic_to(x1, y1, x2, y2, x, y)
+            }
+            Segment::Close => content.close_path(),
+        };
+    }
+}
+
+/// The bounding box of the points of an outline after applying a transform.
+///
+/// Includes control points, so it may be slightly too large for curves.
+pub(crate) fn outline_bbox(segments: &[Segment], ts: Transform) -> Option<[f32; 4]> {
+    let mut bbox: Option<[f32; 4]> = None;
+    let mut add = |x: f32, y: f32| {
+        let (x, y) = ts.map_point(x, y);
+        let b = bbox.get_or_insert([x, y, x, y]);
+        *b = [b[0].min(x), b[1].min(y), b[2].max(x), b[3].max(y)];
+    };
+    for &segment in segments {
+        match segment {
+            Segment::MoveTo(x, y) | Segment::LineTo(x, y) => add(x, y),
+            Segment::QuadTo(x1, y1, x, y) => {
+                add(x1, y1);
+                add(x, y);
+            }
+            Segment::CubicTo(x1, y1, x2, y2, x, y) => {
+                add(x1, y1);
+                add(x2, y2);
+                add(x, y);
+            }
+            Segment::Close => {}
+        }
+    }
+    bbox
+}
+
+/// Decode a TrueType glyph, resolving composite glyphs recursively.
+fn glyf_outline(
+    glyf: &Glyf,
+    glyph: u16,
+    ts: Transform,
+    depth: u8,
+    segments: &mut Vec<Segment>,
+) -> Option<()> {
+    if depth > 8 {
+        return None;
+    }
+
+    let data = glyf.glyph(glyph)?;
+    if data.is_empty() {
+        return Some(());
+    }
+
+    let contours = read_i16(data, 0)?;
+    if contours < 0 {
+        return glyf_composite(glyf, data, ts, depth, segments);
+    }
+
+    let mut r = Reader::at(data, 10);
+    let mut ends = Vec::with_capacity(contours as usize);
+    for _ in 0..contours {
+        ends.push(usize::from(r.u16()?));
+    }
+    let count = ends.last().map_or(0, |&last| last + 1);
+    let instructions = usize::from(r.u16()?);
+    r.skip(instructions)?;
+
+    // Flags with the repeat count expanded.
+    let mut flags = Vec::with_capacity(count);
+    while flags.len() < count {
+        let flag = r.u8()?;
+        flags.push(flag);
+        if flag & 0x08 != 0 {
+            let repeat = r.u8()?;
+            flags.extend(std::iter::repeat_n(flag, repeat.into()));
+        }
+    }
+    flags.truncate(count);
+
+    let mut read_coords = |short: u8, same: u8| -> Option<Vec<f32>> {
+        let mut value = 0_i32;
+        let mut coords = Vec::with_capacity(count);
+        for &flag in &flags {
+            if flag & short != 0 {
+                let delta = i32::from(r.u8()?);
+                value += if flag & same != 0 { delta } else { -delta };
+            } else if flag & same == 0 {
+                value += i32::from(r.i16()?);
+            }
+            coords.push(value as f32);
+        }
+        Some(coords)
+    };
+    let xs = read_coords(0x02, 0x10)?;
+    let ys = read_coords(0x04, 0x20)?;
+
+    let mut start = 0;
+    for end in ends {
+        if end < start || end >= count {
+            return None;
+        }
+        let points: Vec<(f32, f32, bool)> = (start..=end)
+            .map(|i| {
+                let (x, y) = ts.map_point(xs[i], ys[i]);
+                (x, y, flags[i] & 0x01 != 0)
+            })
+            .collect();
+        contour_segments(&points, segments);
+        start = end + 1;
+    }
+
+    Some(())
+}
+
+/// Convert the points of a TrueType contour into segments.
+fn contour_segments(points: &[(f32, f32, bool)], segments: &mut Vec<Segment>) {
+    let Some(&first) = points.first() else { return };
+    let last = points[points.len() - 1];
+    let mid = |a: (f32, f32, bool), b: (f32, f32, bool)| {
+        ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0, true)
+    };
+
+    // Start at an on-curve point, which may be implied.
+    let (start, rest) = if first.2 {
+        (first, &points[1..])
+    } else if last.2 {
+        (last, &points[..points.len() - 1])
+    } else {
+        (mid(last, first), points)
+    };
+
+    segments.push(Segment::MoveTo(start.0, start.1));
+    let mut control: Option<(f32, f32, bool)> = None;
+    for (i, &point) in rest.iter().chain([&start]).enumerate() {
+        match (control, point.2) {
+            // The closing line to the start is implied by closing the path.
+            (None, true) if i == rest.len() => {}
+            (None, true) => segments.push(Segment::LineTo(point.0, point.1)),
+            (None, false) => control = Some(point),
+            (Some(c), true) => {
+                segments.push(Segment::QuadTo(c.0, c.1, point.0, point.1));
+                control = None;
+            }
+            (Some(c), false) => {
+                let m = mid(c, point);
+                segments.push(Segment::QuadTo(c.0, c.1, m.0, m.1));
+                control = Some(point);
+            }
+        }
+    }
+    segments.push(Segment::Close);
+}
+
+/// Decode the components of a composite TrueType glyph.
+fn glyf_composite(
+    glyf: &Glyf,
+    data: &[u8],
+    ts: Transform,
+    depth: u8,
+    segments: &mut Vec<Segment>,
+) -> Option<()> {
+    let mut r = Reader::at(data, 10);
+    loop {
+        let flags = r.u16()?;
+        let glyph = r.u16()?;
+        let (dx, dy) = match (flags & 0x0001 != 0, flags & 0x0002 != 0) {
+            (true, true) => (f32::from(r.i16()?), f32::from(r.i16()?)),
+            (false, true) => (f32::from(r.u8()? as i8), f32::from(r.u8()? as i8)),
+            // Point matching is not supported, the component stays in place.
+            (true, false) => {
+                r.skip(4)?;
+                (0.0, 0.0)
+            }
+            (false, false) => {
+                r.skip(2)?;
+                (0.0, 0.0)
+            }
+        };
+
+        let f2dot14 = |r: &mut Reader| r.i16().map(|v| f32::from(v) / 16384.0);
+        let [a, b, c, d] = if flags & 0x0008 != 0 {
+            let scale = f2dot14(&mut r)?;
+            [scale, 0.0, 0.0, scale]
+        } else if flags & 0x0040 != 0 {
+            [f2dot14(&mut r)?, 0.0, 0.0, f2dot14(&mut r)?]
+        } else if flags & 0x0080 != 0 {
+            [f2dot14(&mut r)?, f2dot14(&mut r)?, f2dot14(&mut r)?, f2dot14(&mut r)?]
+        } else {
+            [1.0, 0.0, 0.0, 1.0]
+        };
+
+        let component = Transform::new(a, b, c, d, dx, dy).then(ts);
+        glyf_outline(glyf, glyph, component, depth + 1, segments)?;
+
+        if flags & 0x0020 == 0 {
+            return Some(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cff::fixture::{cff, push};
+    use crate::sfnt::fixture::{be, truetype_font};
+
+    /// The outline of each glyph of a name-keyed CFF font.
+    fn cff_outlines(
+        char_strings: &[&[u8]],
+        global_subrs: &[&[u8]],
+        local_subrs: &[&[u8]],
+    ) -> Vec<Vec<Segment>> {
+        let data = cff(char_strings, global_subrs, &[local_subrs], &[]);
+        let cff = Cff::parse(&data).unwrap();
+        (0..char_strings.len() as u16)
+            .map(|glyph| {
+                let mut segments = vec![];
+                cff.outline(glyph, &mut segments).unwrap();
+                segments
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_contour_implied_points() {
+        // Two off-curve points in a row imply an on-curve point between them,
+        // which is where the contour starts here.
+        let points = [
+            (0.0, 100.0, false),
+            (0.0, 0.0, true),
+            (100.0, 0.0, false),
+            (100.0, 100.0, false),
+        ];
+        let mut segments = vec![];
+        contour_segments(&points, &mut segments);
+        assert_eq!(
+            segments,
+            [
+                Segment::MoveTo(50.0, 100.0),
+                Segment::QuadTo(0.0, 100.0, 0.0, 0.0),
+                Segment::QuadTo(100.0, 0.0, 100.0, 50.0),
+                Segment::QuadTo(100.0, 100.0, 50.0, 100.0),
+                Segment::Close,
+            ]
+        );
+
+        let mut content = Content::new();
+        let ts = Transform::scale(2.0, 2.0).then(Transform::translate(10.0, 0.0));
+        let transformed: Vec<_> = segments.iter().map(|s| s.transform(ts)).collect();
+        write_path(&mut content, &transformed[..2]);
+        assert_eq!(
+            content.finish().into_vec(),
+            b"110 200 m\n43.333332 200 10 133.33333 10 0 c"
+        );
+        assert_eq!(outline_bbox(&segments, ts), Some([10.0, 0.0, 210.0, 200.0]));
+    }
+
+    #[test]
+    fn test_cff2_blend() {
+        let mut table = vec![2, 0, 5, 0, 19];
+        for (offset, op) in [(66, &[17][..]), (52, &[12, 36]), (28, &[24])] {
+            table.push(29);
+            table.extend(i32::to_be_bytes(offset));
+            table.extend(op);
+        }
+
+        // An empty global subroutine INDEX and a variation store with one
+        // item variation data with two regions.
+        table.extend([0, 0, 0, 0]);
+        table.extend([0, 22, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 12]);
+        table.extend([0, 0, 0, 0, 0, 2, 0, 0, 0, 1]);
+
+        // A Font DICT with an empty Private DICT.
+        table.extend([0, 0, 0, 1, 1, 1, 8, 139, 29, 0, 0, 0, 52, 18]);
+
+        // 100 200 rmoveto, 300 10 20 1 blend 0 rlineto, 0 400 rlineto
+        let code =
+            [239, 247, 92, 21, 247, 192, 149, 159, 140, 16, 139, 5, 139, 248, 36, 5];
+        table.extend([0, 0, 0, 1, 1, 1, 17]);
+        table.extend(code);
+
+        let cff2 = Cff2::parse(&table).unwrap();
+        let mut segments = vec![];
+        cff2.outline(0, &mut segments).unwrap();
+        assert_eq!(
+            segments,
+            [
+                Segment::MoveTo(100.0, 200.0),
+                Segment::LineTo(400.0, 200.0),
+                Segment::LineTo(400.0, 600.0),
+                Segment::Close,
+            ]
+        );
+        assert!(cff2.outline(1, &mut segments).is_none());
+    }
+
+    #[test]
+    fn test_cff_subr_bias() {
+        // The bias that is added to subroutine numbers depends on the number
+        // of subroutines.
+        let mut local = vec![];
+        push(&mut local, &[100, 0], &[5, 11]);
+        let mut global = vec![];
+        push(&mut global, &[0, 100], &[5, 11]);
+        for (count, bias) in [(1239, 107), (1240, 1131), (33899, 1131), (33900, 32768)] {
+            let mut locals = vec![[11].as_slice(); count];
+            locals[0] = &local;
+            let mut globals = locals.clone();
+            globals[0] = &global;
+
+            let mut code = vec![];
+            push(&mut code, &[0, 0], &[21]);
+            push(&mut code, &[-bias], &[10]);
+            push(&mut code, &[-bias], &[29, 14]);
+            let outlines = cff_outlines(&[&code], &globals, &locals);
+            assert_eq!(
+                outlines[0],
+                [
+                    Segment::MoveTo(0.0, 0.0),
+                    Segment::LineTo(100.0, 0.0),
+                    Segment::LineTo(100.0, 100.0),
+                    Segment::Close,
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_cff_hint_masks() {
+        // A width and five horizontal stems, then four implicit vertical stems
+        // before the hint mask. With nine stems, the masks have two bytes,
+        // which would be operators if they weren't skipped.
+        let mut code = vec![];
+        push(&mut code, &[500, 0, 10, 20, 10, 40, 10, 60, 10, 80, 10], &[18]);
+        push(&mut code, &[0, 10, 20, 10, 40, 10, 60, 10], &[19, 21, 14]);
+        code.extend([20, 5, 14]);
+        push(&mut code, &[100, 200], &[21]);
+        push(&mut code, &[50], &[6, 14]);
+
+        let outlines = cff_outlines(&[&code], &[], &[]);
+        assert_eq!(
+            outlines[0],
+            [
+                Segment::MoveTo(100.0, 200.0),
+                Segment::LineTo(150.0, 200.0),
+                Segment::Close
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cff_width() {
+        // The width is an optional first operand of the first stack-clearing
+        // operator.
+        let mut rmoveto = vec![];
+        push(&mut rmoveto, &[500, 10, 20], &[21]);
+        push(&mut rmoveto, &[30, 40], &[21, 14]);
+        let mut hmoveto = vec![];
+        push(&mut hmoveto, &[500, 10], &[22, 14]);
+        let mut vmoveto = vec![];
+        push(&mut vmoveto, &[500, 10], &[4, 14]);
+        let mut without = vec![];
+        push(&mut without, &[10, 20], &[21, 14]);
+        let mut endchar = vec![];
+        push(&mut endchar, &[500], &[14]);
+
+        let outlines =
+            cff_outlines(&[&rmoveto, &hmoveto, &vmoveto, &without, &endchar], &[], &[]);
+        assert_eq!(
+            outlines,
+            [
+                vec![
+                    Segment::MoveTo(10.0, 20.0),
+                    Segment::Close,
+                    Segment::MoveTo(40.0, 60.0),
+                    Segment::Close,
+                ],
+                vec![Segment::MoveTo(10.0, 0.0), Segment::Close],
+                vec![Segment::MoveTo(0.0, 10.0), Segment::Close],
+                vec![Segment::MoveTo(10.0, 20.0), Segment::Close],
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cff_flex() {
+        let cases: [(&[i32], u8, [f32; 12]); 4] = [
+            (
+                &[10, 20, 30, 40, 50, 60, 70, 80, 90, 100, 110, 120, 50],
+                35,
+                [10., 20., 40., 60., 90., 120., 160., 200., 250., 300., 360., 420.],
+            ),
+            (
+                &[10, 20, 30, 40, 50, 60, 70],
+                34,
+                [10., 0., 30., 30., 70., 30., 120., 30., 180., 0., 250., 0.],
+            ),
+            (
+                &[10, 5, 20, 10, 30, 40, 20, -10, 50],
+                36,
+                [10., 5., 30., 15., 60., 15., 100., 15., 120., 5., 170., 0.],
+            ),
+            (
+                &[10, 10, 20, 20, 30, 0, 30, 0, 20, -20, 40],
+                37,
+                [10., 10., 30., 30., 60., 30., 90., 30., 110., 10., 150., 0.],
+            ),
+        ];
+
+        for (operands, op, [a, b, c, d, e, f, g, h, i, j, k, l]) in cases {
+            let mut code = vec![];
+            push(&mut code, &[0, 0], &[21]);
+            push(&mut code, operands, &[12, op, 14]);
+            let outlines = cff_outlines(&[&code], &[], &[]);
+            assert_eq!(
+                outlines[0],
+                [
+                    Segment::MoveTo(0.0, 0.0),
+                    Segment::CubicTo(a, b, c, d, e, f),
+                    Segment::CubicTo(g, h, i, j, k, l),
+                    Segment::Close,
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_glyf_composite() {
+        // Glyph 1 is a rectangle. Glyph 2 has it once scaled by one half and
+        // moved by (100, 50) and once rotated by 90 degrees and moved by
+        // (10, -20) with byte offsets.
+        let mut glyf = be(&[1, 0, 0, 600, 700, 3, 0], 2);
+        glyf.extend([1, 1, 1, 1]);
+        glyf.extend(be(&[0, 600, 0, -600, 0, 0, 700, 0], 2));
+        glyf.extend([0, 0]);
+        glyf.extend(be(&[-1, 0, 0, 0, 0], 2));
+        glyf.extend(be(&[0x002B, 1, 100, 50, 0x2000], 2));
+        glyf.extend(be(&[0x0082, 1], 2));
+        glyf.extend([10, -20_i8 as u8]);
+        glyf.extend(be(&[0, 0x4000, -0x4000, 0], 2));
+        let loca = be(&[0, 0, 18, 35, 35], 2);
+
+        let data = truetype_font([500, 600, 700], &glyf, &loca, &[]);
+        let font = OutlineFont::new(&data, 0).unwrap();
+        let rect = |points: [(f32, f32); 4]| {
+            let [(x0, y0), (x1, y1), (x2, y2), (x3, y3)] = points;
+            [
+                Segment::MoveTo(x0, y0),
+                Segment::LineTo(x1, y1),
+                Segment::LineTo(x2, y2),
+                Segment::LineTo(x3, y3),
+                Segment::Close,
+            ]
+        };
+        let expected: Vec<_> =
+            rect([(100.0, 50.0), (400.0, 50.0), (400.0, 400.0), (100.0, 400.0)])
+                .into_iter()
+                .chain(rect([
+                    (10.0, -20.0),
+                    (10.0, 580.0),
+                    (-690.0, 580.0),
+                    (-690.0, -20.0),
+                ]))
+                .collect();
+        assert_eq!(font.outlines.outline(2).unwrap(), expected);
+        assert_eq!(
+            font.glyph_bbox(2, Transform::identity()),
+            Some(Rect::new(-690.0, -20.0, 400.0, 580.0))
+        );
+    }
+}
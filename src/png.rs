@@ -0,0 +1,756 @@
+use std::borrow::Cow;
+
+use super::*;
+use crate::deflate::{self, Deflate};
+use crate::image::ImageError;
+use crate::inflate::inflate_zlib;
+use crate::reader::read_u32;
+use crate::types::Predictor;
+
+/// A PNG image for embedding as an image XObject.
+///
+/// The compressed `IDAT` data of a PNG file already is a zlib stream with PNG
+/// predictors, which PDF understands through [`Filter::FlateDecode`] with
+/// [decode parameters](crate::writers::DecodeParms::predictor). For images
+/// without an alpha channel that are not interlaced, it is therefore embedded
+/// as is, without decoding and recompressing it.
+///
+/// Palettes are written as `Indexed` color spaces, embedded ICC profiles as
+/// `ICCBased` color spaces and a `gAMA` chunk without a profile as a `CalRGB`
+/// or `CalGray` color space. Transparency from a `tRNS` chunk becomes a color
+/// key mask where possible. Only images with an alpha channel, palettes with
+/// partial transparency and interlaced images are decoded, which is done
+/// eagerly in [`new`](Self::new). Their alpha is written as a soft mask.
+///
+/// ```no_run
+/// use pdf_writer::{Pdf, PngImage, Ref};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("image.png")?;
+/// let image = PngImage::new(&data)?;
+///
+/// let mut pdf = Pdf::new();
+/// let mut alloc = Ref::new(10);
+/// image.write(&mut pdf, Ref::new(1), &mut alloc);
+/// # Ok(())
+/// # }
+/// ```
+pub struct PngImage {
+    header: Header,
+    /// The RGB triples of the `PLTE` chunk.
+    palette: Vec<u8>,
+    /// The contents of the `tRNS` chunk.
+    transparency: Option<Vec<u8>>,
+    /// The compressed profile of the `iCCP` chunk.
+    icc: Option<Vec<u8>>,
+    /// The gamma of the `gAMA` chunk times 100000, unless superseded.
+    gamma: Option<u32>,
+    /// The concatenated `IDAT` chunks.
+    data: Vec<u8>,
+    /// The unfiltered color samples, if the data can't be passed through.
+    samples: Option<Vec<u8>>,
+    /// The samples of the soft mask, if the image has partial transparency.
+    alpha: Option<Vec<u8>>,
+    deflate: Option<Deflate>,
+}
+
+/// The contents of the `IHDR` chunk.
+#[derive(Debug, Copy, Clone)]
+struct Header {
+    width: u32,
+    height: u32,
+    depth: u8,
+    color_type: u8,
+    interlaced: bool,
+}
+
+/// The PNG file signature.
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// The D65 white point used for calibrated color spaces.
+const D65: [f32; 3] = [0.9505, 1.0, 1.089];
+
+/// The matrix from linear sRGB to XYZ.
+const SRGB_MATRIX: [f32; 9] =
+    [0.4124, 0.2126, 0.0193, 0.3576, 0.7152, 0.1192, 0.1805, 0.0722, 0.9505];
+
+/// The origin and spacing of the seven Adam7 passes.
+const ADAM7: [(usize, usize, usize, usize); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+impl PngImage {
+    /// Parse a PNG image.
+    ///
+    /// Images that can't be passed through are decoded right away, so that
+    /// any errors in their data surface here.
+    pub fn new(data: &[u8]) -> Result<Self, ImageError> {
+        let mut rest = data.strip_prefix(SIGNATURE).ok_or(ImageError::UnknownFormat)?;
+        let mut header = None;
+        let mut palette = vec![];
+        let mut transparency = None;
+        let mut icc = None;
+        let mut gamma = None;
+        let mut srgb = false;
+        let mut idat = vec![];
+
+        loop {
+            let malformed = ImageError::Malformed("chunk structure");
+            let len = read_u32(rest, 0).ok_or(malformed)? as usize;
+            let kind: [u8; 4] = rest.get(4..8).ok_or(malformed)?.try_into().unwrap();
+            let body = rest.get(8..).and_then(|r| r.get(..len)).ok_or(malformed)?;
+            rest = rest.get(12 + len..).ok_or(malformed)?;
+
+            match &kind {
+                b"IHDR" if header.is_none() => header = Some(Header::parse(body)?),
+                _ if header.is_none() => return Err(ImageError::Malformed("IHDR chunk")),
+                b"IEND" => break,
+                b"PLTE" => {
+                    if body.is_empty() || body.len() % 3 != 0 || body.len() > 768 {
+                        return Err(ImageError::Malformed("PLTE chunk"));
+                    }
+                    palette = body.to_vec();
+                }
+                b"tRNS" => transparency = Some(body.to_vec()),
+                b"iCCP" => {
+                    let malformed = ImageError::Malformed("iCCP chunk");
+                    let nul = memchr::memchr(0, body).ok_or(malformed)?;
+                    if body.get(nul + 1) != Some(&0) {
+                        return Err(malformed);
+                    }
+                    icc = Some(body[nul + 2..].to_vec());
+                }
+                b"gAMA" => gamma = read_u32(body, 0).filter(|&gamma| gamma > 0),
+                b"sRGB" => srgb = true,
+                b"IDAT" => idat.extend_from_slice(body),
+                _ if kind[0] & 0x20 == 0 => {
+                    return Err(ImageError::Unsupported("critical chunk"));
+                }
+                _ => {}
+            }
+        }
+
+        let header = header.unwrap();
+        if idat.is_empty() {
+            return Err(ImageError::Malformed("image data"));
+        }
+
+        if header.color_type == 3 && palette.is_empty() {
+            return Err(ImageError::Malformed("PLTE chunk"));
+        }
+
+        let valid_transparency = match (&transparency, header.color_type) {
+            (None, _) => true,
+            (Some(trns), 0) => trns.len() == 2,
+            (Some(trns), 2) => trns.len() == 6,
+            (Some(trns), 3) => trns.len() <= palette.len() / 3,
+            _ => false,
+        };
+
+        if !valid_transparency {
+            return Err(ImageError::Malformed("tRNS chunk"));
+        }
+
+        // A profile or an sRGB chunk takes precedence over the gamma.
+        if srgb || icc.is_some() {
+            gamma = None;
+        }
+
+        let mut image = Self {
+            header,
+            palette,
+            transparency,
+            icc,
+            gamma,
+            data: idat,
+            samples: None,
+            alpha: None,
+            deflate: None,
+        };
+
+        let palette_alpha = header.color_type == 3
+            && image
+                .transparency
+                .as_ref()
+                .is_some_and(|t| t.iter().any(|&a| a < 255))
+            && image.color_key().is_none();
+        if header.interlaced || header.has_alpha() || palette_alpha {
+            image.decode()?;
+        }
+
+        Ok(image)
+    }
+
+    /// Compress decoded samples with a [Deflate function](crate#compression).
+    ///
+    /// Samples that were passed through are already compressed. Without this,
+    /// decoded samples are written uncompressed.
+    pub fn deflate(&mut self, deflate: fn(&[u8]) -> Vec<u8>) -> &mut Self {
+        self.deflate = Some(deflate);
+        self
+    }
+
+    /// The width of the image in pixels.
+    pub fn width(&self) -> u32 {
+        self.header.width
+    }
+
+    /// The height of the image in pixels.
+    pub fn height(&self) -> u32 {
+        self.header.height
+    }
+
+    /// The number of bits per sample of the image.
+    pub fn bit_depth(&self) -> u8 {
+        self.header.depth
+    }
+
+    /// Whether the image is written with a soft mask.
+    pub fn has_alpha(&self) -> bool {
+        self.alpha.is_some()
+    }
+
+    /// Whether the `IDAT` data of the image is embedded without decoding it.
+    pub fn is_passthrough(&self) -> bool {
+        self.samples.is_none()
+    }
+
+    /// Write the image XObject.
+    ///
+    /// IDs for the ICC profile and the soft mask are taken from `alloc` if the
+    /// image needs them.
+    pub fn write(&self, chunk: &mut Chunk, id: Ref, alloc: &mut Ref) {
+        let icc_id = self.icc.as_ref().map(|_| alloc.bump());
+        let mask_id = self.alpha.as_ref().map(|_| alloc.bump());
+        let header = self.header;
+
+        let data = match &self.samples {
+            Some(samples) => deflate::compress(self.deflate, samples),
+            None => Cow::Borrowed(self.data.as_slice()),
+        };
+
+        let mut image = chunk.image_xobject(id, &data);
+        image.width(header.width as i32);
+        image.height(header.height as i32);
+
+        if header.color_type == 3 {
+            let mut array = image.insert(Name(b"ColorSpace")).array();
+            array.item(Name(b"Indexed"));
+            self.write_base_space(ColorSpace::start(array.push()), icc_id);
+            array.item(self.palette.len() as i32 / 3 - 1);
+            array.item(Str(&self.palette));
+        } else {
+            self.write_base_space(image.color_space(), icc_id);
+        }
+
+        image.bits_per_component(header.depth.into());
+        if self.samples.is_none() {
+            image.filter(Filter::FlateDecode);
+            image
+                .decode_parms()
+                .predictor(Predictor::PngOptimum)
+                .colors(header.color_channels() as i32)
+                .bits_per_component(header.depth.into())
+                .columns(header.width as i32);
+        } else {
+            deflate::filter(self.deflate, &mut image);
+        }
+
+        if let Some(key) = self.color_key() {
+            image.color_mask(key);
+        }
+
+        if let Some(mask_id) = mask_id {
+            image.s_mask(mask_id);
+        }
+
+        image.finish();
+
+        if let (Some(icc_id), Some(icc)) = (icc_id, &self.icc) {
+            let mut profile = chunk.icc_profile(icc_id, icc);
+            profile.filter(Filter::FlateDecode);
+            if header.color_type & 2 != 0 {
+                profile.n(3).alternate().device_rgb();
+            } else {
+                profile.n(1).alternate().device_gray();
+            }
+        }
+
+        if let (Some(mask_id), Some(alpha)) = (mask_id, &self.alpha) {
+            let data = deflate::compress(self.deflate, alpha);
+            let mut mask = chunk.image_xobject(mask_id, &data);
+            mask.width(header.width as i32);
+            mask.height(header.height as i32);
+            mask.color_space().device_gray();
+            mask.bits_per_component(if header.has_alpha() {
+                header.depth.into()
+            } else {
+                8
+            });
+            deflate::filter(self.deflate, &mut mask);
+        }
+    }
+
+    /// Write the color space of the samples or, for palette images, of the
+    /// palette entries.
+    fn write_base_space(&self, space: ColorSpace, icc_id: Option<Ref>) {
+        let rgb = self.header.color_type & 2 != 0;
+        if let Some(icc_id) = icc_id {
+            space.icc_based(icc_id);
+        } else if let Some(gamma) = self.gamma {
+            let gamma = 100000.0 / gamma as f32;
+            if rgb {
+                space.cal_rgb(D65, None, Some([gamma; 3]), Some(SRGB_MATRIX));
+            } else {
+                space.cal_gray(D65, None, Some(gamma));
+            }
+        } else if rgb {
+            space.device_rgb();
+        } else {
+            space.device_gray();
+        }
+    }
+
+    /// The color key mask for the `tRNS` chunk, if it can be expressed as one.
+    fn color_key(&self) -> Option<Vec<i32>> {
+        let trns = self.transparency.as_ref()?;
+        if self.header.color_type != 3 {
+            return Some(
+                trns.chunks_exact(2)
+                    .map(|pair| i32::from(u16::from_be_bytes([pair[0], pair[1]])))
+                    .flat_map(|value| [value, value])
+                    .collect(),
+            );
+        }
+
+        // A palette with a single fully transparent entry.
+        let mut translucent = trns.iter().enumerate().filter(|(_, &alpha)| alpha < 255);
+        match (translucent.next(), translucent.next()) {
+            (Some((index, 0)), None) => Some(vec![index as i32; 2]),
+            _ => None,
+        }
+    }
+
+    /// Decode the image data into samples and alpha.
+    fn decode(&mut self) -> Result<(), ImageError> {
+        let header = self.header;
+        let malformed = ImageError::Malformed("image data");
+        let len = header.data_len().ok_or(malformed)?;
+        let data = inflate_zlib(&self.data, len).ok_or(malformed)?;
+        let pixels = header.unfilter(&data).ok_or(malformed)?;
+
+        if header.has_alpha() {
+            let bytes = usize::from(header.depth / 8);
+            let split = (header.channels() - 1) * bytes;
+            let mut color = Vec::with_capacity(pixels.len() / 4 * 3);
+            let mut alpha = Vec::with_capacity(pixels.len() / 4);
+            for pixel in pixels.chunks_exact(split + bytes) {
+                color.extend_from_slice(&pixel[..split]);
+                alpha.extend_from_slice(&pixel[split..]);
+            }
+
+            self.samples = Some(color);
+            self.alpha = Some(alpha).filter(|alpha| alpha.iter().any(|&a| a != 255));
+            return Ok(());
+        }
+
+        if header.color_type == 3 {
+            let trns = self.transparency.as_deref().unwrap_or_default();
+            let stride = header.stride(header.width as usize);
+            let bits = usize::from(header.depth);
+            let mut alpha =
+                Vec::with_capacity(header.width as usize * header.height as usize);
+            for row in pixels.chunks_exact(stride) {
+                for x in 0..header.width as usize {
+                    let index = usize::from(read_sample(row, x, bits));
+                    alpha.push(trns.get(index).copied().unwrap_or(255));
+                }
+            }
+
+            self.alpha = Some(alpha);
+        }
+
+        if header.interlaced {
+            self.samples = Some(pixels);
+        }
+
+        Ok(())
+    }
+}
+
+impl Header {
+    fn parse(data: &[u8]) -> Result<Self, ImageError> {
+        let malformed = ImageError::Malformed("IHDR chunk");
+        let width = read_u32(data, 0).ok_or(malformed)?;
+        let height = read_u32(data, 4).ok_or(malformed)?;
+        let bytes = data.get(8..13).ok_or(malformed)?;
+        let [depth, color_type, compression, filter, interlace]: [u8; 5] =
+            bytes.try_into().unwrap();
+
+        let valid_depth = match color_type {
+            0 => matches!(depth, 1 | 2 | 4 | 8 | 16),
+            3 => matches!(depth, 1 | 2 | 4 | 8),
+            2 | 4 | 6 => matches!(depth, 8 | 16),
+            _ => false,
+        };
+
+        if width == 0
+            || height == 0
+            || width > i32::MAX as u32
+            || height > i32::MAX as u32
+            || !valid_depth
+            || compression != 0
+            || filter != 0
+            || interlace > 1
+        {
+            return Err(malformed);
+        }
+
+        Ok(Self {
+            width,
+            height,
+            depth,
+            color_type,
+            interlaced: interlace == 1,
+        })
+    }
+
+    /// Whether the image has an alpha channel.
+    fn has_alpha(self) -> bool {
+        self.color_type & 4 != 0
+    }
+
+    /// The number of samples per pixel, including alpha.
+    fn channels(self) -> usize {
+        match self.color_type {
+            0 | 3 => 1,
+            2 => 3,
+            4 => 2,
+            _ => 4,
+        }
+    }
+
+    /// The number of samples per pixel, excluding alpha.
+    fn color_channels(self) -> usize {
+        self.channels() - usize::from(self.has_alpha())
+    }
+
+    /// The number of bytes in a row of the given width.
+    fn stride(self, width: usize) -> usize {
+        (width * self.channels() * usize::from(self.depth)).div_ceil(8)
+    }
+
+    /// The length of the decompressed image data, in which each row of each
+    /// interlacing pass is preceded by its filter type.
+    fn data_len(self) -> Option<usize> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let bits = self.channels() * usize::from(self.depth);
+        let len = |width: usize, height: usize| {
+            let stride = width.checked_mul(bits)?.div_ceil(8);
+            (stride + 1).checked_mul(height)
+        };
+
+        if !self.interlaced {
+            return len(width, height);
+        }
+
+        ADAM7.into_iter().try_fold(0, |sum: usize, (x0, y0, dx, dy)| {
+            let pass_width = width.saturating_sub(x0).div_ceil(dx);
+            let pass_height = height.saturating_sub(y0).div_ceil(dy);
+            if pass_width == 0 {
+                return Some(sum);
+            }
+            sum.checked_add(len(pass_width, pass_height)?)
+        })
+    }
+
+    /// Undo the row filters and the interlacing of decompressed image data.
+    fn unfilter(self, data: &[u8]) -> Option<Vec<u8>> {
+        // Check the length before allocating, as the header can claim huge
+        // dimensions.
+        if data.len() < self.data_len()? {
+            return None;
+        }
+
+        let (width, height) = (self.width as usize, self.height as usize);
+        let bits = self.channels() * usize::from(self.depth);
+        let stride = self.stride(width);
+        let mut pixels = vec![0; stride.checked_mul(height)?];
+        if !self.interlaced {
+            unfilter_rows(data, bits, stride, &mut pixels)?;
+            return Some(pixels);
+        }
+
+        let mut data = data;
+        for (x0, y0, dx, dy) in ADAM7 {
+            let pass_width = width.saturating_sub(x0).div_ceil(dx);
+            let pass_height = height.saturating_sub(y0).div_ceil(dy);
+            if pass_width == 0 || pass_height == 0 {
+                continue;
+            }
+
+            let pass_stride = self.stride(pass_width);
+            let mut pass = vec![0; pass_stride * pass_height];
+            let used = unfilter_rows(data, bits, pass_stride, &mut pass)?;
+            data = &data[used..];
+
+            for (y, row) in pass.chunks_exact(pass_stride).enumerate() {
+                let start = (y0 + y * dy) * stride;
+                let target = &mut pixels[start..start + stride];
+                for x in 0..pass_width {
+                    copy_pixel(row, x, target, x0 + x * dx, bits);
+                }
+            }
+        }
+
+        Some(pixels)
+    }
+}
+
+/// Undo the row filters of rows with `stride` bytes, writing the result into
+/// `out`. Returns the number of bytes consumed from `data`.
+fn unfilter_rows(
+    data: &[u8],
+    bits: usize,
+    stride: usize,
+    out: &mut [u8],
+) -> Option<usize> {
+    let bpp = bits.div_ceil(8);
+    let rows = out.len() / stride;
+    for y in 0..rows {
+        let src = data.get(y * (stride + 1)..(y + 1) * (stride + 1))?;
+        let (prev, cur) = out.split_at_mut(y * stride);
+        let prev = prev.get(prev.len().saturating_sub(stride)..).unwrap_or_default();
+        let cur = &mut cur[..stride];
+        let filter = src[0];
+        for i in 0..stride {
+            let a = if i >= bpp { cur[i - bpp] } else { 0 };
+            let b = prev.get(i).copied().unwrap_or(0);
+            let c = if i >= bpp { prev.get(i - bpp).copied().unwrap_or(0) } else { 0 };
+            let predicted = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((u16::from(a) + u16::from(b)) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => return None,
+            };
+            cur[i] = src[i + 1].wrapping_add(predicted);
+        }
+    }
+
+    Some(rows * (stride + 1))
+}
+
+/// The Paeth predictor.
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = i16::from(a) + i16::from(b) - i16::from(c);
+    let pa = (p - i16::from(a)).abs();
+    let pb = (p - i16::from(b)).abs();
+    let pc = (p - i16::from(c)).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// Read the `x`-th sample with fewer than eight bits from a row.
+fn read_sample(row: &[u8], x: usize, bits: usize) -> u8 {
+    if bits == 8 {
+        return row[x];
+    }
+
+    let offset = x * bits;
+    (row[offset / 8] >> (8 - bits - offset % 8)) & ((1 << bits) - 1)
+}
+
+/// Copy a pixel with `bits` bits from position `sx` in `src` to position `dx`
+/// in `dst`, whose bits must still be zero.
+fn copy_pixel(src: &[u8], sx: usize, dst: &mut [u8], dx: usize, bits: usize) {
+    if bits >= 8 {
+        let n = bits / 8;
+        dst[dx * n..(dx + 1) * n].copy_from_slice(&src[sx * n..(sx + 1) * n]);
+    } else {
+        let offset = dx * bits;
+        dst[offset / 8] |= read_sample(src, sx, bits) << (8 - bits - offset % 8);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a PNG file from chunks.
+    fn png(chunks: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+        let mut data = SIGNATURE.to_vec();
+        for (kind, body) in chunks {
+            data.extend((body.len() as u32).to_be_bytes());
+            data.extend(*kind);
+            data.extend(*body);
+            data.extend([0; 4]);
+        }
+        data.extend(b"\0\0\0\0IEND\0\0\0\0");
+        data
+    }
+
+    fn ihdr(
+        width: u32,
+        height: u32,
+        depth: u8,
+        color_type: u8,
+        interlace: u8,
+    ) -> Vec<u8> {
+        let mut data = vec![];
+        data.extend(width.to_be_bytes());
+        data.extend(height.to_be_bytes());
+        data.extend([depth, color_type, 0, 0, interlace]);
+        data
+    }
+
+    fn zlib(data: &[u8]) -> Vec<u8> {
+        miniz_oxide::deflate::compress_to_vec_zlib(data, 6)
+    }
+
+    fn write(image: &PngImage) -> String {
+        let mut chunk = Chunk::new();
+        image.write(&mut chunk, Ref::new(1), &mut Ref::new(2));
+        String::from_utf8_lossy(chunk.as_bytes()).into_owned()
+    }
+
+    #[test]
+    fn test_png_passthrough() {
+        // Two RGB rows with the Sub and Up filters.
+        let idat = zlib(&[1, 10, 20, 30, 5, 5, 5, 2, 1, 1, 1, 1, 1, 1]);
+        let data = png(&[
+            (b"IHDR", &ihdr(2, 2, 8, 2, 0)),
+            (b"gAMA", &45455u32.to_be_bytes()),
+            (b"tRNS", &[0, 10, 0, 20, 0, 30]),
+            (b"IDAT", &idat[..5]),
+            (b"IDAT", &idat[5..]),
+        ]);
+
+        let image = PngImage::new(&data).unwrap();
+        assert!(image.is_passthrough());
+        assert!(!image.has_alpha());
+        let pdf = write(&image);
+        assert!(pdf.contains("/ColorSpace [/CalRGB <<"));
+        assert!(pdf.contains("/Gamma [2.199978 2.199978 2.199978]"));
+        assert!(pdf.contains(
+            "/DecodeParms <<\n    /Predictor 15\n    /Colors 3\n    \
+             /BitsPerComponent 8\n    /Columns 2\n  >>"
+        ));
+        assert!(pdf.contains("/Mask [10 10 20 20 30 30]"));
+        assert!(pdf.contains(&format!("/Length {}", idat.len())));
+    }
+
+    #[test]
+    fn test_png_palette() {
+        let idat = zlib(&[0, 0b0001_1011]);
+        let palette = [255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0];
+        let single = png(&[
+            (b"IHDR", &ihdr(4, 1, 2, 3, 0)),
+            (b"PLTE", &palette),
+            (b"tRNS", &[255, 0]),
+            (b"IDAT", &idat),
+        ]);
+
+        let image = PngImage::new(&single).unwrap();
+        assert!(image.is_passthrough());
+        let pdf = write(&image);
+        assert!(pdf
+            .contains("/ColorSpace [/Indexed /DeviceRGB 3 <FF000000FF000000FF000000>]"));
+        assert!(pdf.contains("/Mask [1 1]"));
+
+        let partial = png(&[
+            (b"IHDR", &ihdr(4, 1, 2, 3, 0)),
+            (b"PLTE", &palette),
+            (b"tRNS", &[255, 0, 128]),
+            (b"IDAT", &idat),
+        ]);
+
+        let image = PngImage::new(&partial).unwrap();
+        assert!(image.is_passthrough());
+        assert_eq!(image.alpha.as_deref(), Some([255, 0, 128, 255].as_slice()));
+        let pdf = write(&image);
+        assert!(pdf.contains("/SMask 2 0 R"));
+        assert!(!pdf.contains("/Mask ["));
+    }
+
+    #[test]
+    fn test_png_alpha() {
+        // Gray and alpha at 16 bits with the Average and Paeth filters.
+        let rows = [3, 0, 10, 255, 255, 0, 20, 128, 0, 4, 0, 1, 0, 0, 0, 2, 0, 0];
+        let data = png(&[(b"IHDR", &ihdr(2, 2, 16, 4, 0)), (b"IDAT", &zlib(&rows))]);
+        let image = PngImage::new(&data).unwrap();
+        assert!(!image.is_passthrough());
+        assert!(image.has_alpha());
+        assert_eq!(
+            image.samples.as_deref(),
+            Some([0, 10, 0, 25, 0, 11, 0, 27].as_slice())
+        );
+        assert_eq!(
+            image.alpha.as_deref(),
+            Some([255, 255, 255, 127, 255, 255, 255, 127].as_slice())
+        );
+
+        let pdf = write(&image);
+        assert!(pdf.contains("/ColorSpace /DeviceGray\n  /BitsPerComponent 16"));
+        assert!(pdf.contains("/SMask 2 0 R"));
+        assert!(!pdf.contains("/Filter"));
+
+        // Fully opaque alpha is dropped.
+        let rows = [0, 1, 2, 3, 255, 4, 5, 6, 255];
+        let data = png(&[(b"IHDR", &ihdr(2, 1, 8, 6, 0)), (b"IDAT", &zlib(&rows))]);
+        let mut image = PngImage::new(&data).unwrap();
+        image.deflate(zlib);
+        assert!(!image.has_alpha());
+        assert_eq!(image.samples.as_deref(), Some([1, 2, 3, 4, 5, 6].as_slice()));
+        let pdf = write(&image);
+        assert!(pdf.contains("/Filter /FlateDecode"));
+        assert!(!pdf.contains("/DecodeParms"));
+    }
+
+    #[test]
+    fn test_png_interlaced() {
+        // A 3x3 image with one bit per pixel. The non-empty passes cover the
+        // pixels (0, 0); (2, 0); (0, 2) and (2, 2); (1, 0) and (1, 2); and
+        // the middle row.
+        let passes = [0, 0x80, 0, 0x00, 0, 0x00, 0, 0x80, 0, 0x80, 0, 0xE0];
+        let data = png(&[(b"IHDR", &ihdr(3, 3, 1, 0, 1)), (b"IDAT", &zlib(&passes))]);
+        let image = PngImage::new(&data).unwrap();
+        assert!(!image.is_passthrough());
+        assert_eq!(image.samples.as_deref(), Some([0xC0, 0xE0, 0x40].as_slice()));
+    }
+
+    #[test]
+    fn test_png_errors() {
+        assert_eq!(PngImage::new(b"GIF89a").err(), Some(ImageError::UnknownFormat));
+        let bad_depth = png(&[(b"IHDR", &ihdr(1, 1, 4, 2, 0))]);
+        assert_eq!(
+            PngImage::new(&bad_depth).err(),
+            Some(ImageError::Malformed("IHDR chunk"))
+        );
+        let truncated = png(&[(b"IHDR", &ihdr(1, 1, 8, 4, 0)), (b"IDAT", &zlib(&[0]))]);
+        assert_eq!(
+            PngImage::new(&truncated).err(),
+            Some(ImageError::Malformed("image data"))
+        );
+
+        // Neither the claimed size nor the decompressed data are allocated.
+        let rows = vec![0; 1 << 20];
+        let huge = ihdr(0x7FFF_FFFF, 0x7FFF_FFFF, 8, 6, 0);
+        let bomb = png(&[(b"IHDR", &huge), (b"IDAT", &zlib(&rows))]);
+        assert_eq!(PngImage::new(&bomb).err(), Some(ImageError::Malformed("image data")));
+        let long = png(&[(b"IHDR", &ihdr(1, 1, 8, 6, 0)), (b"IDAT", &zlib(&rows))]);
+        assert_eq!(PngImage::new(&long).err(), Some(ImageError::Malformed("image data")));
+    }
+}
@@ -0,0 +1,90 @@
+/// Reads big-endian values from binary data.
+#[derive(Debug, Clone)]
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Start reading at the beginning of the data.
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Start reading at an offset into the data.
+    pub(crate) fn at(data: &'a [u8], pos: usize) -> Self {
+        Self { data, pos }
+    }
+
+    /// The current offset.
+    pub(crate) fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Whether there is no more data.
+    pub(crate) fn eof(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub(crate) fn skip(&mut self, n: usize) -> Option<()> {
+        self.bytes(n).map(|_| ())
+    }
+
+    pub(crate) fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(n)?;
+        let bytes = self.data.get(self.pos..end)?;
+        self.pos = end;
+        Some(bytes)
+    }
+
+    pub(crate) fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Option<u16> {
+        self.bytes(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    pub(crate) fn i16(&mut self) -> Option<i16> {
+        self.u16().map(|v| v as i16)
+    }
+
+    pub(crate) fn u24(&mut self) -> Option<u32> {
+        self.bytes(3).map(|b| u32::from_be_bytes([0, b[0], b[1], b[2]]))
+    }
+
+    pub(crate) fn u32(&mut self) -> Option<u32> {
+        self.bytes(4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub(crate) fn i32(&mut self) -> Option<i32> {
+        self.u32().map(|v| v as i32)
+    }
+
+    /// Read an unsigned offset of `size` bytes (1 to 4).
+    pub(crate) fn offset(&mut self, size: u8) -> Option<usize> {
+        let value = match size {
+            1 => self.u8()?.into(),
+            2 => self.u16()?.into(),
+            3 => self.u24()?,
+            4 => self.u32()?,
+            _ => return None,
+        };
+        usize::try_from(value).ok()
+    }
+}
+
+/// Read a big-endian `u16` at an offset.
+pub(crate) fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+    Reader::at(data, pos).u16()
+}
+
+/// Read a big-endian `i16` at an offset.
+pub(crate) fn read_i16(data: &[u8], pos: usize) -> Option<i16> {
+    Reader::at(data, pos).i16()
+}
+
+/// Read a big-endian `u32` at an offset.
+pub(crate) fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+    Reader::at(data, pos).u32()
+}
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// Collects the resources used by a content stream and assigns names to them.
+///
+/// Content stream operators refer to fonts, images and other resources by
+/// name. These names must be defined in the resource dictionary of the page,
+/// form XObject, tiling pattern or Type 3 font the content stream belongs to.
+/// Instead of maintaining both sides by hand, register each resource when you
+/// use it and write the resulting dictionary once the content is done.
+///
+/// Registering the same reference twice in the same category yields the same
+/// name. Names are allocated per category in the order of registration.
+///
+/// ```
+/// use pdf_writer::{Content, Pdf, Rect, Ref, ResourceCollector, Str};
+///
+/// let mut pdf = Pdf::new();
+/// let page_id = Ref::new(1);
+/// let font_id = Ref::new(2);
+/// let image_id = Ref::new(3);
+/// let content_id = Ref::new(4);
+///
+/// let mut resources = ResourceCollector::new();
+/// let mut content = Content::new();
+/// content.begin_text();
+/// content.set_font(resources.font(font_id), 14.0);
+/// content.show(Str(b"Hello"));
+/// content.end_text();
+/// content.x_object(resources.image(image_id));
+///
+/// let mut page = pdf.page(page_id);
+/// page.media_box(Rect::new(0.0, 0.0, 595.0, 842.0));
+/// page.contents(content_id);
+/// resources.write(&mut page.resources());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ResourceCollector {
+    categories: [Category; 8],
+}
+
+/// The resources of one subdictionary in registration order.
+#[derive(Debug, Clone, Default)]
+struct Category {
+    entries: Vec<(Ref, Vec<u8>)>,
+    lookup: HashMap<Ref, usize>,
+}
+
+const FONT: usize = 0;
+const IMAGE: usize = 1;
+const FORM: usize = 2;
+const COLOR_SPACE: usize = 3;
+const PATTERN: usize = 4;
+const SHADING: usize = 5;
+const EXT_G_STATE: usize = 6;
+const PROPERTIES: usize = 7;
+
+impl ResourceCollector {
+    /// Create an empty resource collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a font and return the name to use with
+    /// [`Content::set_font`] (`F1`, `F2`, ...).
+    pub fn font(&mut self, id: Ref) -> Name<'_> {
+        self.register(FONT, b"F", id)
+    }
+
+    /// Register an image XObject and return the name to use with
+    /// [`Content::x_object`] (`Im1`, `Im2`, ...).
+    pub fn image(&mut self, id: Ref) -> Name<'_> {
+        self.register(IMAGE, b"Im", id)
+    }
+
+    /// Register a form XObject and return the name to use with
+    /// [`Content::x_object`] (`Fm1`, `Fm2`, ...).
+    pub fn form(&mut self, id: Ref) -> Name<'_> {
+        self.register(FORM, b"Fm", id)
+    }
+
+    /// Register a color space and return the name to use with
+    /// [`Content::set_fill_color_space`] and
+    /// [`Content::set_stroke_color_space`] (`Cs1`, `Cs2`, ...).
+    pub fn color_space(&mut self, id: Ref) -> Name<'_> {
+        self.register(COLOR_SPACE, b"Cs", id)
+    }
+
+    /// Register a pattern and return the name to use with
+    /// [`Content::set_fill_pattern`] and [`Content::set_stroke_pattern`]
+    /// (`P1`, `P2`, ...).
+    pub fn pattern(&mut self, id: Ref) -> Name<'_> {
+        self.register(PATTERN, b"P", id)
+    }
+
+    /// Register a shading and return the name to use with
+    /// [`Content::shading`] (`Sh1`, `Sh2`, ...).
+    pub fn shading(&mut self, id: Ref) -> Name<'_> {
+        self.register(SHADING, b"Sh", id)
+    }
+
+    /// Register an external graphics state and return the name to use with
+    /// [`Content::set_parameters`] (`Gs1`, `Gs2`, ...).
+    pub fn ext_g_state(&mut self, id: Ref) -> Name<'_> {
+        self.register(EXT_G_STATE, b"Gs", id)
+    }
+
+    /// Register a property list and return the name to use with
+    /// [`MarkContent::properties_named`] (`Pr1`, `Pr2`, ...).
+    pub fn properties(&mut self, id: Ref) -> Name<'_> {
+        self.register(PROPERTIES, b"Pr", id)
+    }
+
+    /// Whether no resources were registered.
+    pub fn is_empty(&self) -> bool {
+        self.categories.iter().all(|category| category.entries.is_empty())
+    }
+
+    /// Write all registered resources into a resource dictionary.
+    ///
+    /// Only the subdictionaries that have at least one entry are written.
+    pub fn write(&self, resources: &mut Resources) {
+        let xobjects = self.categories[IMAGE].entries.iter();
+        if xobjects.len() > 0 || !self.categories[FORM].entries.is_empty() {
+            resources
+                .x_objects()
+                .pairs(xobjects.chain(&self.categories[FORM].entries).map(pair));
+        }
+
+        let write = |index: usize, mut dict: Dict| {
+            dict.pairs(self.categories[index].entries.iter().map(pair));
+        };
+
+        if !self.categories[FONT].entries.is_empty() {
+            write(FONT, resources.fonts());
+        }
+        if !self.categories[COLOR_SPACE].entries.is_empty() {
+            write(COLOR_SPACE, resources.color_spaces());
+        }
+        if !self.categories[PATTERN].entries.is_empty() {
+            write(PATTERN, resources.patterns());
+        }
+        if !self.categories[SHADING].entries.is_empty() {
+            write(SHADING, resources.shadings());
+        }
+        if !self.categories[EXT_G_STATE].entries.is_empty() {
+            write(EXT_G_STATE, resources.ext_g_states());
+        }
+        if !self.categories[PROPERTIES].entries.is_empty() {
+            // `Resources::properties` only takes direct property lists, but
+            // the registered ones are indirect.
+            write(PROPERTIES, resources.insert(Name(b"Properties")).dict());
+        }
+    }
+
+    fn register(&mut self, index: usize, prefix: &[u8], id: Ref) -> Name<'_> {
+        let category = &mut self.categories[index];
+        let len = category.entries.len();
+        let i = *category.lookup.entry(id).or_insert(len);
+        if i == len {
+            let mut name = prefix.to_vec();
+            name.extend(itoa::Buffer::new().format(len + 1).as_bytes());
+            category.entries.push((id, name));
+        }
+        Name(&category.entries[i].1)
+    }
+}
+
+fn pair((id, name): &(Ref, Vec<u8>)) -> (Name<'_>, Ref) {
+    (Name(name), *id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_collector() {
+        let mut collector = ResourceCollector::new();
+        assert!(collector.is_empty());
+        assert_eq!(collector.font(Ref::new(5)), Name(b"F1"));
+        assert_eq!(collector.font(Ref::new(3)), Name(b"F2"));
+        assert_eq!(collector.font(Ref::new(5)), Name(b"F1"));
+        assert_eq!(collector.image(Ref::new(3)), Name(b"Im1"));
+        assert_eq!(collector.form(Ref::new(7)), Name(b"Fm1"));
+        assert_eq!(collector.ext_g_state(Ref::new(8)), Name(b"Gs1"));
+        assert_eq!(collector.properties(Ref::new(9)), Name(b"Pr1"));
+        assert!(!collector.is_empty());
+
+        test_obj!(
+            |obj| collector.write(&mut obj.start::<Resources>()),
+            b"<<\n  /XObject <<\n    /Im1 3 0 R\n    /Fm1 7 0 R\n  >>",
+            b"  /Font <<\n    /F1 5 0 R\n    /F2 3 0 R\n  >>",
+            b"  /ExtGState <<\n    /Gs1 8 0 R\n  >>",
+            b"  /Properties <<\n    /Pr1 9 0 R\n  >>\n>>",
+        );
+
+        test_obj!(|obj| ResourceCollector::new().write(&mut obj.start()), b"<<>>");
+    }
+}
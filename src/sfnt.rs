@@ -0,0 +1,525 @@
+use crate::reader::{read_i16, read_u16, Reader};
+use crate::FontError;
+
+/// A face in a TrueType or OpenType font file or collection.
+#[derive(Debug, Clone)]
+pub(crate) struct Face<'a> {
+    tables: Vec<(Tag, &'a [u8])>,
+    cmap: Option<Cmap<'a>>,
+    units_per_em: u16,
+    num_glyphs: u16,
+}
+
+/// A four-byte table tag.
+pub(crate) type Tag = [u8; 4];
+
+impl<'a> Face<'a> {
+    /// Parse the face with the given index.
+    ///
+    /// The index must be zero unless the data is a font collection.
+    pub(crate) fn parse(data: &'a [u8], index: u32) -> Result<Self, FontError> {
+        let mut r = Reader::new(data);
+        let mut version = r.u32().ok_or(FontError::UnknownFormat)?;
+        let mut start = 0;
+        if version == u32::from_be_bytes(*b"ttcf") {
+            r.skip(4).ok_or(FontError::UnknownFormat)?;
+            let count = r.u32().ok_or(FontError::UnknownFormat)?;
+            if index >= count {
+                return Err(FontError::FaceIndexOutOfBounds);
+            }
+            r.skip(4 * index as usize).ok_or(FontError::UnknownFormat)?;
+            start = r.u32().ok_or(FontError::UnknownFormat)? as usize;
+            r = Reader::at(data, start);
+            version = r.u32().ok_or(FontError::UnknownFormat)?;
+        } else if index > 0 {
+            return Err(FontError::FaceIndexOutOfBounds);
+        }
+
+        if !matches!(&version.to_be_bytes(), b"\0\x01\0\0" | b"OTTO" | b"true") {
+            return Err(FontError::UnknownFormat);
+        }
+
+        let count = r.u16().ok_or(FontError::UnknownFormat)?;
+        r = Reader::at(data, start + 12);
+        let mut tables = Vec::with_capacity(count.into());
+        for _ in 0..count {
+            let malformed = FontError::MalformedTable("font directory");
+            let tag: Tag = r.bytes(4).ok_or(malformed)?.try_into().unwrap();
+            r.skip(4).ok_or(malformed)?;
+            let offset = r.u32().ok_or(malformed)? as usize;
+            let length = r.u32().ok_or(malformed)? as usize;
+            let table = offset
+                .checked_add(length)
+                .and_then(|end| data.get(offset..end))
+                .ok_or(FontError::MalformedTable(tag_name(&tag)))?;
+            tables.push((tag, table));
+        }
+
+        let mut face = Self { tables, cmap: None, units_per_em: 0, num_glyphs: 0 };
+        let head = face.require(b"head")?;
+        face.units_per_em = read_u16(head, 18)
+            .filter(|&units| units > 0)
+            .ok_or(FontError::MalformedTable("head"))?;
+        face.num_glyphs = read_u16(face.require(b"maxp")?, 4)
+            .filter(|&n| n > 0)
+            .ok_or(FontError::MalformedTable("maxp"))?;
+        face.cmap = face.table(b"cmap").and_then(Cmap::parse);
+        Ok(face)
+    }
+
+    /// The data of a table.
+    pub(crate) fn table(&self, tag: &Tag) -> Option<&'a [u8]> {
+        self.tables
+            .iter()
+            .find(|(other, _)| other == tag)
+            .map(|&(_, data)| data)
+    }
+
+    /// The data of a table that must be present.
+    pub(crate) fn require(&self, tag: &Tag) -> Result<&'a [u8], FontError> {
+        self.table(tag).ok_or(FontError::MissingTable(tag_name(tag)))
+    }
+
+    /// The number of glyphs in the font.
+    pub(crate) fn num_glyphs(&self) -> u16 {
+        self.num_glyphs
+    }
+
+    /// The number of font units per em.
+    pub(crate) fn units_per_em(&self) -> u16 {
+        self.units_per_em
+    }
+
+    /// Convert a value in font units to thousandths of an em.
+    pub(crate) fn to_em(&self, units: impl Into<f32>) -> f32 {
+        units.into() * 1000.0 / f32::from(self.units_per_em)
+    }
+
+    /// The glyph for a character.
+    pub(crate) fn glyph_id(&self, c: char) -> Option<u16> {
+        self.cmap.as_ref()?.lookup(c)
+    }
+
+    /// The horizontal advance of a glyph in font units.
+    pub(crate) fn advance(&self, glyph: u16) -> Option<u16> {
+        if glyph >= self.num_glyphs {
+            return None;
+        }
+
+        let hmtx = self.table(b"hmtx")?;
+        let metrics = read_u16(self.table(b"hhea")?, 34)?.max(1);
+        read_u16(hmtx, 4 * usize::from(glyph.min(metrics - 1)))
+    }
+}
+
+/// The name of a table for error messages.
+fn tag_name(tag: &Tag) -> &'static str {
+    const KNOWN: &[&str] = &[
+        "CFF ", "CFF2", "COLR", "CPAL", "OS/2", "cmap", "cvt ", "fpgm", "glyf", "head",
+        "hhea", "hmtx", "loca", "maxp", "name", "post", "prep",
+    ];
+    KNOWN
+        .iter()
+        .find(|name| name.as_bytes() == tag)
+        .copied()
+        .unwrap_or("unknown")
+}
+
+/// A character to glyph mapping subtable.
+#[derive(Debug, Clone)]
+struct Cmap<'a> {
+    format: u16,
+    data: &'a [u8],
+    /// Whether this is a symbol subtable, which maps the characters of a
+    /// single-byte encoding into the private use area starting at `U+F000`.
+    symbol: bool,
+}
+
+impl<'a> Cmap<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut r = Reader::new(data);
+        r.skip(2)?;
+        let count = r.u16()?;
+        let mut best: Option<(u8, Self)> = None;
+        for _ in 0..count {
+            let platform = r.u16()?;
+            let encoding = r.u16()?;
+            let Some(subtable) = data.get(r.u32()? as usize..) else { continue };
+            let Some(format) = read_u16(subtable, 0) else { continue };
+            let rank = match (platform, encoding, format) {
+                (3, 10, 12) | (0, 4 | 6, 12) => 4,
+                (3, 1, 4) | (0, 0..=3, 4) => 3,
+                (3, 0, 4) => 1,
+                _ => continue,
+            };
+            if best.as_ref().is_none_or(|(other, _)| rank > *other) {
+                let symbol = platform == 3 && encoding == 0;
+                best = Some((rank, Self { format, data: subtable, symbol }));
+            }
+        }
+        best.map(|(_, cmap)| cmap)
+    }
+
+    fn lookup(&self, c: char) -> Option<u16> {
+        let code = u32::from(c);
+        let glyph = match self.format {
+            4 => self.lookup_format4(code),
+            12 => self.lookup_format12(code),
+            _ => None,
+        };
+
+        match glyph {
+            None if self.symbol && code <= 0xFF => self.lookup_format4(0xF000 + code),
+            glyph => glyph,
+        }
+    }
+
+    fn lookup_format4(&self, code: u32) -> Option<u16> {
+        let code = u16::try_from(code).ok()?;
+        let segments = usize::from(read_u16(self.data, 6)? / 2);
+        let ends = 14;
+        let starts = ends + 2 * segments + 2;
+        let deltas = starts + 2 * segments;
+        let offsets = deltas + 2 * segments;
+
+        // Find the first segment whose end code is at least the code.
+        let (mut lo, mut hi) = (0, segments);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if read_u16(self.data, ends + 2 * mid)? < code {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        let i = lo;
+        if i >= segments {
+            return None;
+        }
+
+        let start = read_u16(self.data, starts + 2 * i)?;
+        if code < start {
+            return None;
+        }
+
+        let delta = read_u16(self.data, deltas + 2 * i)?;
+        let range_offset = read_u16(self.data, offsets + 2 * i)?;
+        let glyph = if range_offset == 0 {
+            code.wrapping_add(delta)
+        } else {
+            let pos = offsets
+                + 2 * i
+                + usize::from(range_offset)
+                + 2 * usize::from(code - start);
+            match read_u16(self.data, pos)? {
+                0 => 0,
+                glyph => glyph.wrapping_add(delta),
+            }
+        };
+
+        Some(glyph).filter(|&glyph| glyph != 0)
+    }
+
+    fn lookup_format12(&self, code: u32) -> Option<u16> {
+        let mut r = Reader::at(self.data, 12);
+        let count = r.u32()? as usize;
+        let groups = self.data.get(16..16 + count.checked_mul(12)?)?;
+
+        let (mut lo, mut hi) = (0, count);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let mut r = Reader::at(groups, 12 * mid);
+            let (start, end, glyph) = (r.u32()?, r.u32()?, r.u32()?);
+            if code < start {
+                hi = mid;
+            } else if code > end {
+                lo = mid + 1;
+            } else {
+                let glyph = glyph.checked_add(code - start)?;
+                return u16::try_from(glyph).ok().filter(|&g| g != 0);
+            }
+        }
+
+        None
+    }
+}
+
+/// The global metrics and style of a face, in font units.
+#[derive(Debug, Clone)]
+pub(crate) struct FaceMetrics {
+    pub bbox: [i16; 4],
+    pub ascender: i16,
+    pub descender: i16,
+    pub cap_height: Option<i16>,
+    pub x_height: Option<i16>,
+    pub italic_angle: f32,
+    pub weight: u16,
+    pub fixed_pitch: bool,
+    pub italic: bool,
+    pub serif: bool,
+    pub script: bool,
+    /// The `fsType` embedding permissions.
+    pub permissions: u16,
+}
+
+impl FaceMetrics {
+    /// Read the metrics from the `head`, `hhea`, `OS/2` and `post` tables.
+    pub(crate) fn parse(face: &Face) -> Result<Self, FontError> {
+        let head = face.require(b"head")?;
+        let hhea = face.require(b"hhea")?;
+        let mut r = Reader::at(head, 36);
+        let bbox = (|| Some([r.i16()?, r.i16()?, r.i16()?, r.i16()?]))()
+            .ok_or(FontError::MalformedTable("head"))?;
+        let mac_style = read_u16(head, 44).unwrap_or(0);
+
+        let mut metrics = Self {
+            bbox,
+            ascender: read_i16(hhea, 4).ok_or(FontError::MalformedTable("hhea"))?,
+            descender: read_i16(hhea, 6).ok_or(FontError::MalformedTable("hhea"))?,
+            cap_height: None,
+            x_height: None,
+            italic_angle: 0.0,
+            weight: if mac_style & 1 != 0 { 700 } else { 400 },
+            fixed_pitch: false,
+            italic: mac_style & 2 != 0,
+            serif: false,
+            script: false,
+            permissions: 0,
+        };
+
+        if let Some(os2) = face.table(b"OS/2") {
+            let version = read_u16(os2, 0).unwrap_or(0);
+            metrics.weight = read_u16(os2, 4).unwrap_or(metrics.weight);
+            metrics.permissions = read_u16(os2, 8).unwrap_or(0);
+            let class = read_u16(os2, 30).unwrap_or(0) >> 8;
+            metrics.serif = (1..=7).contains(&class);
+            metrics.script = class == 10;
+            if let Some(selection) = read_u16(os2, 62) {
+                metrics.italic |= selection & 1 != 0;
+            }
+            // Prefer the typographic metrics if the font says so.
+            if read_u16(os2, 62).is_some_and(|selection| selection & 0x80 != 0) {
+                metrics.ascender = read_i16(os2, 68).unwrap_or(metrics.ascender);
+                metrics.descender = read_i16(os2, 70).unwrap_or(metrics.descender);
+            }
+            if version >= 2 {
+                metrics.x_height = read_i16(os2, 86).filter(|&h| h > 0);
+                metrics.cap_height = read_i16(os2, 88).filter(|&h| h > 0);
+            }
+        }
+
+        if let Some(post) = face.table(b"post") {
+            let mut r = Reader::at(post, 4);
+            if let Some(angle) = r.i32() {
+                metrics.italic_angle = angle as f32 / 65536.0;
+            }
+            metrics.fixed_pitch = Reader::at(post, 12).u32().is_some_and(|v| v != 0);
+        }
+
+        Ok(metrics)
+    }
+}
+
+/// The PostScript name of a face from its `name` table.
+pub(crate) fn postscript_name(face: &Face) -> Option<String> {
+    let data = face.table(b"name")?;
+    let mut r = Reader::at(data, 2);
+    let count = r.u16()?;
+    let storage = usize::from(r.u16()?);
+    for _ in 0..count {
+        let (platform, encoding, _, id) = (r.u16()?, r.u16()?, r.u16()?, r.u16()?);
+        let (length, offset) = (usize::from(r.u16()?), usize::from(r.u16()?));
+        if id != 6 {
+            continue;
+        }
+
+        let start = storage + offset;
+        let Some(bytes) = data.get(start..start + length) else { continue };
+        let name = match (platform, encoding) {
+            (0, _) | (3, 0 | 1) => {
+                let units =
+                    bytes.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]]));
+                char::decode_utf16(units).collect::<Result<String, _>>().ok()?
+            }
+            (1, 0) => bytes.iter().map(|&b| char::from(b)).collect(),
+            _ => continue,
+        };
+
+        return Some(name);
+    }
+
+    None
+}
+
+/// The locations of the glyphs in the `glyf` table.
+#[derive(Debug, Clone)]
+pub(crate) struct Glyf<'a> {
+    loca: &'a [u8],
+    glyf: &'a [u8],
+    long: bool,
+}
+
+impl<'a> Glyf<'a> {
+    pub(crate) fn parse(face: &Face<'a>) -> Result<Self, FontError> {
+        let long = match read_i16(face.require(b"head")?, 50) {
+            Some(0) => false,
+            Some(1) => true,
+            _ => return Err(FontError::MalformedTable("head")),
+        };
+
+        Ok(Self {
+            loca: face.require(b"loca")?,
+            glyf: face.require(b"glyf")?,
+            long,
+        })
+    }
+
+    /// The data of a glyph. Empty for glyphs without an outline.
+    pub(crate) fn glyph(&self, glyph: u16) -> Option<&'a [u8]> {
+        let i = usize::from(glyph);
+        let (start, end) = if self.long {
+            let mut r = Reader::at(self.loca, 4 * i);
+            (r.u32()? as usize, r.u32()? as usize)
+        } else {
+            let mut r = Reader::at(self.loca, 2 * i);
+            (2 * usize::from(r.u16()?), 2 * usize::from(r.u16()?))
+        };
+        self.glyf.get(start..end)
+    }
+}
+
+/// The glyphs a composite glyph refers to.
+pub(crate) fn glyph_components(data: &[u8]) -> impl Iterator<Item = u16> + '_ {
+    let composite = read_i16(data, 0).is_some_and(|contours| contours < 0);
+    let mut r = Reader::at(data, 10);
+    let mut more = composite;
+    std::iter::from_fn(move || {
+        if !more {
+            return None;
+        }
+
+        let flags = r.u16()?;
+        let glyph = r.u16()?;
+        let args = if flags & 0x0001 != 0 { 4 } else { 2 };
+        let transform = if flags & 0x0008 != 0 {
+            2
+        } else if flags & 0x0040 != 0 {
+            4
+        } else if flags & 0x0080 != 0 {
+            8
+        } else {
+            0
+        };
+        r.skip(args + transform)?;
+        more = flags & 0x0020 != 0;
+        Some(glyph)
+    })
+}
+
+/// Builds small TrueType fonts for tests.
+#[cfg(test)]
+pub(crate) mod fixture {
+    use super::Tag;
+    use crate::subset::write_sfnt;
+
+    /// Encode each value as a big-endian integer with `size` bytes.
+    pub(crate) fn be(values: &[i32], size: usize) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|v| v.to_be_bytes()[4 - size..].to_vec())
+            .collect()
+    }
+
+    /// A TrueType font with four glyphs, 1000 units per em and the given
+    /// advance widths, of which the last one also applies to the fourth glyph.
+    ///
+    /// The `loca` table must be in the short format. The `extra` tables are
+    /// added as they are.
+    pub(crate) fn truetype_font(
+        advances: [i32; 3],
+        glyf: &[u8],
+        loca: &[u8],
+        extra: &[(Tag, &[u8])],
+    ) -> Vec<u8> {
+        let mut head = be(&[0x00010000, 0, 0, 0x5F0F3CF5], 4);
+        head.extend(be(&[0, 1000], 2));
+        head.extend([0; 16]);
+        head.extend(be(&[0, -200, 800, 800, 0, 8, 2, 0, 0], 2));
+        let mut hhea = be(&[0x00010000], 4);
+        hhea.extend(be(&[800, -200, 0, 600, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 3], 2));
+        let mut maxp = be(&[0x00005000], 4);
+        maxp.extend(be(&[4], 2));
+        let [a, b, c] = advances;
+        let hmtx = be(&[a, 0, b, 0, c, 0, 0], 2);
+
+        let mut tables: Vec<(Tag, &[u8])> = vec![
+            (*b"glyf", glyf),
+            (*b"head", &head),
+            (*b"hhea", &hhea),
+            (*b"hmtx", &hmtx),
+            (*b"loca", loca),
+            (*b"maxp", &maxp),
+        ];
+        tables.extend(extra);
+        tables.sort_by_key(|&(tag, _)| tag);
+        write_sfnt(&tables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixture::{be, truetype_font};
+    use super::*;
+
+    #[test]
+    fn test_face() {
+        // Glyph 3 is a composite of glyph 1 and of glyph 2 with a 2x2 matrix.
+        let mut glyf = be(&[-1, 0, 0, 0, 0], 2);
+        glyf.extend(be(&[0x0021, 1, 10, 20, 0x0081, 2], 2));
+        glyf.extend([0; 12]);
+        let loca = be(&[0, 0, 0, 0, 17], 2);
+        let data = truetype_font([500, 600, 700], &glyf, &loca, &[]);
+
+        let face = Face::parse(&data, 0).unwrap();
+        assert_eq!(face.num_glyphs(), 4);
+        assert_eq!(face.units_per_em(), 1000);
+        assert_eq!(face.advance(2), Some(700));
+        assert_eq!(face.advance(3), Some(700));
+        assert_eq!(face.advance(4), None);
+        assert_eq!(face.glyph_id('A'), None);
+        assert!(matches!(face.require(b"CFF "), Err(FontError::MissingTable("CFF "))));
+
+        let glyf = Glyf::parse(&face).unwrap();
+        assert_eq!(glyf.glyph(0), Some(&[][..]));
+        assert_eq!(glyph_components(glyf.glyph(3).unwrap()).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(glyf.glyph(4), None);
+    }
+
+    #[test]
+    fn test_cmap() {
+        // A symbol subtable for the characters 'A' to 'C' at U+F041 to U+F043.
+        let mut symbol = be(&[0, 1, 3, 0], 2);
+        symbol.extend(be(&[12], 4));
+        symbol.extend(be(&[4, 32, 0, 4, 4, 1, 0], 2));
+        symbol.extend(be(&[0xF043, 0xFFFF, 0, 0xF041, 0xFFFF, -0xF040, 1, 0, 0], 2));
+        let cmap = Cmap::parse(&symbol).unwrap();
+        assert_eq!(cmap.lookup('A'), Some(1));
+        assert_eq!(cmap.lookup('\u{F043}'), Some(3));
+        assert_eq!(cmap.lookup('D'), None);
+
+        // A full Unicode subtable that is preferred over the symbol one.
+        let mut full = be(&[0, 2, 3, 0], 2);
+        full.extend(be(&[20], 4));
+        full.extend(be(&[3, 10], 2));
+        full.extend(be(&[52], 4));
+        full.extend(&symbol[12..]);
+        full.extend(be(&[12, 0], 2));
+        full.extend(be(&[40, 0, 2, 0x41, 0x42, 5, 0x1F600, 0x1F600, 2], 4));
+        let cmap = Cmap::parse(&full).unwrap();
+        assert_eq!(cmap.lookup('A'), Some(5));
+        assert_eq!(cmap.lookup('B'), Some(6));
+        assert_eq!(cmap.lookup('C'), None);
+        assert_eq!(cmap.lookup('😀'), Some(2));
+    }
+}
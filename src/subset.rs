@@ -0,0 +1,168 @@
+use std::collections::BTreeSet;
+
+use crate::reader::Reader;
+use crate::sfnt::{glyph_components, Face, Glyf, Tag};
+use crate::FontError;
+
+/// The glyphs of a TrueType font that are needed to draw the given glyphs.
+///
+/// This includes the `.notdef` glyph and the components of composite glyphs.
+pub(crate) fn glyph_closure(glyf: &Glyf, glyphs: &BTreeSet<u16>) -> BTreeSet<u16> {
+    let mut closure = BTreeSet::new();
+    let mut work: Vec<u16> = glyphs.iter().copied().chain([0]).collect();
+    while let Some(glyph) = work.pop() {
+        if closure.insert(glyph) {
+            if let Some(data) = glyf.glyph(glyph) {
+                work.extend(glyph_components(data));
+            }
+        }
+    }
+    closure
+}
+
+/// Write a copy of a TrueType font that only contains the tables needed for
+/// embedding and in which only the given glyphs have outlines.
+///
+/// The glyph IDs stay the same, but glyphs after the last used one are
+/// dropped. The `glyphs` must be closed under composition, see
+/// [`glyph_closure`].
+pub(crate) fn subset_truetype(
+    face: &Face,
+    glyphs: &BTreeSet<u16>,
+) -> Result<Vec<u8>, FontError> {
+    let glyf = Glyf::parse(face)?;
+    let old_count = face.num_glyphs();
+    let count = glyphs.last().map_or(1, |&last| last + 1).min(old_count);
+
+    // Rebuild `glyf` and a long `loca` with empty entries for unused glyphs.
+    let mut new_glyf = vec![];
+    let mut new_loca = Vec::with_capacity(4 * (usize::from(count) + 1));
+    for glyph in 0..count {
+        new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+        if glyphs.contains(&glyph) {
+            new_glyf.extend(glyf.glyph(glyph).unwrap_or_default());
+            new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+        }
+    }
+    new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+
+    let malformed = |table| move || FontError::MalformedTable(table);
+    let mut head = face.require(b"head")?.to_vec();
+    head.get_mut(8..12).ok_or_else(malformed("head"))?.fill(0);
+    head.get_mut(50..52)
+        .ok_or_else(malformed("head"))?
+        .copy_from_slice(&[0, 1]);
+
+    let mut maxp = face.require(b"maxp")?.to_vec();
+    maxp[4..6].copy_from_slice(&count.to_be_bytes());
+
+    // Keep as many full metrics as possible and the left side bearings of the
+    // remaining glyphs.
+    let mut hhea = face.require(b"hhea")?.to_vec();
+    let hmtx = face.require(b"hmtx")?;
+    let old_metrics = Reader::at(&hhea, 34).u16().ok_or_else(malformed("hhea"))?;
+    let metrics = old_metrics.clamp(1, count);
+    hhea[34..36].copy_from_slice(&metrics.to_be_bytes());
+    let mut new_hmtx = hmtx
+        .get(..4 * usize::from(metrics))
+        .ok_or_else(malformed("hmtx"))?
+        .to_vec();
+    let bearings = 4 * usize::from(old_metrics);
+    for glyph in metrics..count {
+        let pos = if glyph < old_metrics {
+            4 * usize::from(glyph) + 2
+        } else {
+            bearings + 2 * usize::from(glyph - old_metrics)
+        };
+        new_hmtx.extend(hmtx.get(pos..pos + 2).unwrap_or(&[0, 0]));
+    }
+
+    let mut tables: Vec<(Tag, &[u8])> = vec![
+        (*b"glyf", &new_glyf),
+        (*b"head", &head),
+        (*b"hhea", &hhea),
+        (*b"hmtx", &new_hmtx),
+        (*b"loca", &new_loca),
+        (*b"maxp", &maxp),
+    ];
+
+    // The hinting tables.
+    for tag in [b"cvt ", b"fpgm", b"prep"] {
+        if let Some(data) = face.table(tag) {
+            tables.push((*tag, data));
+        }
+    }
+
+    tables.sort_by_key(|&(tag, _)| tag);
+    Ok(write_sfnt(&tables))
+}
+
+/// Write a TrueType font file with the given tables, which must be sorted by
+/// tag and include a `head` table.
+pub(crate) fn write_sfnt(tables: &[(Tag, &[u8])]) -> Vec<u8> {
+    let count = tables.len() as u16;
+    let selector = 15 - count.leading_zeros() as u16;
+    let search_range: u16 = (1 << selector) * 16;
+
+    let mut out = vec![];
+    out.extend(0x00010000_u32.to_be_bytes());
+    out.extend(count.to_be_bytes());
+    out.extend(search_range.to_be_bytes());
+    out.extend(selector.to_be_bytes());
+    out.extend((count * 16 - search_range).to_be_bytes());
+
+    let mut offset = 12 + 16 * tables.len();
+    let mut head_offset = 0;
+    for (tag, data) in tables {
+        if tag == b"head" {
+            head_offset = offset;
+        }
+        out.extend(tag);
+        out.extend(checksum(data).to_be_bytes());
+        out.extend((offset as u32).to_be_bytes());
+        out.extend((data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+
+    for (_, data) in tables {
+        out.extend(*data);
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+
+    let adjustment = 0xB1B0AFBA_u32.wrapping_sub(checksum(&out));
+    out[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    out
+}
+
+/// The checksum of a table, which is the sum of its big-endian `u32`s.
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sfnt::fixture::truetype_font;
+
+    #[test]
+    fn test_write_sfnt() {
+        let data = truetype_font([500, 600, 700], &[], &[0; 10], &[]);
+        let mut r = Reader::new(&data);
+        assert_eq!(r.u32(), Some(0x00010000));
+        assert_eq!([r.u16(), r.u16(), r.u16(), r.u16()], [6, 64, 2, 32].map(Some));
+
+        // The tables are aligned to four bytes and the whole file sums to the
+        // magic number thanks to the adjustment in the `head` table.
+        let face = Face::parse(&data, 0).unwrap();
+        let head = face.table(b"head").unwrap();
+        let offset = head.as_ptr() as usize - data.as_ptr() as usize;
+        assert_eq!(offset % 4, 0);
+        assert_ne!(&head[8..12], [0; 4]);
+        assert_eq!(checksum(&data), 0xB1B0AFBA);
+        assert_eq!(face.table(b"loca"), Some(&[0; 10][..]));
+    }
+}
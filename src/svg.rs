@@ -0,0 +1,427 @@
+use std::fmt::{self, Display, Formatter};
+
+use super::*;
+use crate::content::arc_to_cubics;
+
+/// SVG path data.
+impl Content {
+    /// Append the path described by SVG path data.
+    ///
+    /// The `data` is the value of the `d` attribute of an SVG `<path>` element
+    /// and may contain all of SVG's path commands in their absolute and
+    /// relative forms. Quadratic curves and elliptical arcs are converted to
+    /// cubic Bézier segments (see [`quad_to`](Self::quad_to) and
+    /// [`arc_to`](Self::arc_to)).
+    ///
+    /// The coordinates are used as they are. Since SVG's y-axis points down,
+    /// you typically want to use [`svg_path_with_transform`] to flip it.
+    ///
+    /// Like SVG renderers, this writes the path up to the first error and then
+    /// returns the error.
+    ///
+    /// [`svg_path_with_transform`]: Self::svg_path_with_transform
+    pub fn svg_path(&mut self, data: &str) -> Result<&mut Self, SvgPathError> {
+        self.svg_path_with_transform(data, Transform::identity())
+    }
+
+    /// Append the path described by SVG path data, mapping all points
+    /// through an affine `matrix`.
+    ///
+    /// For example, the matrix `[s, 0, 0, -s, x, y]` (i.e.
+    /// `Transform::scale(s, -s).then(Transform::translate(x, y))`) draws an
+    /// icon with its top-left corner at (x, y), scaled by `s` and with the
+    /// y-axis flipped.
+    ///
+    /// See [`svg_path`](Self::svg_path) for more details.
+    pub fn svg_path_with_transform(
+        &mut self,
+        data: &str,
+        matrix: impl Into<[f32; 6]>,
+    ) -> Result<&mut Self, SvgPathError> {
+        let mut builder = PathBuilder::new(self, matrix.into().into());
+        let mut parser = Parser { data: data.as_bytes(), cursor: 0 };
+        parser.skip_separators();
+
+        let mut command = None;
+        while parser.cursor < parser.data.len() {
+            let start = parser.cursor;
+            let byte = parser.data[start];
+            if byte.is_ascii_alphabetic() {
+                parser.cursor += 1;
+                command = Some(byte);
+            } else if matches!(command, None | Some(b'Z' | b'z')) {
+                return Err(SvgPathError(start));
+            }
+
+            let Some(cmd) = command else { return Err(SvgPathError(start)) };
+            if !b"MmLlHhVvCcSsQqTtAaZz".contains(&cmd)
+                || (!builder.started && !matches!(cmd, b'M' | b'm'))
+            {
+                return Err(SvgPathError(start));
+            }
+
+            builder.command(cmd, &mut parser).ok_or(SvgPathError(parser.cursor))?;
+
+            // A moveto followed by more coordinates implies lineto commands.
+            command = match cmd {
+                b'M' => Some(b'L'),
+                b'm' => Some(b'l'),
+                _ => command,
+            };
+
+            parser.skip_separators();
+        }
+
+        Ok(self)
+    }
+}
+
+/// Writes SVG path commands into a content stream.
+struct PathBuilder<'a> {
+    content: &'a mut Content,
+    transform: Transform,
+    /// The current point in SVG coordinates.
+    current: (f32, f32),
+    /// The start of the current subpath in SVG coordinates.
+    start: (f32, f32),
+    /// The last control point of a cubic curve, for `S`.
+    cubic_control: Option<(f32, f32)>,
+    /// The last control point of a quadratic curve, for `T`.
+    quad_control: Option<(f32, f32)>,
+    /// Whether the subpath was just closed, such that a new one must be
+    /// started explicitly.
+    closed: bool,
+    /// Whether a subpath was started yet.
+    started: bool,
+}
+
+impl<'a> PathBuilder<'a> {
+    fn new(content: &'a mut Content, transform: Transform) -> Self {
+        Self {
+            content,
+            transform,
+            current: (0.0, 0.0),
+            start: (0.0, 0.0),
+            cubic_control: None,
+            quad_control: None,
+            closed: false,
+            started: false,
+        }
+    }
+
+    /// Process a command with its arguments. Returns `None` if the arguments
+    /// are malformed.
+    fn command(&mut self, cmd: u8, parser: &mut Parser) -> Option<()> {
+        let relative = cmd.is_ascii_lowercase();
+        let upper = cmd.to_ascii_uppercase();
+        if upper != b'M' && upper != b'Z' && self.closed {
+            let (x, y) = self.map(self.start);
+            self.content.move_to(x, y);
+        }
+
+        let (cx, cy) = self.current;
+        let point = |parser: &mut Parser| {
+            let x = parser.number()?;
+            let y = parser.number()?;
+            Some(if relative { (cx + x, cy + y) } else { (x, y) })
+        };
+
+        let mut cubic_control = None;
+        let mut quad_control = None;
+        match upper {
+            b'M' => {
+                let p = point(parser)?;
+                self.move_to(p);
+                self.start = p;
+                self.started = true;
+            }
+            b'L' => {
+                let p = point(parser)?;
+                self.line_to(p);
+            }
+            b'H' => {
+                let x = parser.number()?;
+                self.line_to((if relative { cx + x } else { x }, cy));
+            }
+            b'V' => {
+                let y = parser.number()?;
+                self.line_to((cx, if relative { cy + y } else { y }));
+            }
+            b'C' => {
+                let p1 = point(parser)?;
+                let p2 = point(parser)?;
+                let p = point(parser)?;
+                self.cubic_to(p1, p2, p);
+                cubic_control = Some(p2);
+            }
+            b'S' => {
+                let p1 = reflect(self.cubic_control, self.current);
+                let p2 = point(parser)?;
+                let p = point(parser)?;
+                self.cubic_to(p1, p2, p);
+                cubic_control = Some(p2);
+            }
+            b'Q' => {
+                let p1 = point(parser)?;
+                let p = point(parser)?;
+                self.quad_to(p1, p);
+                quad_control = Some(p1);
+            }
+            b'T' => {
+                let p1 = reflect(self.quad_control, self.current);
+                let p = point(parser)?;
+                self.quad_to(p1, p);
+                quad_control = Some(p1);
+            }
+            b'A' => {
+                let rx = parser.number()?;
+                let ry = parser.number()?;
+                let rotation = parser.number()?;
+                let large_arc = parser.flag()?;
+                let sweep = parser.flag()?;
+                let p = point(parser)?;
+                self.arc_to((rx, ry), rotation, large_arc, sweep, p);
+            }
+            b'Z' => {
+                self.content.close_path();
+                self.current = self.start;
+                self.closed = true;
+                self.cubic_control = None;
+                self.quad_control = None;
+                return Some(());
+            }
+            _ => return None,
+        }
+
+        self.cubic_control = cubic_control;
+        self.quad_control = quad_control;
+        self.closed = false;
+        Some(())
+    }
+
+    fn move_to(&mut self, p: (f32, f32)) {
+        let (x, y) = self.map(p);
+        self.content.move_to(x, y);
+        self.current = p;
+    }
+
+    fn line_to(&mut self, p: (f32, f32)) {
+        let (x, y) = self.map(p);
+        self.content.line_to(x, y);
+        self.current = p;
+    }
+
+    fn cubic_to(&mut self, p1: (f32, f32), p2: (f32, f32), p: (f32, f32)) {
+        let (x1, y1) = self.map(p1);
+        let (x2, y2) = self.map(p2);
+        let (x3, y3) = self.map(p);
+        self.content.cubic_to(x1, y1, x2, y2, x3, y3);
+        self.current = p;
+    }
+
+    fn quad_to(&mut self, p1: (f32, f32), p: (f32, f32)) {
+        let (x1, y1) = self.map(p1);
+        let (x2, y2) = self.map(p);
+        self.content.quad_to(x1, y1, x2, y2);
+        self.current = p;
+    }
+
+    fn arc_to(
+        &mut self,
+        radii: (f32, f32),
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        p: (f32, f32),
+    ) {
+        if self.current == p {
+            return;
+        }
+
+        if radii.0 == 0.0 || radii.1 == 0.0 {
+            self.line_to(p);
+            return;
+        }
+
+        // The arc is approximated in SVG coordinates and then transformed,
+        // because an affine transform maps Bézier curves exactly.
+        let from = self.current;
+        arc_to_cubics(from, radii, rotation, large_arc, sweep, p, |c| {
+            self.cubic_to((c[0], c[1]), (c[2], c[3]), (c[4], c[5]));
+        });
+    }
+
+    fn map(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        self.transform.map_point(x, y)
+    }
+}
+
+/// Reflect a control point at the current point, or return the current point
+/// if there is no previous control point.
+fn reflect(control: Option<(f32, f32)>, (x, y): (f32, f32)) -> (f32, f32) {
+    match control {
+        Some((cx, cy)) => (2.0 * x - cx, 2.0 * y - cy),
+        None => (x, y),
+    }
+}
+
+/// Lexes the arguments of SVG path commands.
+struct Parser<'a> {
+    data: &'a [u8],
+    cursor: usize,
+}
+
+impl Parser<'_> {
+    /// Parse a number, including leading separators.
+    ///
+    /// Follows SVG's number grammar, so `1.5.5` are the two numbers `1.5` and
+    /// `.5` and `1-2` are the two numbers `1` and `-2`.
+    fn number(&mut self) -> Option<f32> {
+        self.skip_separators();
+        let start = self.cursor;
+        self.eat_if(|b| matches!(b, b'+' | b'-'));
+
+        let digits = self.eat_digits();
+        let fraction = if self.eat_if(|b| b == b'.') { self.eat_digits() } else { 0 };
+        if digits == 0 && fraction == 0 {
+            self.cursor = start;
+            return None;
+        }
+
+        // Only eat an exponent if it is complete, so that something like `1e`
+        // doesn't swallow the `e`.
+        let mantissa_end = self.cursor;
+        if self.eat_if(|b| matches!(b, b'e' | b'E')) {
+            self.eat_if(|b| matches!(b, b'+' | b'-'));
+            if self.eat_digits() == 0 {
+                self.cursor = mantissa_end;
+            }
+        }
+
+        let string = std::str::from_utf8(&self.data[start..self.cursor]).ok()?;
+        let value = string.parse::<f32>().ok().filter(|v| v.is_finite())?;
+        Some(value)
+    }
+
+    /// Parse an arc flag, including leading separators.
+    ///
+    /// Flags are single digits and need no separator after them.
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+        let value = match self.data.get(self.cursor)? {
+            b'0' => false,
+            b'1' => true,
+            _ => return None,
+        };
+        self.cursor += 1;
+        Some(value)
+    }
+
+    /// Skip whitespace and at most one comma.
+    fn skip_separators(&mut self) {
+        self.skip_whitespace();
+        if self.eat_if(|b| b == b',') {
+            self.skip_whitespace();
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.eat_if(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\x0c' | b'\r')) {}
+    }
+
+    fn eat_digits(&mut self) -> usize {
+        let start = self.cursor;
+        while self.eat_if(|b| b.is_ascii_digit()) {}
+        self.cursor - start
+    }
+
+    fn eat_if(&mut self, predicate: impl FnOnce(u8) -> bool) -> bool {
+        let matches = self.data.get(self.cursor).copied().is_some_and(predicate);
+        if matches {
+            self.cursor += 1;
+        }
+        matches
+    }
+}
+
+/// An error in SVG path data.
+///
+/// Holds the byte offset in the path data at which the error occurred.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SvgPathError(pub usize);
+
+impl Display for SvgPathError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "invalid path data at offset {}", self.0)
+    }
+}
+
+impl std::error::Error for SvgPathError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Op;
+
+    fn path(data: &str) -> Result<Vec<u8>, SvgPathError> {
+        let mut content = Content::new();
+        content.svg_path(data)?;
+        Ok(content.finish().into_vec())
+    }
+
+    #[test]
+    fn test_svg_path_commands() {
+        test!(
+            path("M10 20 30 40h5v-5L0 0zl1 1").unwrap(),
+            b"10 20 m",
+            b"30 40 l",
+            b"35 40 l",
+            b"35 35 l",
+            b"0 0 l",
+            b"h",
+            b"10 20 m",
+            b"11 21 l"
+        );
+
+        test!(
+            path("m1,1 c1 0 2 1 2 2s0 2 -1 2q3 0 3 3t3 3").unwrap(),
+            b"1 1 m",
+            b"2 1 3 2 3 3 c",
+            b"3 4 3 5 2 5 c",
+            b"4 5 5 6 5 8 c",
+            b"5 10 6 11 8 11 c"
+        );
+    }
+
+    #[test]
+    fn test_svg_path_numbers() {
+        test!(path("M1.5.5-1e1-2E+1").unwrap(), b"1.5 0.5 m", b"-10 -20 l");
+        // Two semicircles with flags that need no separator.
+        let data = path("M0 0a1 1 0 00 2 0a1,1,0,1,1,-2,0").unwrap();
+        let ops: Vec<_> = crate::ContentParser::new(&data).map(Result::unwrap).collect();
+        assert_eq!(ops.len(), 5);
+        assert!(matches!(ops[2], Op::CubicTo(.., 2.0, 0.0)));
+        assert!(matches!(ops[4], Op::CubicTo(.., 0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_svg_path_transform_and_errors() {
+        let mut content = Content::new();
+        content
+            .svg_path_with_transform("M0 0H10V10", [2.0, 0.0, 0.0, -2.0, 5.0, 100.0])
+            .unwrap();
+        test!(content.finish().into_vec(), b"5 100 m", b"25 100 l", b"25 80 l");
+
+        assert_eq!(path("L1 1"), Err(SvgPathError(0)));
+        assert_eq!(path("M1 1 L2"), Err(SvgPathError(7)));
+        assert_eq!(path("M1 1 Z 2 2"), Err(SvgPathError(7)));
+        assert_eq!(path("M0 0 A1 1 0 2 0 1 1"), Err(SvgPathError(12)));
+        assert_eq!(path("M0 0 X"), Err(SvgPathError(5)));
+        assert_eq!(path("M0 0 L1 1e"), Err(SvgPathError(9)));
+
+        // Everything up to the error is written.
+        let mut content = Content::new();
+        assert!(content.svg_path("M0 0 L1 1 L2").is_err());
+        test!(content.finish().into_vec(), b"0 0 m", b"1 1 l");
+    }
+}
@@ -0,0 +1,736 @@
+use std::collections::HashMap;
+
+use super::*;
+use crate::deflate::{self, Deflate};
+use crate::types::{FontFlags, SimpleEncoding, SystemInfo};
+
+/// A Type 1 font program for embedding into a simple font.
+///
+/// Reads fonts in the binary PFB or the textual PFA format and converts them
+/// into the form that PDF expects: The cleartext part, the binary encrypted
+/// part and the trailer, whose lengths are written as `/Length1`, `/Length2`
+/// and `/Length3` into the font file stream.
+///
+/// The font's name, bounding box, italic angle, built-in encoding and glyph
+/// widths are read from the program, so that [`write`](Self::write) can fill
+/// in the _Type 1 font dictionary_ and the font descriptor.
+///
+/// ```no_run
+/// use pdf_writer::{Chunk, Content, Name, Ref, Str, Type1Program};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("Corporate.pfb")?;
+/// let program = Type1Program::new(&data)?;
+///
+/// let mut content = Content::new();
+/// content.begin_text();
+/// content.set_font(Name(b"F1"), 12.0);
+/// content.show(Str(&program.encoding().encode("Hello")?));
+/// content.end_text();
+///
+/// let mut chunk = Chunk::new();
+/// let mut alloc = Ref::new(2);
+/// program.write(&mut chunk, Ref::new(1), &mut alloc);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Type1Program {
+    /// The three parts of the program, concatenated.
+    data: Vec<u8>,
+    lengths: [usize; 3],
+    font_name: String,
+    bbox: Rect,
+    italic_angle: f32,
+    fixed_pitch: bool,
+    /// Whether the font uses the standard encoding as its built-in encoding.
+    standard: bool,
+    encoding: SimpleEncoder,
+    /// The advance widths in thousandths of an em.
+    widths: HashMap<String, f32>,
+    stem_v: Option<f32>,
+    deflate: Option<Deflate>,
+}
+
+impl Type1Program {
+    /// Parse a font program in the PFB or PFA format.
+    pub fn new(data: &[u8]) -> Result<Self, FontError> {
+        let (data, lengths) = match data.first() {
+            Some(0x80) => split_pfb(data)?,
+            _ if data.starts_with(b"%!") => split_pfa(data)?,
+            _ => return Err(FontError::UnknownFormat),
+        };
+
+        let mut program = Self {
+            data: vec![],
+            lengths,
+            font_name: String::new(),
+            bbox: Rect::new(0.0, 0.0, 0.0, 0.0),
+            italic_angle: 0.0,
+            fixed_pitch: false,
+            standard: true,
+            encoding: SimpleEncoder::new(SimpleEncoding::Standard),
+            widths: HashMap::new(),
+            stem_v: None,
+            deflate: None,
+        };
+
+        let [length1, length2, _] = lengths;
+        let scale = program.parse_cleartext(&data[..length1])?;
+        program.parse_private(&data[length1..length1 + length2], scale)?;
+        program.data = data;
+        Ok(program)
+    }
+
+    /// Compress the font file stream with a [Deflate function](crate#compression).
+    pub fn deflate(&mut self, deflate: fn(&[u8]) -> Vec<u8>) -> &mut Self {
+        self.deflate = Some(deflate);
+        self
+    }
+
+    /// The PostScript name of the font.
+    pub fn font_name(&self) -> &str {
+        &self.font_name
+    }
+
+    /// The bounding box of all glyphs in thousandths of an em.
+    pub fn bbox(&self) -> Rect {
+        self.bbox
+    }
+
+    /// The italic angle in degrees counter-clockwise from the vertical.
+    pub fn italic_angle(&self) -> f32 {
+        self.italic_angle
+    }
+
+    /// Whether all glyphs have the same width.
+    pub fn is_fixed_pitch(&self) -> bool {
+        self.fixed_pitch
+    }
+
+    /// The font's built-in encoding.
+    ///
+    /// It is expressed as differences to the standard encoding, which apply to
+    /// the built-in encoding when written without a base encoding.
+    pub fn encoding(&self) -> &SimpleEncoder {
+        &self.encoding
+    }
+
+    /// The advance width of a glyph in thousandths of an em, that is, in text
+    /// space units for a font size of one.
+    pub fn glyph_width(&self, glyph: &str) -> Option<f32> {
+        self.widths.get(glyph).copied()
+    }
+
+    /// The font program in PDF's form: cleartext, binary encrypted part and
+    /// trailer.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The lengths of the cleartext part, the encrypted part and the trailer.
+    pub fn lengths(&self) -> [usize; 3] {
+        self.lengths
+    }
+
+    /// Write the font program as a stream, to be referenced with
+    /// [`FontDescriptor::font_file`].
+    pub fn write_font_file(&self, chunk: &mut Chunk, id: Ref) {
+        let data = deflate::compress(self.deflate, &self.data);
+
+        let mut stream = chunk.stream(id, &data);
+        let [length1, length2, length3] = self.lengths;
+        stream.pair(Name(b"Length1"), length1 as i32);
+        stream.pair(Name(b"Length2"), length2 as i32);
+        stream.pair(Name(b"Length3"), length3 as i32);
+        deflate::filter(self.deflate, &mut stream);
+    }
+
+    /// Write the _Type 1 font dictionary_ with the given ID and everything it
+    /// references.
+    ///
+    /// The font uses its built-in encoding. Its widths cover all codes that
+    /// the encoding maps to glyphs of the font and a `/ToUnicode` map is
+    /// written for them. Further IDs are taken from `alloc`.
+    ///
+    /// Type 1 programs don't store the ascent, descent and cap height, so the
+    /// font descriptor approximates them with the top and bottom of the
+    /// bounding box. The stem width is taken from `/StdVW` or, if the private
+    /// dictionary has none, assumed to be 80, a typical value for regular
+    /// weights.
+    pub fn write(&self, chunk: &mut Chunk, id: Ref, alloc: &mut Ref) {
+        let descriptor_id = alloc.bump();
+        let file_id = alloc.bump();
+        let cmap_id = alloc.bump();
+
+        let codes: Vec<u8> = (0..=255)
+            .filter(|&code| {
+                self.encoding.glyph(code).is_some_and(|glyph| {
+                    glyph != ".notdef" && self.widths.contains_key(glyph)
+                })
+            })
+            .collect();
+        let first = codes.first().copied().unwrap_or(0);
+        let last = codes.last().copied().unwrap_or(0);
+        let widths = (first..=last).map(|code| {
+            self.encoding
+                .glyph(code)
+                .and_then(|glyph| self.glyph_width(glyph))
+                .unwrap_or(0.0)
+        });
+
+        let base_font = Name(self.font_name.as_bytes());
+        chunk
+            .type1_font(id)
+            .base_font(base_font)
+            .first_char(first)
+            .last_char(last)
+            .widths(widths)
+            .font_descriptor(descriptor_id)
+            .to_unicode(cmap_id);
+
+        let mut flags =
+            if self.standard { FontFlags::NON_SYMBOLIC } else { FontFlags::SYMBOLIC };
+        flags.set(FontFlags::FIXED_PITCH, self.fixed_pitch);
+        flags.set(FontFlags::ITALIC, self.italic_angle != 0.0);
+        chunk
+            .font_descriptor(descriptor_id)
+            .name(base_font)
+            .flags(flags)
+            .bbox(self.bbox)
+            .italic_angle(self.italic_angle)
+            .ascent(self.bbox.y2)
+            .descent(self.bbox.y1)
+            .cap_height(self.bbox.y2)
+            .stem_v(self.stem_v.unwrap_or(80.0))
+            .font_file(file_id);
+
+        self.write_font_file(chunk, file_id);
+
+        let info = SystemInfo {
+            registry: Str(b"Adobe"),
+            ordering: Str(b"UCS"),
+            supplement: 0,
+        };
+        let cmap = self.encoding.to_unicode(Name(b"Custom"), info).finish();
+        chunk.cmap(cmap_id, &cmap).name(Name(b"Custom")).system_info(info);
+    }
+
+    /// Read the font dictionary entries from the cleartext part. Returns the
+    /// factor from glyph space to thousandths of an em.
+    fn parse_cleartext(&mut self, cleartext: &[u8]) -> Result<f32, FontError> {
+        let malformed = FontError::MalformedProgram("cleartext");
+        let mut tokens = Tokens(cleartext);
+        let mut bbox = None;
+        let mut scale = 1.0;
+        while let Some(token) = tokens.next() {
+            match token {
+                b"/FontName" => {
+                    let name = tokens.next().and_then(|t| t.strip_prefix(b"/"));
+                    let name = name.and_then(|n| std::str::from_utf8(n).ok());
+                    self.font_name = name.ok_or(malformed)?.into();
+                }
+                b"/FontBBox" => bbox = Some(tokens.numbers::<4>().ok_or(malformed)?),
+                b"/FontMatrix" => {
+                    scale = tokens.numbers::<6>().ok_or(malformed)?[0] * 1000.0
+                }
+                b"/ItalicAngle" => {
+                    self.italic_angle = tokens.number().ok_or(malformed)?
+                }
+                b"/isFixedPitch" => self.fixed_pitch = tokens.next() == Some(b"true"),
+                b"/Encoding" => self.parse_encoding(&mut tokens).ok_or(malformed)?,
+                b"eexec" => break,
+                _ => {}
+            }
+        }
+
+        if self.font_name.is_empty() {
+            return Err(malformed);
+        }
+
+        let [x1, y1, x2, y2] = bbox.ok_or(malformed)?.map(|v| v * scale);
+        self.bbox = Rect::new(x1, y1, x2, y2);
+        Ok(scale)
+    }
+
+    /// Read the built-in encoding, which is either the standard encoding or
+    /// an array that is filled with `dup code /name put`.
+    fn parse_encoding(&mut self, tokens: &mut Tokens) -> Option<()> {
+        let mut glyphs: Vec<Option<&[u8]>> = vec![None; 256];
+        match tokens.next()? {
+            b"StandardEncoding" => return Some(()),
+            b"256" => {}
+            _ => return None,
+        }
+
+        while let Some(token) = tokens.next() {
+            match token {
+                b"def" | b"readonly" => break,
+                b"dup" => {
+                    let code = tokens.number()? as usize;
+                    let name = tokens.next()?.strip_prefix(b"/")?;
+                    *glyphs.get_mut(code)? = Some(name);
+                }
+                _ => {}
+            }
+        }
+
+        self.standard = false;
+        self.encoding = SimpleEncoder::with_differences(
+            SimpleEncoding::Standard,
+            glyphs.iter().enumerate().map(|(code, glyph)| {
+                let glyph = glyph.and_then(|g| std::str::from_utf8(g).ok());
+                (code as u8, glyph.unwrap_or(".notdef"))
+            }),
+        );
+        Some(())
+    }
+
+    /// Decrypt the private part and read the glyph widths from the
+    /// charstrings.
+    fn parse_private(&mut self, encrypted: &[u8], scale: f32) -> Result<(), FontError> {
+        let malformed = FontError::MalformedProgram("private part");
+        let private = decrypt(encrypted, 55665);
+        let private = private.get(4..).ok_or(malformed)?;
+
+        let mut tokens = Tokens(private);
+        let mut len_iv = Some(4);
+        let mut prev: &[u8] = b"";
+        while let Some(token) = tokens.next() {
+            match token {
+                // The binary data of subroutines.
+                b"RD" | b"-|" => {
+                    let length =
+                        std::str::from_utf8(prev).ok().and_then(|n| n.parse().ok());
+                    tokens.binary(length.ok_or(malformed)?).ok_or(malformed)?;
+                }
+                // A negative value means that the charstrings aren't encrypted.
+                b"/lenIV" => {
+                    let n = tokens.number().ok_or(malformed)? as i32;
+                    len_iv = usize::try_from(n).ok();
+                }
+                b"/StdVW" => {
+                    self.stem_v = Some(tokens.numbers::<1>().ok_or(malformed)?[0] * scale)
+                }
+                b"/CharStrings" => break,
+                _ => {}
+            }
+            prev = token;
+        }
+
+        // Skip to the first glyph and then read `/name length RD binary ND`.
+        while tokens.peek().is_some_and(|t| !t.starts_with(b"/")) {
+            tokens.next();
+        }
+
+        while let Some(name) = tokens.next().and_then(|t| t.strip_prefix(b"/")) {
+            let length = tokens.number().ok_or(malformed)? as usize;
+            tokens.next().ok_or(malformed)?;
+            let code = tokens.binary(length).ok_or(malformed)?;
+            tokens.next().ok_or(malformed)?;
+
+            let decrypted;
+            let code = match len_iv {
+                Some(len_iv) => {
+                    decrypted = decrypt(code, 4330);
+                    decrypted.get(len_iv..).unwrap_or(&[])
+                }
+                None => code,
+            };
+
+            if let (Some(width), Ok(name)) =
+                (char_string_width(code), std::str::from_utf8(name))
+            {
+                self.widths.insert(name.into(), width * scale);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Split a PFB file into its ASCII and binary segments.
+fn split_pfb(data: &[u8]) -> Result<(Vec<u8>, [usize; 3]), FontError> {
+    let malformed = FontError::MalformedProgram("PFB segments");
+    let mut out = Vec::with_capacity(data.len());
+    let mut lengths = [0; 3];
+    let mut rest = data;
+    loop {
+        let &[0x80, kind, ..] = rest else { return Err(malformed) };
+        if kind == 3 {
+            break;
+        }
+
+        let size: [u8; 4] = rest.get(2..6).ok_or(malformed)?.try_into().unwrap();
+        let size = u32::from_le_bytes(size) as usize;
+        let segment = rest.get(6..6 + size).ok_or(malformed)?;
+        let part = match kind {
+            1 if lengths[1] == 0 => 0,
+            1 => 2,
+            2 if lengths[2] == 0 => 1,
+            _ => return Err(malformed),
+        };
+        out.extend(segment);
+        lengths[part] += size;
+        rest = &rest[6 + size..];
+        if rest.is_empty() {
+            break;
+        }
+    }
+
+    if lengths[1] == 0 {
+        return Err(malformed);
+    }
+    Ok((out, lengths))
+}
+
+/// Split a PFA file into its parts and convert the encrypted part from
+/// hexadecimal to binary.
+fn split_pfa(data: &[u8]) -> Result<(Vec<u8>, [usize; 3]), FontError> {
+    let malformed = FontError::MalformedProgram("PFA sections");
+    let eexec = memchr::memmem::find(data, b"eexec").ok_or(malformed)?;
+    let mut start = eexec + 5;
+    while data.get(start).is_some_and(u8::is_ascii_whitespace) {
+        start += 1;
+    }
+
+    // The trailer starts with lines of zeros followed by `cleartomark`.
+    let mark = memchr::memmem::rfind(data, b"cleartomark").ok_or(malformed)?;
+    let mut end = mark;
+    while end > start && (data[end - 1] == b'0' || data[end - 1].is_ascii_whitespace()) {
+        end -= 1;
+    }
+    while end < mark && !matches!(data[end], b'\n' | b'\r') {
+        end += 1;
+    }
+    while end < mark && matches!(data[end], b'\n' | b'\r') {
+        end += 1;
+    }
+
+    let mut out = data[..start].to_vec();
+    let mut high = None;
+    for &byte in &data[start..end] {
+        let nibble = match byte {
+            b'0'..=b'9' => byte - b'0',
+            b'a'..=b'f' => byte - b'a' + 10,
+            b'A'..=b'F' => byte - b'A' + 10,
+            _ if byte.is_ascii_whitespace() => continue,
+            _ => return Err(malformed),
+        };
+        match high.take() {
+            Some(high) => out.push(high << 4 | nibble),
+            None => high = Some(nibble),
+        }
+    }
+
+    let length2 = out.len() - start;
+    out.extend(&data[end..]);
+    Ok((out, [start, length2, data.len() - end]))
+}
+
+/// Decrypt eexec-encrypted data or a charstring with the given key.
+fn decrypt(data: &[u8], mut r: u16) -> Vec<u8> {
+    data.iter()
+        .map(|&cipher| {
+            let plain = cipher ^ (r >> 8) as u8;
+            r = (u16::from(cipher).wrapping_add(r))
+                .wrapping_mul(52845)
+                .wrapping_add(22719);
+            plain
+        })
+        .collect()
+}
+
+/// Read the advance width from the `hsbw` or `sbw` command at the start of a
+/// decrypted Type 1 charstring.
+fn char_string_width(code: &[u8]) -> Option<f32> {
+    let mut stack = vec![];
+    let mut iter = code.iter().copied();
+    while let Some(b0) = iter.next() {
+        match b0 {
+            // hsbw
+            13 => return stack.get(1).copied(),
+            // sbw
+            12 if iter.next()? == 7 => return stack.get(2).copied(),
+            32..=246 => stack.push(f32::from(b0) - 139.0),
+            247..=250 => stack
+                .push((f32::from(b0) - 247.0) * 256.0 + f32::from(iter.next()?) + 108.0),
+            251..=254 => stack
+                .push(-(f32::from(b0) - 251.0) * 256.0 - f32::from(iter.next()?) - 108.0),
+            255 => {
+                let bytes = [iter.next()?, iter.next()?, iter.next()?, iter.next()?];
+                stack.push(i32::from_be_bytes(bytes) as f32);
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// A minimal tokenizer for the PostScript code of Type 1 fonts.
+struct Tokens<'a>(&'a [u8]);
+
+impl<'a> Tokens<'a> {
+    /// The next token without consuming it.
+    fn peek(&self) -> Option<&'a [u8]> {
+        Tokens(self.0).next()
+    }
+
+    /// Read a number.
+    fn number(&mut self) -> Option<f32> {
+        std::str::from_utf8(self.next()?).ok()?.parse().ok()
+    }
+
+    /// Read an array or procedure of exactly `N` numbers.
+    fn numbers<const N: usize>(&mut self) -> Option<[f32; N]> {
+        if !matches!(self.next()?, b"[" | b"{") {
+            return None;
+        }
+        let mut numbers = [0.0; N];
+        for number in &mut numbers {
+            *number = self.number()?;
+        }
+        matches!(self.next()?, b"]" | b"}").then_some(numbers)
+    }
+
+    /// Read `n` bytes of binary data that follow a token after a single
+    /// space.
+    fn binary(&mut self, n: usize) -> Option<&'a [u8]> {
+        let data = self.0.get(1..1 + n)?;
+        self.0 = &self.0[1 + n..];
+        Some(data)
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.0.iter().position(|b| !b.is_ascii_whitespace())?;
+            self.0 = &self.0[start..];
+            if self.0[0] != b'%' {
+                break;
+            }
+            let end = self.0.iter().position(|&b| matches!(b, b'\n' | b'\r'));
+            self.0 = &self.0[end.unwrap_or(self.0.len())..];
+        }
+
+        // Every token is at least one byte long, such that stray closing
+        // delimiters can't stall the tokenizer.
+        let data = self.0;
+        let len = match data[0] {
+            b'<' | b'>' if data.get(1) == Some(&data[0]) => 2,
+            b'[' | b']' | b'{' | b'}' | b')' | b'>' => 1,
+            b'(' => {
+                let mut depth = 0;
+                let mut escaped = false;
+                let mut len = data.len();
+                for (i, &b) in data.iter().enumerate() {
+                    match b {
+                        _ if escaped => escaped = false,
+                        b'\\' => escaped = true,
+                        b'(' => depth += 1,
+                        b')' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                len = i + 1;
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                len
+            }
+            b'<' => data.iter().position(|&b| b == b'>').map_or(data.len(), |i| i + 1),
+            first => {
+                let is_delimiter =
+                    |b: &u8| b.is_ascii_whitespace() || b"[]{}()<>/%".contains(b);
+                let skip = usize::from(first == b'/');
+                data[skip..]
+                    .iter()
+                    .position(is_delimiter)
+                    .map_or(data.len(), |i| i + skip)
+            }
+        };
+
+        self.0 = &data[len..];
+        Some(&data[..len])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encrypt(data: &[u8], mut r: u16) -> Vec<u8> {
+        [0; 4]
+            .iter()
+            .chain(data)
+            .map(|&plain| {
+                let cipher = plain ^ (r >> 8) as u8;
+                r = (u16::from(cipher).wrapping_add(r))
+                    .wrapping_mul(52845)
+                    .wrapping_add(22719);
+                cipher
+            })
+            .collect()
+    }
+
+    /// The cleartext, encrypted part and trailer of a font with the glyphs
+    /// `.notdef` and `A`, where `A` is encoded as code 1.
+    ///
+    /// The charstrings are encrypted with four random bytes if `len_iv` is 4
+    /// and stored in plain if it is -1.
+    fn test_parts(len_iv: i32) -> [Vec<u8>; 3] {
+        let cleartext = b"%!FontType1-1.0: Test 001.000\n\
+            12 dict begin\n\
+            /FontName /Test def\n\
+            /FontMatrix [0.001 0 0 0.001 0 0] readonly def\n\
+            /FontBBox {-10 -200 600 800} readonly def\n\
+            /ItalicAngle -12.5 def\n\
+            /Encoding 256 array\n\
+            0 1 255 {1 index exch /.notdef put} for\n\
+            dup 1 /A put\n\
+            readonly def\n\
+            currentdict end\n\
+            currentfile eexec\n";
+
+        // hsbw with a side bearing of 10 and widths of 250 and 600. The
+        // subroutine contains a parenthesis to check that binary data is
+        // skipped.
+        let charstring = |code: &[u8]| match len_iv {
+            4 => encrypt(code, 4330),
+            _ => code.to_vec(),
+        };
+        let notdef = charstring(&[149, 247, 142, 13, 14]);
+        let a = charstring(&[149, 248, 236, 13, 14]);
+        let subr = charstring(b"(");
+        let mut private = b"dup /Private 8 dict dup begin\n".to_vec();
+        if len_iv != 4 {
+            private.extend(format!("/lenIV {len_iv} def\n").as_bytes());
+        }
+        private.extend(b"/StdVW [88] def\n/Subrs 1 array\n");
+        private.extend(format!("dup 0 {} RD ", subr.len()).as_bytes());
+        private.extend(subr);
+        private.extend(b" NP\nND\n2 index /CharStrings 2 dict dup begin\n");
+        private.extend(format!("/.notdef {} RD ", notdef.len()).as_bytes());
+        private.extend(notdef);
+        private.extend(format!(" ND\n/A {} RD ", a.len()).as_bytes());
+        private.extend(a);
+        private.extend(b" ND\nend\nend\nmark currentfile closefile\n");
+
+        let mut trailer = [b'0'; 64].iter().chain(b"\n").copied().cycle().take(65 * 8);
+        let mut trailer: Vec<u8> = trailer.by_ref().collect();
+        trailer.extend(b"cleartomark\n");
+        [cleartext.to_vec(), encrypt(&private, 55665), trailer]
+    }
+
+    fn test_pfb(parts: [Vec<u8>; 3]) -> Vec<u8> {
+        let mut pfb = vec![];
+        for (kind, part) in [1, 2, 1].into_iter().zip(parts) {
+            pfb.extend([0x80, kind]);
+            pfb.extend((part.len() as u32).to_le_bytes());
+            pfb.extend(part);
+        }
+        pfb.extend([0x80, 3]);
+        pfb
+    }
+
+    #[test]
+    fn test_type1_pfb_and_pfa() {
+        let [cleartext, encrypted, trailer] = test_parts(4);
+        let mut pfa = cleartext.clone();
+        for line in encrypted.chunks(32) {
+            for byte in line {
+                pfa.extend(format!("{byte:02x}").as_bytes());
+            }
+            pfa.push(b'\n');
+        }
+        pfa.extend(&trailer);
+
+        let lengths = [cleartext.len(), encrypted.len(), trailer.len()];
+        let expected = [cleartext, encrypted, trailer].concat();
+        for data in [test_pfb(test_parts(4)), pfa] {
+            let program = Type1Program::new(&data).unwrap();
+            assert_eq!(program.lengths(), lengths);
+            assert_eq!(program.as_bytes(), expected);
+            assert_eq!(program.font_name(), "Test");
+            assert_eq!(program.bbox(), Rect::new(-10.0, -200.0, 600.0, 800.0));
+            assert_eq!(program.italic_angle(), -12.5);
+            assert_eq!(program.glyph_width("A"), Some(600.0));
+            assert_eq!(program.glyph_width(".notdef"), Some(250.0));
+            assert_eq!(program.encoding().encode("A").unwrap(), [1]);
+        }
+
+        assert!(matches!(Type1Program::new(b"wOFF"), Err(FontError::UnknownFormat)));
+    }
+
+    #[test]
+    fn test_type1_tokens() {
+        let data = b"/FontInfo << /a 1 >> def <48> ) > (x(y)) [/b{c}] % d\n/e";
+        let tokens: Vec<_> =
+            Tokens(data).map(|t| std::str::from_utf8(t).unwrap()).collect();
+        assert_eq!(
+            tokens,
+            [
+                "/FontInfo",
+                "<<",
+                "/a",
+                "1",
+                ">>",
+                "def",
+                "<48>",
+                ")",
+                ">",
+                "(x(y))",
+                "[",
+                "/b",
+                "{",
+                "c",
+                "}",
+                "]",
+                "/e"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_type1_font_info_dict() {
+        let [cleartext, encrypted, trailer] = test_parts(4);
+        let cleartext = String::from_utf8(cleartext).unwrap().replace(
+            "/ItalicAngle -12.5 def",
+            "/FontInfo << /Notice (a) /ItalicAngle -11 /isFixedPitch true >> def",
+        );
+
+        let program =
+            Type1Program::new(&test_pfb([cleartext.into(), encrypted, trailer])).unwrap();
+        assert_eq!(program.font_name(), "Test");
+        assert_eq!(program.italic_angle(), -11.0);
+        assert_eq!(program.glyph_width("A"), Some(600.0));
+    }
+
+    #[test]
+    fn test_type1_unencrypted_charstrings() {
+        let program = Type1Program::new(&test_pfb(test_parts(-1))).unwrap();
+        assert_eq!(program.glyph_width("A"), Some(600.0));
+        assert_eq!(program.glyph_width(".notdef"), Some(250.0));
+    }
+
+    #[test]
+    fn test_type1_write() {
+        let program = Type1Program::new(&test_pfb(test_parts(4))).unwrap();
+        let mut chunk = Chunk::new();
+        let mut alloc = Ref::new(2);
+        program.write(&mut chunk, Ref::new(1), &mut alloc);
+        assert_eq!(alloc, Ref::new(5));
+
+        let output = String::from_utf8_lossy(chunk.as_bytes());
+        assert!(output
+            .contains("/BaseFont /Test\n  /FirstChar 1\n  /LastChar 1\n  /Widths [600]"));
+        assert!(output.contains("/Flags 68\n"));
+        assert!(output.contains("/StemV 88\n  /FontFile 3 0 R"));
+        assert!(output.contains("/Length1 296\n  /Length2 209\n  /Length3 532\n"));
+        assert!(output.contains("<01> <0041>"));
+    }
+}
@@ -0,0 +1,346 @@
+use super::*;
+use crate::deflate::{self, Deflate};
+use crate::encoding::char_to_glyph;
+use crate::types::{CmapBuilder, SystemInfo};
+
+/// Builds _Type 3_ fonts from glyph descriptions given as content streams.
+///
+/// Each added glyph gets the next free character code. Since codes of simple
+/// fonts are just one byte long, a new font is started after every 256
+/// glyphs. Once all glyphs are added, [`write`](Self::write) produces the
+/// fonts together with their glyph streams and `/ToUnicode` character maps.
+///
+/// Glyphs are described in glyph space, which maps to text space through the
+/// [font matrix](Self::matrix). By default, 1000 units are one em, like in
+/// most other fonts.
+///
+/// ```
+/// use pdf_writer::{Chunk, Content, Name, Rect, Ref, Str, Type3Builder};
+///
+/// let mut alloc = Ref::new(1);
+/// let mut builder = Type3Builder::new();
+///
+/// let mut square = Content::new();
+/// square.rect(50.0, 0.0, 500.0, 500.0).fill_nonzero();
+/// let bbox = Rect::new(50.0, 0.0, 550.0, 500.0);
+/// let (font_id, code) = builder.add_glyph(&mut alloc, square, 600.0, bbox, Some("■"));
+///
+/// let mut content = Content::new();
+/// content.begin_text();
+/// content.set_font(Name(b"F1"), 12.0);
+/// content.show(Str(&[code]));
+/// content.end_text();
+///
+/// let mut chunk = Chunk::new();
+/// builder.write(&mut chunk, &mut alloc);
+/// ```
+///
+/// Glyphs may use resources like images and shadings. These must be
+/// registered with the builder's [resource collector](Self::resources), which
+/// is written into each of the fonts.
+pub struct Type3Builder {
+    fonts: Vec<(Ref, Vec<Type3Glyph>)>,
+    matrix: [f32; 6],
+    resources: ResourceCollector,
+    deflate: Option<Deflate>,
+}
+
+/// A glyph of a Type 3 font.
+struct Type3Glyph {
+    name: String,
+    content: Buf,
+    advance: f32,
+    bbox: Rect,
+    text: Option<String>,
+}
+
+impl Type3Builder {
+    /// Create a new builder without any glyphs.
+    pub fn new() -> Self {
+        Self {
+            fonts: vec![],
+            matrix: [0.001, 0.0, 0.0, 0.001, 0.0, 0.0],
+            resources: ResourceCollector::new(),
+            deflate: None,
+        }
+    }
+
+    /// Set the matrix that maps glyph space to text space for all fonts.
+    pub fn matrix(&mut self, matrix: impl Into<[f32; 6]>) -> &mut Self {
+        self.matrix = matrix.into();
+        self
+    }
+
+    /// Compress the glyph streams and character maps with a
+    /// [Deflate function](crate#compression).
+    pub fn deflate(&mut self, deflate: fn(&[u8]) -> Vec<u8>) -> &mut Self {
+        self.deflate = Some(deflate);
+        self
+    }
+
+    /// The resources that the glyph descriptions use.
+    pub fn resources(&mut self) -> &mut ResourceCollector {
+        &mut self.resources
+    }
+
+    /// Add a glyph that only describes a shape and return the font and
+    /// character code to show it with.
+    ///
+    /// The glyph is painted in the current fill or stroke color, so its
+    /// content must not set colors or use images other than stencil masks.
+    /// The `advance` and `bbox` are in glyph space. The `text` is what the
+    /// glyph stands for and is used for text extraction. The ID of a new font
+    /// is taken from `alloc` when the previous one is full.
+    pub fn add_glyph(
+        &mut self,
+        alloc: &mut Ref,
+        content: Content,
+        advance: f32,
+        bbox: Rect,
+        text: Option<&str>,
+    ) -> (Ref, u8) {
+        let mut start = Content::new();
+        start.start_shape_glyph(advance, bbox.x1, bbox.y1, bbox.x2, bbox.y2);
+        self.push(alloc, start, content, advance, bbox, text)
+    }
+
+    /// Add a glyph that sets its own colors, like an emoji, and return the
+    /// font and character code to show it with.
+    ///
+    /// See [`add_glyph`](Self::add_glyph) for the meaning of the arguments.
+    /// The `bbox` is only used for the font's bounding box.
+    pub fn add_color_glyph(
+        &mut self,
+        alloc: &mut Ref,
+        content: Content,
+        advance: f32,
+        bbox: Rect,
+        text: Option<&str>,
+    ) -> (Ref, u8) {
+        let mut start = Content::new();
+        start.start_color_glyph(advance);
+        self.push(alloc, start, content, advance, bbox, text)
+    }
+
+    /// The number of fonts that were started so far.
+    pub fn num_fonts(&self) -> usize {
+        self.fonts.len()
+    }
+
+    /// Write all fonts and the objects belonging to them.
+    ///
+    /// The glyph streams and character maps use IDs from `alloc`, which is
+    /// advanced accordingly.
+    pub fn write(&self, chunk: &mut Chunk, alloc: &mut Ref) {
+        for (id, glyphs) in &self.fonts {
+            self.write_font(chunk, *id, glyphs, alloc);
+        }
+    }
+
+    fn push(
+        &mut self,
+        alloc: &mut Ref,
+        start: Content,
+        content: Content,
+        advance: f32,
+        bbox: Rect,
+        text: Option<&str>,
+    ) -> (Ref, u8) {
+        if self.fonts.last().is_none_or(|(_, glyphs)| glyphs.len() == 256) {
+            self.fonts.push((alloc.bump(), vec![]));
+        }
+
+        let (id, glyphs) = self.fonts.last_mut().unwrap();
+        let code = glyphs.len() as u8;
+
+        // Name the glyph after the character it stands for if that is
+        // unambiguous.
+        let mut chars = text.unwrap_or_default().chars();
+        let name = match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(char_to_glyph(c))
+                .filter(|name| glyphs.iter().all(|glyph| glyph.name != *name)),
+            _ => None,
+        };
+        let name = name.map_or_else(|| format!("g{code}"), Into::into);
+
+        let mut buf = start.finish();
+        let content = content.finish();
+        if !content.is_empty() {
+            buf.push(b'\n');
+            buf.extend_buf(&content);
+        }
+
+        glyphs.push(Type3Glyph {
+            name,
+            content: buf,
+            advance,
+            bbox,
+            text: text.map(Into::into),
+        });
+
+        (*id, code)
+    }
+
+    fn write_font(
+        &self,
+        chunk: &mut Chunk,
+        id: Ref,
+        glyphs: &[Type3Glyph],
+        alloc: &mut Ref,
+    ) {
+        let proc_ids: Vec<Ref> = glyphs.iter().map(|_| alloc.bump()).collect();
+        let has_text = glyphs.iter().any(|glyph| glyph.text.is_some());
+        let cmap_id = has_text.then(|| alloc.bump());
+
+        let bbox = glyphs
+            .iter()
+            .map(|glyph| glyph.bbox)
+            .reduce(|a, b| {
+                Rect::new(a.x1.min(b.x1), a.y1.min(b.y1), a.x2.max(b.x2), a.y2.max(b.y2))
+            })
+            .unwrap_or(Rect::new(0.0, 0.0, 0.0, 0.0));
+
+        let mut font = chunk.type3_font(id);
+        font.bbox(bbox).matrix(self.matrix);
+        font.char_procs().pairs(
+            glyphs
+                .iter()
+                .zip(&proc_ids)
+                .map(|(glyph, &id)| (Name(glyph.name.as_bytes()), id)),
+        );
+        font.encoding_custom()
+            .differences()
+            .consecutive(0, glyphs.iter().map(|glyph| Name(glyph.name.as_bytes())));
+        font.first_char(0)
+            .last_char((glyphs.len() - 1) as u8)
+            .widths(glyphs.iter().map(|glyph| glyph.advance));
+        if !self.resources.is_empty() {
+            self.resources.write(&mut font.resources());
+        }
+        if let Some(cmap_id) = cmap_id {
+            font.to_unicode(cmap_id);
+        }
+        font.finish();
+
+        for (glyph, &proc_id) in glyphs.iter().zip(&proc_ids) {
+            let data = deflate::compress(self.deflate, glyph.content.as_slice());
+            deflate::filter(self.deflate, &mut chunk.stream(proc_id, &data));
+        }
+
+        if let Some(cmap_id) = cmap_id {
+            let info = SystemInfo {
+                registry: Str(b"Adobe"),
+                ordering: Str(b"Identity"),
+                supplement: 0,
+            };
+            let mut cmap = CmapBuilder::new(Name(b"Custom"), info);
+            cmap.codespace_range(&[0x00], &[0xFF]);
+            for (code, glyph) in glyphs.iter().enumerate() {
+                if let Some(text) = glyph.text.as_deref().filter(|text| !text.is_empty())
+                {
+                    cmap.bf_char(&[code as u8], text);
+                }
+            }
+            let cmap = cmap.finish();
+            let data = deflate::compress(self.deflate, cmap.as_slice());
+            let mut cmap = chunk.cmap(cmap_id, &data);
+            cmap.name(Name(b"Custom")).system_info(info);
+            deflate::filter(self.deflate, &mut cmap);
+        }
+    }
+}
+
+impl Default for Type3Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type3_builder() {
+        let mut alloc = Ref::new(1);
+        let mut builder = Type3Builder::new();
+
+        let mut content = Content::new();
+        content.rect(0.0, 0.0, 500.0, 700.0).fill_nonzero();
+        let bbox = Rect::new(0.0, 0.0, 500.0, 700.0);
+        let a = builder.add_glyph(&mut alloc, content, 600.0, bbox, Some("A"));
+        let bbox = Rect::new(-10.0, -200.0, 400.0, 500.0);
+        let b = builder.add_color_glyph(&mut alloc, Content::new(), 400.0, bbox, None);
+        assert_eq!(a, (Ref::new(1), 0));
+        assert_eq!(b, (Ref::new(1), 1));
+
+        let mut chunk = Chunk::new();
+        builder.write(&mut chunk, &mut alloc);
+        assert_eq!(alloc, Ref::new(5));
+
+        let bytes = chunk.as_bytes();
+        let cmap = bytes.windows(7).position(|w| w == b"4 0 obj").unwrap();
+        let text = std::str::from_utf8(&bytes[cmap..]).unwrap();
+        assert!(text.contains("1 beginbfchar\n<00> <0041>\nendbfchar\n"));
+        test!(
+            &bytes[..cmap + 7],
+            b"1 0 obj",
+            b"<<",
+            b"  /Type /Font",
+            b"  /Subtype /Type3",
+            b"  /FontBBox [-10 -200 500 700]",
+            b"  /FontMatrix [0.001 0 0 0.001 0 0]",
+            b"  /CharProcs <<",
+            b"    /A 2 0 R",
+            b"    /g1 3 0 R",
+            b"  >>",
+            b"  /Encoding <<",
+            b"    /Type /Encoding",
+            b"    /Differences [0 /A /g1]",
+            b"  >>",
+            b"  /FirstChar 0",
+            b"  /LastChar 1",
+            b"  /Widths [600 400]",
+            b"  /ToUnicode 4 0 R",
+            b">>",
+            b"endobj",
+            b"",
+            b"2 0 obj",
+            b"<<",
+            b"  /Length 37",
+            b">>",
+            b"stream",
+            b"600 0 0 0 500 700 d1",
+            b"0 0 500 700 re",
+            b"f",
+            b"endstream",
+            b"endobj",
+            b"",
+            b"3 0 obj",
+            b"<<",
+            b"  /Length 8",
+            b">>",
+            b"stream",
+            b"400 0 d0",
+            b"endstream",
+            b"endobj",
+            b"",
+            b"4 0 obj",
+        );
+    }
+
+    #[test]
+    fn test_type3_builder_splits_fonts() {
+        let mut alloc = Ref::new(1);
+        let mut builder = Type3Builder::new();
+        let bbox = Rect::new(0.0, 0.0, 1.0, 1.0);
+        let glyphs: Vec<_> = (0..300)
+            .map(|_| builder.add_glyph(&mut alloc, Content::new(), 1.0, bbox, Some("x")))
+            .collect();
+        assert_eq!(builder.num_fonts(), 2);
+        assert_eq!(glyphs[0], (Ref::new(1), 0));
+        assert_eq!(glyphs[255], (Ref::new(1), 255));
+        assert_eq!(glyphs[256], (Ref::new(2), 0));
+        assert_eq!(glyphs[299], (Ref::new(2), 43));
+    }
+}
@@ -0,0 +1,368 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Tracks the graphics object state machine of a content stream.
+///
+/// See section 8.2 and figure 9 of the PDF 1.7 specification.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Validator {
+    state: State,
+    /// Offsets of open `q`, `BMC`/`BDC` and `BX` operators.
+    saves: Vec<usize>,
+    marked: Vec<usize>,
+    compat: Vec<usize>,
+    /// The number of operators checked so far.
+    count: usize,
+    violations: Vec<Violation>,
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+enum State {
+    #[default]
+    Page,
+    Path(usize),
+    Clip(usize),
+    Text(usize),
+}
+
+impl State {
+    fn object(self) -> GraphicsObject {
+        match self {
+            Self::Page => GraphicsObject::PageDescription,
+            Self::Path(_) => GraphicsObject::Path,
+            Self::Clip(_) => GraphicsObject::ClippingPath,
+            Self::Text(_) => GraphicsObject::Text,
+        }
+    }
+}
+
+impl Validator {
+    /// Check the next operator, which starts at `offset` in the stream.
+    pub(crate) fn check(&mut self, operator: &str, offset: usize) {
+        let first = self.count == 0;
+        self.count += 1;
+
+        // Nesting is tracked regardless of the state, such that a misplaced
+        // operator doesn't also result in a balancing error later.
+        match operator {
+            "q" => self.saves.push(offset),
+            "Q" => self.close(operator, offset, Stack::Saves),
+            "BMC" | "BDC" => self.marked.push(offset),
+            "EMC" => self.close(operator, offset, Stack::Marked),
+            "BX" => self.compat.push(offset),
+            "EX" => self.close(operator, offset, Stack::Compat),
+            _ => {}
+        }
+
+        let allowed = match (self.state, operator) {
+            // Operators that are allowed at the page description level and in
+            // text objects.
+            (
+                State::Page | State::Text(_),
+                "w" | "J" | "j" | "M" | "d" | "ri" | "i" | "gs" | "CS" | "cs" | "SC"
+                | "SCN" | "sc" | "scn" | "G" | "g" | "RG" | "rg" | "K" | "k" | "Tc"
+                | "Tw" | "Tz" | "TL" | "Tf" | "Tr" | "Ts" | "MP" | "DP" | "BMC" | "BDC"
+                | "EMC" | "BX" | "EX",
+            ) => true,
+
+            (State::Page, "q" | "Q" | "cm" | "sh" | "Do" | "BI" | "ID" | "EI") => true,
+            (State::Page, "m" | "re") => {
+                self.state = State::Path(offset);
+                true
+            }
+            (State::Page, "BT") => {
+                self.state = State::Text(offset);
+                true
+            }
+            (State::Page, "d0" | "d1") => {
+                if !first {
+                    self.report(operator, offset, ViolationKind::NotFirst);
+                }
+                true
+            }
+            (State::Page, "ET") => {
+                self.report(operator, offset, ViolationKind::Unbalanced);
+                true
+            }
+
+            (State::Path(_), "m" | "l" | "c" | "v" | "y" | "h" | "re") => true,
+            (State::Path(start), "W" | "W*") => {
+                self.state = State::Clip(start);
+                true
+            }
+            (
+                State::Path(_) | State::Clip(_),
+                "S" | "s" | "f" | "F" | "f*" | "B" | "B*" | "b" | "b*" | "n",
+            ) => {
+                self.state = State::Page;
+                true
+            }
+
+            (State::Text(_), "Td" | "TD" | "Tm" | "T*" | "Tj" | "TJ" | "'" | "\"") => {
+                true
+            }
+            (State::Text(_), "ET") => {
+                self.state = State::Page;
+                true
+            }
+
+            // Operators that are unknown to us or that can occur in no state
+            // (like inline image data) are ignored.
+            (_, op) => !KNOWN.contains(&op),
+        };
+
+        if !allowed {
+            let kind = ViolationKind::NotAllowed(self.state.object());
+            self.report(operator, offset, kind);
+        }
+    }
+
+    /// The violations found so far, plus the ones resulting from the stream
+    /// ending in the current state.
+    pub(crate) fn violations(&self) -> Vec<Violation> {
+        let mut violations = self.violations.clone();
+        let mut unclosed = |operator: &str, offset| {
+            violations.push(Violation {
+                offset,
+                operator: operator.into(),
+                kind: ViolationKind::Unclosed,
+            });
+        };
+
+        match self.state {
+            State::Page => {}
+            State::Path(offset) | State::Clip(offset) => unclosed("m", offset),
+            State::Text(offset) => unclosed("BT", offset),
+        }
+
+        for &offset in &self.saves {
+            unclosed("q", offset);
+        }
+        for &offset in &self.marked {
+            unclosed("BMC", offset);
+        }
+        for &offset in &self.compat {
+            unclosed("BX", offset);
+        }
+
+        violations.sort_by_key(|v| v.offset);
+        violations
+    }
+
+    fn close(&mut self, operator: &str, offset: usize, stack: Stack) {
+        let stack = match stack {
+            Stack::Saves => &mut self.saves,
+            Stack::Marked => &mut self.marked,
+            Stack::Compat => &mut self.compat,
+        };
+
+        if stack.pop().is_none() {
+            self.report(operator, offset, ViolationKind::Unbalanced);
+        }
+    }
+
+    fn report(&mut self, operator: &str, offset: usize, kind: ViolationKind) {
+        self.violations
+            .push(Violation { offset, operator: operator.into(), kind });
+    }
+}
+
+enum Stack {
+    Saves,
+    Marked,
+    Compat,
+}
+
+/// All operators from table 51 of the PDF 1.7 specification.
+const KNOWN: &[&str] = &[
+    "b", "B", "b*", "B*", "BDC", "BI", "BMC", "BT", "BX", "c", "cm", "CS", "cs", "d",
+    "d0", "d1", "Do", "DP", "EI", "EMC", "ET", "EX", "f", "F", "f*", "G", "g", "gs", "h",
+    "i", "ID", "j", "J", "K", "k", "l", "m", "M", "MP", "n", "q", "Q", "re", "RG", "rg",
+    "ri", "s", "S", "SC", "sc", "SCN", "scn", "sh", "T*", "Tc", "Td", "TD", "Tf", "Tj",
+    "TJ", "TL", "Tm", "Tr", "Ts", "Tw", "Tz", "v", "w", "W", "W*", "y", "'", "\"",
+];
+
+/// A violation of the content stream structure rules.
+///
+/// Found by a [`Content`](crate::Content) stream with
+/// [validation](crate::Content::enable_validation) enabled.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Violation {
+    offset: usize,
+    operator: String,
+    kind: ViolationKind,
+}
+
+impl Violation {
+    /// The byte offset in the content stream at which the offending operation
+    /// starts.
+    ///
+    /// This includes the whitespace separating the operation from the previous
+    /// one.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The offending operator.
+    ///
+    /// For [unclosed](ViolationKind::Unclosed) sequences, this is the
+    /// operator that opened the sequence.
+    pub fn operator(&self) -> &str {
+        &self.operator
+    }
+
+    /// What rule was violated.
+    pub fn kind(&self) -> ViolationKind {
+        self.kind
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let op = &self.operator;
+        match self.kind {
+            ViolationKind::NotAllowed(object) => {
+                write!(f, "`{op}` is not allowed {object}")?
+            }
+            ViolationKind::Unbalanced => write!(f, "`{op}` has no matching start")?,
+            ViolationKind::Unclosed => write!(f, "`{op}` is never closed")?,
+            ViolationKind::NotFirst => write!(f, "`{op}` must be the first operator")?,
+        }
+        write!(f, " (at offset {})", self.offset)
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// The kind of a [`Violation`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum ViolationKind {
+    /// The operator is not allowed in the current graphics object.
+    ///
+    /// For example, path construction operators are not allowed in text
+    /// objects and text showing operators are only allowed in them.
+    NotAllowed(GraphicsObject),
+    /// A `Q`, `ET`, `EMC` or `EX` operator without a matching `q`, `BT`,
+    /// `BMC`/`BDC` or `BX`.
+    Unbalanced,
+    /// A `q`, `BT`, `BMC`/`BDC` or `BX` operator without a matching `Q`, `ET`,
+    /// `EMC` or `EX` or a path that was never painted.
+    Unclosed,
+    /// A Type 3 glyph operator (`d0` or `d1`) that is not the first operator
+    /// of the content stream.
+    NotFirst,
+}
+
+/// The graphics object that a content stream is in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum GraphicsObject {
+    /// Outside of any graphics object.
+    PageDescription,
+    /// A path is being constructed.
+    Path,
+    /// A path with a clipping operator that is yet to be painted.
+    ClippingPath,
+    /// Inside of a text object (between `BT` and `ET`).
+    Text,
+}
+
+impl Display for GraphicsObject {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad(match self {
+            Self::PageDescription => "outside of a graphics object",
+            Self::Path => "during path construction",
+            Self::ClippingPath => "after a clipping operator",
+            Self::Text => "in a text object",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{GraphicsObject, ViolationKind};
+    use crate::{Content, Name, Str};
+
+    fn violations(f: impl FnOnce(&mut Content)) -> Vec<(usize, String, ViolationKind)> {
+        let mut content = Content::new();
+        content.enable_validation();
+        f(&mut content);
+        match content.validate() {
+            Ok(()) => vec![],
+            Err(violations) => violations
+                .into_iter()
+                .map(|v| (v.offset(), v.operator().to_string(), v.kind()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_validation_ok() {
+        let found = violations(|c| {
+            c.save_state().rect(0.0, 0.0, 1.0, 1.0).clip_nonzero().end_path();
+            c.begin_marked_content(Name(b"P"));
+            c.begin_text().set_font(Name(b"F1"), 12.0).show(Str(b"Hi")).end_text();
+            c.end_marked_content().restore_state();
+            c.begin_compat().op("foo");
+            c.end_compat().circle(0.0, 0.0, 1.0).fill_nonzero();
+        });
+        assert_eq!(found, []);
+
+        let found = violations(|c| {
+            c.start_shape_glyph(500.0, 0.0, 0.0, 500.0, 700.0)
+                .rect(0.0, 0.0, 1.0, 1.0);
+            c.fill_nonzero();
+        });
+        assert_eq!(found, []);
+    }
+
+    #[test]
+    fn test_validation_violations() {
+        use ViolationKind::*;
+
+        let found = violations(|c| {
+            c.restore_state().show(Str(b"A")).begin_text().move_to(1.0, 2.0);
+            c.save_state().begin_marked_content(Name(b"Span"));
+            c.end_text().line_to(1.0, 1.0).start_color_glyph(100.0).begin_compat();
+        });
+        assert_eq!(
+            found,
+            [
+                (0, "Q".into(), Unbalanced),
+                (1, "Tj".into(), NotAllowed(GraphicsObject::PageDescription)),
+                (11, "m".into(), NotAllowed(GraphicsObject::Text)),
+                (17, "q".into(), NotAllowed(GraphicsObject::Text)),
+                (17, "q".into(), Unclosed),
+                (19, "BMC".into(), Unclosed),
+                (32, "l".into(), NotAllowed(GraphicsObject::PageDescription)),
+                (38, "d0".into(), NotFirst),
+                (47, "BX".into(), Unclosed),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validation_on_finish() {
+        let mut content = Content::new();
+        content.enable_validation().stroke();
+        let violations = content.finish_checked().unwrap_err();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].to_string(),
+            "`S` is not allowed outside of a graphics object (at offset 0)"
+        );
+
+        let mut content = Content::new();
+        content.enable_validation().rect(0.0, 0.0, 1.0, 1.0).stroke();
+        assert_eq!(content.finish_checked().unwrap().as_slice(), b"0 0 1 1 re\nS");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "malformed content stream: `BT` is never closed (at offset 0)"
+    )]
+    fn test_validation_panics_on_finish() {
+        let mut content = Content::new();
+        content.enable_validation().begin_text();
+        content.finish();
+    }
+}
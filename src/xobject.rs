@@ -214,8 +214,8 @@ impl<'a> FormXObject<'a> {
     }
 
     /// Write the `/Matrix` attribute to map form space to user space.
-    pub fn matrix(&mut self, matrix: [f32; 6]) -> &mut Self {
-        self.insert(Name(b"Matrix")).array().items(matrix);
+    pub fn matrix(&mut self, matrix: impl Into<[f32; 6]>) -> &mut Self {
+        self.insert(Name(b"Matrix")).array().items(matrix.into());
         self
     }
 